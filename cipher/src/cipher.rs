@@ -1,20 +1,25 @@
+mod backup;
+
+pub use backup::BACKUP_VERSION;
 use {
     crate::CipherError,
     chacha20poly1305::{aead::Aead, AeadCore, ChaCha20Poly1305, KeyInit, Nonce},
     dashmap::DashMap,
     derive_more::{AsMut, AsRef},
+    flate2::{read::GzDecoder, write::GzEncoder, Compression},
     hkdf::Hkdf,
+    lru::LruCache,
     monedero_domain::{Pairing, SessionSettled},
-    monedero_relay::{
-        ed25519_dalek::{SecretKey, VerifyingKey},
-        DecodedTopic,
-        Topic,
-    },
+    monedero_relay::{ed25519_dalek::SecretKey, DecodedTopic, Topic},
     monedero_store::KvStorage,
     serde::{de::DeserializeOwned, Deserialize, Serialize},
     sha2::{Digest, Sha256},
     std::{
+        borrow::Cow,
+        collections::HashSet,
         fmt::{Debug, Formatter},
+        io::{Read, Write},
+        num::NonZeroUsize,
         sync::Arc,
     },
     tracing::debug,
@@ -22,10 +27,59 @@ use {
 };
 
 pub const MULTICODEC_ED25519_LENGTH: usize = 32;
+/// Largest serialized payload the relay will accept
+pub const MAX_PAYLOAD_BYTES: usize = 512 * 1024;
+
+/// Default limit on session topics derived from a single pairing
+pub const MAX_SESSIONS_PER_PAIRING: usize = 50;
+/// Default nesting limit of decoded JSON, well above any relay message
+pub const MAX_JSON_DEPTH: usize = 64;
+const NONCE_LENGTH: usize = 12;
+/// Poly1305 authentication tag appended to the ciphertext
+const TAG_LENGTH: usize = 16;
+/// Leading bytes of a gzip stream, which no JSON payload starts with
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// Type byte followed by the sender's public key
+const TYPE1_ENVELOPE_LENGTH: usize = 1 + MULTICODEC_ED25519_LENGTH;
 const CRYPTO_STORAGE_PREFIX_KEY: &str = "crypto";
+/// Round-tripped through each session key by [`Cipher::self_test`]
+const SELF_TEST_PLAINTEXT: &str = "monedero-self-test";
 
 pub type AtomicPairing = Arc<DashMap<Topic, Arc<Pairing>>>;
 type CipherSessionKeyStore = Arc<DashMap<Topic, ChaCha20Poly1305>>;
+type EnvelopeStore = Arc<DashMap<Topic, Type>>;
+/// Session topics by the pairing topic they were derived on
+type PairingSessionStore = Arc<DashMap<Topic, HashSet<Topic>>>;
+
+/// Remembers the most recent nonces seen per topic to reject replayed frames
+#[derive(Clone)]
+struct NonceGuard {
+    capacity: NonZeroUsize,
+    seen: Arc<DashMap<Topic, LruCache<[u8; NONCE_LENGTH], ()>>>,
+}
+
+impl NonceGuard {
+    fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            seen: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Record the nonce, failing if it was already seen for this topic
+    fn check(&self, topic: &Topic, nonce: [u8; NONCE_LENGTH]) -> Result<(), CipherError> {
+        let replayed = self
+            .seen
+            .entry(topic.clone())
+            .or_insert_with(|| LruCache::new(self.capacity))
+            .put(nonce, ())
+            .is_some();
+        if replayed {
+            return Err(CipherError::NonceReuse(topic.clone()));
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Default, Serialize, PartialEq, Eq, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -33,11 +87,12 @@ struct SessionSettleRequest {
     pub expiry: i64,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+/// Envelope of an encoded payload, Type1 carries the sender's public key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum Type {
     #[default]
     Type0,
-    Type1(VerifyingKey),
+    Type1(PublicKey),
 }
 
 impl Type {
@@ -53,10 +108,13 @@ impl Type {
     }
 
     fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        match bytes[0] {
+        match bytes.first()? {
             0u8 => Some(Self::Type0),
-            1u8 => VerifyingKey::from_bytes((&bytes[1..32]).try_into().unwrap())
-                .map_or(None, |key| Some(Self::Type1(key))),
+            1u8 => {
+                let key: [u8; MULTICODEC_ED25519_LENGTH] =
+                    bytes.get(1..TYPE1_ENVELOPE_LENGTH)?.try_into().ok()?;
+                Some(Self::Type1(PublicKey::from(key)))
+            }
             _ => None,
         }
     }
@@ -83,8 +141,16 @@ impl std::fmt::Display for DecodedSymKey {
 #[derive(Clone)]
 pub struct Cipher {
     ciphers: CipherSessionKeyStore,
+    envelopes: EnvelopeStore,
     pairing: AtomicPairing,
+    pairing_sessions: PairingSessionStore,
     storage: Arc<KvStorage>,
+    max_payload_bytes: usize,
+    max_json_depth: usize,
+    compress_threshold: Option<usize>,
+    max_sessions: usize,
+    nonce_guard: Option<NonceGuard>,
+    canonical_json: bool,
 }
 
 impl Debug for Cipher {
@@ -114,6 +180,10 @@ impl Cipher {
     fn storage_settlement(topic: &Topic) -> String {
         format!("{CRYPTO_STORAGE_PREFIX_KEY}-settlement-{topic}")
     }
+
+    fn storage_proposer_key() -> String {
+        format!("{CRYPTO_STORAGE_PREFIX_KEY}-proposer-key")
+    }
 }
 
 impl Cipher {
@@ -130,19 +200,76 @@ impl Cipher {
         }
         let cipher = Self {
             ciphers: Arc::new(DashMap::new()),
+            envelopes: Arc::new(DashMap::new()),
             pairing: Arc::new(pairings),
+            pairing_sessions: Arc::new(DashMap::new()),
             storage,
+            max_payload_bytes: MAX_PAYLOAD_BYTES,
+            max_json_depth: MAX_JSON_DEPTH,
+            compress_threshold: None,
+            max_sessions: MAX_SESSIONS_PER_PAIRING,
+            nonce_guard: None,
+            canonical_json: false,
         };
         cipher.init()?;
         Ok(cipher)
     }
 
+    /// Override the serialized payload limit, see [`MAX_PAYLOAD_BYTES`].
+    /// Applies to decoded payloads too.
+    #[must_use]
+    pub const fn with_max_payload_bytes(mut self, max: usize) -> Self {
+        self.max_payload_bytes = max;
+        self
+    }
+
+    /// Override the nesting limit of decoded payloads, see [`MAX_JSON_DEPTH`]
+    #[must_use]
+    pub const fn with_max_json_depth(mut self, max: usize) -> Self {
+        self.max_json_depth = max;
+        self
+    }
+
+    /// Gzip serialized payloads longer than `threshold` bytes before
+    /// encrypting them. Compressed payloads are always inflated on decode,
+    /// but peers not using this crate can't read them, so only enable it
+    /// when both sides do.
+    #[must_use]
+    pub const fn with_compress_threshold(mut self, threshold: usize) -> Self {
+        self.compress_threshold = Some(threshold);
+        self
+    }
+
+    /// Override the number of session topics each pairing may spawn, see
+    /// [`MAX_SESSIONS_PER_PAIRING`]
+    #[must_use]
+    pub const fn with_max_sessions(mut self, max: usize) -> Self {
+        self.max_sessions = max;
+        self
+    }
+
+    /// Reject decoding frames whose nonce was already seen on the same topic.
+    /// Up to `capacity` nonces are remembered per topic.
+    #[must_use]
+    pub fn with_nonce_guard(mut self, capacity: NonZeroUsize) -> Self {
+        self.nonce_guard = Some(NonceGuard::new(capacity));
+        self
+    }
+
+    /// Serialize payloads with object keys sorted, so the encrypted bytes
+    /// don't depend on field or insertion order. Decoding is unaffected.
+    #[must_use]
+    pub const fn with_canonical_json(mut self, enabled: bool) -> Self {
+        self.canonical_json = enabled;
+        self
+    }
+
     fn init(&self) -> Result<(), CipherError> {
         let mut session_expired = false;
         let pairing = self.pairing();
         if pairing.is_none() {
             debug!("clearing session storage");
-            self.storage.clear();
+            self.clear_storage();
             return Ok(());
         }
         let pairing = pairing.unwrap();
@@ -150,10 +277,8 @@ impl Cipher {
         self.pairing
             .insert(pairing.topic.clone(), Arc::new(pairing.clone()));
         let key = pairing.params.sym_key.clone();
-        self.ciphers.insert(
-            pairing.topic,
-            ChaCha20Poly1305::new((&key.to_bytes()).into()),
-        );
+        self.register_pairing(&pairing.topic, &key);
+        let keys = self.session_keys()?;
         let sessions_key = format!("{CRYPTO_STORAGE_PREFIX_KEY}-sessions");
         if let Some(sessions) = self.storage.get::<Vec<String>>(&sessions_key)? {
             debug!("restoring {} sessions", sessions.len());
@@ -167,17 +292,23 @@ impl Cipher {
                     session_expired = true;
                     break;
                 }
+                let topic = Topic::from(s);
                 if let Some(controller_pk) = self
                     .storage
-                    .get::<String>(Self::storage_session_key(&Topic::from(s)))?
+                    .get::<String>(Self::storage_session_key(&topic))?
                 {
-                    let (topic, expanded_key) = Self::derive_sym_key(&key, &controller_pk)?;
-                    self.register(&topic, &expanded_key);
+                    match Self::derive_session_key(&keys, &topic, &controller_pk) {
+                        Ok(expanded_key) => {
+                            self.track_session(&pairing.topic, &topic);
+                            self.register(&topic, &expanded_key);
+                        }
+                        Err(e) => tracing::warn!("cannot restore session key: {e}"),
+                    }
                 }
             }
             if session_expired {
                 tracing::info!("Session has expired, resetting storage");
-                self.storage.clear();
+                self.clear_storage();
             }
         }
         Ok(())
@@ -203,17 +334,19 @@ impl Cipher {
             .unwrap_or_default();
         let mut settled: Vec<SessionSettled> = Vec::new();
         for topic in sessions {
-            if let Some(s) = self
-                .storage
-                .get::<SessionSettled>(Self::storage_settlement(&topic))?
-            {
+            if let Some(s) = self.settlement(&topic)? {
                 settled.push(s);
             }
         }
         Ok(settled)
     }
 
-    pub(crate) fn is_expired(&self, topic: Topic) -> Result<bool, CipherError> {
+    /// Stored settlement of the session on `topic`, `None` if there is none
+    pub fn settlement(&self, topic: &Topic) -> Result<Option<SessionSettled>, CipherError> {
+        Ok(self.storage.get(Self::storage_settlement(topic))?)
+    }
+
+    pub fn is_expired(&self, topic: Topic) -> Result<bool, CipherError> {
         let sessions_key = format!("{CRYPTO_STORAGE_PREFIX_KEY}-settlement-{topic}");
         let session: SessionSettleRequest = self
             .storage
@@ -228,12 +361,47 @@ impl Cipher {
     pub fn delete_session(&self, topic: &Topic) -> Result<(), CipherError> {
         self.storage.delete(Self::storage_session_key(topic))?;
         if let Some(sessions) = self.storage.get::<Vec<Topic>>(Self::storage_sessions())? {
-            let new_sessions: Vec<Topic> = sessions.into_iter().filter(|t| t == topic).collect();
+            let new_sessions: Vec<Topic> = sessions.into_iter().filter(|t| t != topic).collect();
             self.storage.set(Self::storage_sessions(), new_sessions)?;
         }
         let sessions_key = Self::storage_settlement(topic);
         self.storage.delete(sessions_key)?;
         self.ciphers.remove(topic);
+        self.envelopes.remove(topic);
+        for mut sessions in self.pairing_sessions.iter_mut() {
+            sessions.remove(topic);
+        }
+        if let Some(guard) = &self.nonce_guard {
+            guard.seen.remove(topic);
+        }
+        Ok(())
+    }
+
+    /// Clears the storage, keeping the proposer key as it outlives pairings
+    fn clear_storage(&self) {
+        let proposer_key = self.proposer_key().ok().flatten();
+        self.storage.clear();
+        if let Some(key) = proposer_key {
+            if let Err(e) = self.set_proposer_key(&key) {
+                tracing::warn!("failed to keep the proposer key: {e}");
+            }
+        }
+    }
+
+    /// Proposer key stored with [`Self::set_proposer_key`], it survives
+    /// pairing resets
+    pub fn proposer_key(&self) -> Result<Option<StaticSecret>, CipherError> {
+        Ok(self
+            .storage
+            .get::<DecodedSymKey>(Self::storage_proposer_key())?
+            .map(|key| StaticSecret::from(key.0)))
+    }
+
+    pub fn set_proposer_key(&self, key: &StaticSecret) -> Result<(), CipherError> {
+        self.storage.set(
+            Self::storage_proposer_key(),
+            DecodedSymKey::from_key(&key.to_bytes()),
+        )?;
         Ok(())
     }
 
@@ -246,11 +414,26 @@ impl Cipher {
             self.pairing
                 .insert(new_pair.topic.clone(), Arc::new(new_pair.clone()));
             let key = new_pair.params.sym_key.clone();
-            self.ciphers.insert(
-                new_pair.topic,
-                ChaCha20Poly1305::new((&key.to_bytes()).into()),
-            );
+            self.register_pairing(&new_pair.topic, &key);
+        }
+        Ok(())
+    }
+
+    /// Register `pairing` next to the current one, so its topic is decoded
+    /// and part of [`Self::subscriptions`]. It becomes the current pairing
+    /// when there is none. Unlike [`Self::set_pairing`] nothing is reset, and
+    /// additional pairings are not persisted.
+    pub fn add_pairing(&self, pairing: Pairing) -> Result<(), CipherError> {
+        if self.pairing().is_none() {
+            return self.set_pairing(Some(pairing));
+        }
+        if self.pairing.contains_key(&pairing.topic) {
+            return Ok(());
         }
+        debug!("adding pairing topic {}", pairing.topic);
+        self.register_pairing(&pairing.topic, &pairing.params.sym_key);
+        self.pairing
+            .insert(pairing.topic.clone(), Arc::new(pairing));
         Ok(())
     }
 
@@ -279,6 +462,12 @@ impl Cipher {
         None
     }
 
+    /// The pairing on `topic`, the current pairing or one from
+    /// [`Self::add_pairing`]
+    pub fn pairing_by_topic(&self, topic: &Topic) -> Option<Pairing> {
+        self.pairing.get(topic).map(|pairing| (**pairing).clone())
+    }
+
     pub fn pairing(&self) -> Option<Pairing> {
         self.storage
             .get(Self::storage_pairing())
@@ -286,18 +475,46 @@ impl Cipher {
             .unwrap_or_else(|| None)
     }
 
+    /// Derive the session with `controller_pk` from the key of the pairing on
+    /// `pairing_topic`, the current pairing or one from [`Self::add_pairing`]
     pub fn create_common_topic(
         &self,
+        pairing_topic: &Topic,
+        controller_pk: String,
+    ) -> Result<(Topic, PublicKey), CipherError> {
+        let pairing = self
+            .pairing_by_topic(pairing_topic)
+            .ok_or(CipherError::NonExistingPairing)?;
+        self.create_common_topic_with(pairing_topic, &pairing.params.sym_key, controller_pk)
+    }
+
+    /// Like [`Self::create_common_topic`], deriving the session key from `key`
+    /// instead of the pairing key, e.g. a dapp's [`Self::proposer_key`]. The
+    /// session still counts against the limit of the pairing on
+    /// `pairing_topic`.
+    pub fn create_common_topic_with(
+        &self,
+        pairing_topic: &Topic,
+        key: &StaticSecret,
         controller_pk: String,
     ) -> Result<(Topic, PublicKey), CipherError> {
-        let pairing_key = self.pairing_key().ok_or(CipherError::NonExistingPairing)?;
-        let (new_topic, expanded_key) = Self::derive_sym_key(&pairing_key, &controller_pk)?;
-        self.update_sessions(controller_pk, &new_topic)?;
+        let (new_topic, expanded_key) = Self::derive_sym_key(key, &controller_pk)?;
+        if !self.ciphers.contains_key(&new_topic)
+            && self.pairing_session_count(pairing_topic) >= self.max_sessions
+        {
+            return Err(CipherError::SessionLimitReached(self.max_sessions));
+        }
+        self.update_sessions(pairing_topic, controller_pk, &new_topic)?;
         self.register(&new_topic, &expanded_key);
         Ok((new_topic, PublicKey::from(&expanded_key)))
     }
 
-    fn update_sessions(&self, controller_pk: String, topic: &Topic) -> Result<(), CipherError> {
+    fn update_sessions(
+        &self,
+        pairing_topic: &Topic,
+        controller_pk: String,
+        topic: &Topic,
+    ) -> Result<(), CipherError> {
         // TODO: May need to lock this entire operation
         let sessions_storage_key = Self::storage_sessions();
         let sessions: Vec<Topic> = vec![topic.clone()];
@@ -307,9 +524,17 @@ impl Cipher {
         self.storage.set(&sessions_storage_key, sessions)?;
         self.storage
             .set(Self::storage_session_key(topic), controller_pk)?;
+        self.track_session(pairing_topic, topic);
         Ok(())
     }
 
+    fn track_session(&self, pairing_topic: &Topic, topic: &Topic) {
+        self.pairing_sessions
+            .entry(pairing_topic.clone())
+            .or_default()
+            .insert(topic.clone());
+    }
+
     #[allow(clippy::missing_panics_doc)]
     pub fn derive_sym_key(
         static_key: &StaticSecret,
@@ -332,11 +557,58 @@ impl Cipher {
         Ok((new_topic, expanded_key))
     }
 
+    /// Key of the session on `topic`, derived from whichever of `keys` the
+    /// session was created with
+    pub(crate) fn derive_session_key(
+        keys: &[StaticSecret],
+        topic: &Topic,
+        controller_pk: &str,
+    ) -> Result<StaticSecret, CipherError> {
+        for key in keys {
+            let (derived, expanded_key) = Self::derive_sym_key(key, controller_pk)?;
+            if &derived == topic {
+                return Ok(expanded_key);
+            }
+        }
+        Err(CipherError::UnknownSessionTopic(topic.clone()))
+    }
+
+    /// Keys a session may be derived from: those of the current pairing and
+    /// the ones from [`Self::add_pairing`], then the proposer key
+    fn session_keys(&self) -> Result<Vec<StaticSecret>, CipherError> {
+        Ok(self
+            .pairing
+            .iter()
+            .map(|pairing| pairing.params.sym_key.clone())
+            .chain(self.proposer_key()?)
+            .collect())
+    }
+
+    /// Session topics are encoded with [`Type::Type0`]
     fn register(&self, topic: &Topic, key: &StaticSecret) {
         self.ciphers.insert(
             topic.clone(),
             ChaCha20Poly1305::new((&key.to_bytes()).into()),
         );
+        self.envelopes.insert(topic.clone(), Type::Type0);
+    }
+
+    /// The pairing topic is encoded with [`Type::Type1`], conveying our public
+    /// key
+    fn register_pairing(&self, topic: &Topic, key: &StaticSecret) {
+        self.register(topic, key);
+        self.envelopes
+            .insert(topic.clone(), Type::Type1(PublicKey::from(key)));
+    }
+
+    /// Envelope [`Self::encode`] uses for `topic`
+    pub fn envelope(&self, topic: &Topic) -> Type {
+        self.envelopes.get(topic).map(|t| *t).unwrap_or_default()
+    }
+
+    /// Override the envelope [`Self::encode`] uses for `topic`
+    pub fn set_envelope(&self, topic: &Topic, envelope: Type) {
+        self.envelopes.insert(topic.clone(), envelope);
     }
 
     pub fn encode<T: Serialize>(&self, topic: &Topic, payload: &T) -> Result<String, CipherError> {
@@ -344,7 +616,7 @@ impl Cipher {
             topic,
             payload,
             ChaCha20Poly1305::generate_nonce(&mut rand::thread_rng()),
-            Type::default(),
+            self.envelope(topic),
         )
     }
 
@@ -360,10 +632,25 @@ impl Cipher {
             .ciphers
             .get(topic)
             .ok_or(CipherError::UnknownTopic(topic.clone()))?;
-        let serialized_payload = serde_json::to_string(payload)?;
-        debug!("serialized payload for topic {topic} {serialized_payload}");
+        let serialized_payload = self.serialize(payload)?;
+        if serialized_payload.len() > self.max_payload_bytes {
+            return Err(CipherError::PayloadTooLarge {
+                size: serialized_payload.len(),
+                max: self.max_payload_bytes,
+            });
+        }
+        debug!(
+            "serialized payload for topic {topic} {}",
+            loggable(&serialized_payload)
+        );
+        let plaintext = match self.compress_threshold {
+            Some(threshold) if serialized_payload.len() > threshold => {
+                gzip(serialized_payload.as_bytes())?
+            }
+            _ => serialized_payload.into_bytes(),
+        };
         let encrypted_payload = cipher
-            .encrypt(&nonce, &*serialized_payload.into_bytes())
+            .encrypt(&nonce, &*plaintext)
             .map_err(|_| CipherError::Corrupted)?;
         let mut envelope = envelope_type.as_bytes();
         envelope.extend(nonce.to_vec());
@@ -371,12 +658,25 @@ impl Cipher {
         Ok(data_encoding::BASE64.encode(&envelope))
     }
 
+    fn serialize<T: Serialize>(&self, payload: &T) -> Result<String, CipherError> {
+        if self.canonical_json {
+            Ok(serde_json::to_string(&canonical(serde_json::to_value(
+                payload,
+            )?))?)
+        } else {
+            Ok(serde_json::to_string(payload)?)
+        }
+    }
+
     pub fn decode<T: DeserializeOwned>(
         &self,
         topic: &Topic,
         payload: &str,
     ) -> Result<T, CipherError> {
         let decoded_msg = &self.decode_to_string(topic, payload)?;
+        if exceeds_depth(decoded_msg, self.max_json_depth) {
+            return Err(CipherError::JsonTooDeep(self.max_json_depth));
+        }
         let from_str = serde_json::from_str(decoded_msg);
         Ok(from_str?)
     }
@@ -387,11 +687,12 @@ impl Cipher {
         payload: &str,
     ) -> Result<String, CipherError> {
         let encrypted_payload = data_encoding::BASE64.decode(payload.as_bytes())?;
-        match Type::from_bytes(&encrypted_payload) {
-            Some(Type::Type0) => self.decode_bytes(topic, &encrypted_payload[1..]),
-            Some(Type::Type1(_)) => self.decode_bytes(topic, &encrypted_payload[33..]),
-            _ => Err(CipherError::CorruptedPayload),
-        }
+        let sealed = match Type::from_bytes(&encrypted_payload) {
+            Some(Type::Type0) => encrypted_payload.get(1..),
+            Some(Type::Type1(_)) => encrypted_payload.get(TYPE1_ENVELOPE_LENGTH..),
+            None => None,
+        };
+        self.decode_bytes(topic, sealed.ok_or(CipherError::CorruptedPayload)?)
     }
 
     // TODO review this allow
@@ -401,27 +702,211 @@ impl Cipher {
             .ciphers
             .get(topic)
             .ok_or(CipherError::UnknownTopic(topic.clone()))?;
+        let (nonce, payload) = bytes
+            .split_first_chunk::<NONCE_LENGTH>()
+            .ok_or(CipherError::CorruptedPayload)?;
+        let size = payload.len().saturating_sub(TAG_LENGTH);
+        if size > self.max_payload_bytes {
+            return Err(CipherError::PayloadTooLarge {
+                size,
+                max: self.max_payload_bytes,
+            });
+        }
         let decoded_bytes = cipher
-            .decrypt((&bytes[0..12]).into(), &bytes[12..])
+            .decrypt(nonce.into(), payload)
             .map_err(|_| CipherError::EncryptionError)?;
+        if let Some(guard) = &self.nonce_guard {
+            guard.check(topic, *nonce)?;
+        }
+        let decoded_bytes = if decoded_bytes.starts_with(&GZIP_MAGIC) {
+            gunzip(&decoded_bytes, self.max_payload_bytes)?
+        } else {
+            decoded_bytes
+        };
         let decoded = String::from_utf8(decoded_bytes)?;
-        debug!("decoded from topic {topic} {decoded}");
+        debug!("decoded from topic {topic} {}", loggable(&decoded));
         Ok(decoded)
     }
 
+    /// Check every stored session still has a usable key: the key derived
+    /// from the stored peer public key must match the session topic and
+    /// round-trip a known plaintext. Sessions failing the check are deleted
+    /// instead of surfacing on the first real message.
+    ///
+    /// Returns the pruned session topics.
+    pub fn self_test(&self) -> Result<Vec<Topic>, CipherError> {
+        if self.pairing_key().is_none() {
+            return Ok(Vec::new());
+        }
+        let keys = self.session_keys()?;
+        let sessions: Vec<Topic> = self
+            .storage
+            .get(Self::storage_sessions())?
+            .unwrap_or_default();
+        let mut pruned = Vec::new();
+        for topic in sessions {
+            if let Err(e) = self.check_session(&keys, &topic) {
+                tracing::warn!("dropping session {topic}: {e}");
+                self.delete_session(&topic)?;
+                pruned.push(topic);
+            }
+        }
+        Ok(pruned)
+    }
+
+    /// The session key must derive from one of `keys`, see
+    /// [`Self::derive_session_key`]
+    fn check_session(&self, keys: &[StaticSecret], topic: &Topic) -> Result<(), CipherError> {
+        let controller_pk: String = self
+            .storage
+            .get(Self::storage_session_key(topic))?
+            .ok_or_else(|| CipherError::UnknownSessionTopic(topic.clone()))?;
+        Self::derive_session_key(keys, topic, &controller_pk).map_err(|e| match e {
+            CipherError::UnknownSessionTopic(topic) => CipherError::SelfTestFailed(topic),
+            e => e,
+        })?;
+        let encoded = self.encode(topic, &SELF_TEST_PLAINTEXT)?;
+        let decoded: String = self.decode(topic, &encoded)?;
+        if decoded != SELF_TEST_PLAINTEXT {
+            return Err(CipherError::SelfTestFailed(topic.clone()));
+        }
+        Ok(())
+    }
+
     #[allow(dead_code)]
     fn session_topics(&self) -> usize {
         self.ciphers.len()
     }
 
+    /// Topics holding a session key, the pairing topic excluded
+    pub fn session_count(&self) -> usize {
+        self.ciphers
+            .iter()
+            .filter(|k| !self.pairing.contains_key(k.key()))
+            .count()
+    }
+
+    /// Sessions derived on the pairing on `pairing_topic`, the count
+    /// [`Self::with_max_sessions`] limits
+    pub fn pairing_session_count(&self, pairing_topic: &Topic) -> usize {
+        self.pairing_sessions
+            .get(pairing_topic)
+            .map_or(0, |sessions| sessions.len())
+    }
+
     pub fn subscriptions(&self) -> Vec<Topic> {
         self.ciphers.iter().map(|k| k.key().clone()).collect()
     }
 
+    /// Whether `topic` is the topic of the current pairing or one from
+    /// [`Self::add_pairing`]
+    pub fn is_pairing_topic(&self, topic: &Topic) -> bool {
+        self.pairing.contains_key(topic)
+    }
+
+    /// Whether `topic` is one of [`Self::subscriptions`]
+    pub fn is_subscribed(&self, topic: &Topic) -> bool {
+        self.ciphers.contains_key(topic)
+    }
+
     pub fn reset(&self) {
         self.ciphers.clear();
+        self.envelopes.clear();
+        if let Some(guard) = &self.nonce_guard {
+            guard.seen.clear();
+        }
         self.pairing.clear();
-        self.storage.clear();
+        self.pairing_sessions.clear();
+        self.clear_storage();
+    }
+}
+
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>, CipherError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    Ok(encoder.finish()?)
+}
+
+/// Inflate at most `max` bytes, so a small payload can't expand without bound
+fn gunzip(bytes: &[u8], max: usize) -> Result<Vec<u8>, CipherError> {
+    let mut inflated = Vec::new();
+    GzDecoder::new(bytes)
+        .take(max as u64 + 1)
+        .read_to_end(&mut inflated)?;
+    if inflated.len() > max {
+        return Err(CipherError::PayloadTooLarge {
+            size: inflated.len(),
+            max,
+        });
+    }
+    Ok(inflated)
+}
+
+/// What payload log lines show of `payload`: its length and a short hash
+/// with the `redact-logs` feature, unless `wire-trace` asks for everything
+#[cfg(all(feature = "redact-logs", not(feature = "wire-trace")))]
+fn loggable(payload: &str) -> Cow<'_, str> {
+    let hash = Sha256::digest(payload.as_bytes());
+    Cow::Owned(format!(
+        "<redacted {} bytes sha256:{}>",
+        payload.len(),
+        data_encoding::HEXLOWER.encode(&hash[..8])
+    ))
+}
+
+#[cfg(not(all(feature = "redact-logs", not(feature = "wire-trace"))))]
+const fn loggable(payload: &str) -> Cow<'_, str> {
+    Cow::Borrowed(payload)
+}
+
+/// Whether arrays and objects in `json` nest deeper than `max`, checked
+/// before handing untrusted input to the recursive parser
+fn exceeds_depth(json: &str, max: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for b in json.bytes() {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                if depth > max {
+                    return true;
+                }
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// `value` with the keys of every object sorted
+fn canonical(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, canonical(v)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(canonical).collect())
+        }
+        other => other,
     }
 }
 
@@ -452,9 +937,10 @@ mod tests {
     // );
     //
     // let (dapp_topic, _) =
-    // dapp.create_common_topic(wallet.public_key_hex().unwrap())?;
-    // let (session_topic, _) =
-    // wallet.create_common_topic(dapp.public_key_hex().unwrap())?;
+    // dapp.create_common_topic(&dapp.pairing().unwrap().topic,
+    // wallet.public_key_hex().unwrap())?; let (session_topic, _) =
+    // wallet.create_common_topic(&wallet.pairing().unwrap().topic,
+    // dapp.public_key_hex().unwrap())?;
     //
     // assert_eq!(dapp_topic, session_topic);
     // assert_eq!(wallet.public_key().unwrap(), wallet_pk);
@@ -490,6 +976,401 @@ mod tests {
         Pairing::default()
     }
 
+    #[test]
+    pub fn test_payload_too_large() -> anyhow::Result<()> {
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let oversized = "x".repeat(MAX_PAYLOAD_BYTES);
+        let result = ciphers.encode(&pairing.topic, &oversized);
+        assert!(matches!(
+            result,
+            Err(CipherError::PayloadTooLarge {
+                max: MAX_PAYLOAD_BYTES,
+                ..
+            })
+        ));
+
+        let ciphers = ciphers.with_max_payload_bytes(16);
+        assert!(ciphers.encode(&pairing.topic, &"small").is_ok());
+        let result = ciphers.encode(&pairing.topic, &"this is longer than sixteen bytes");
+        assert!(matches!(
+            result,
+            Err(CipherError::PayloadTooLarge { size: 35, max: 16 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_decode_limits() -> anyhow::Result<()> {
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+
+        let mut nested = serde_json::json!("[{\\\"}]");
+        for _ in 0..MAX_JSON_DEPTH {
+            nested = serde_json::json!([nested]);
+        }
+        let encoded = ciphers.encode(&pairing.topic, &nested)?;
+        assert_eq!(
+            nested,
+            ciphers.decode::<serde_json::Value>(&pairing.topic, &encoded)?
+        );
+        let encoded = ciphers.encode(&pairing.topic, &serde_json::json!({"a": nested}))?;
+        let result = ciphers.decode::<serde_json::Value>(&pairing.topic, &encoded);
+        assert!(matches!(
+            result,
+            Err(CipherError::JsonTooDeep(MAX_JSON_DEPTH))
+        ));
+
+        let encoded = ciphers.encode(&pairing.topic, &"this is longer than sixteen bytes")?;
+        let ciphers = ciphers.with_max_payload_bytes(16);
+        let result = ciphers.decode::<String>(&pairing.topic, &encoded);
+        assert!(matches!(
+            result,
+            Err(CipherError::PayloadTooLarge { size: 35, max: 16 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_compression() -> anyhow::Result<()> {
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let large = "transaction ".repeat(1000);
+        let plain = ciphers.encode(&pairing.topic, &large)?;
+
+        let ciphers = ciphers.with_compress_threshold(1024);
+        let compressed = ciphers.encode(&pairing.topic, &large)?;
+        assert!(compressed.len() < plain.len() / 10);
+        assert_eq!(
+            large,
+            ciphers.decode::<String>(&pairing.topic, &compressed)?
+        );
+        // below the threshold payloads are left alone
+        let small = ciphers.encode(&pairing.topic, &"small")?;
+        assert_eq!(
+            "\"small\"",
+            ciphers.decode_to_string(&pairing.topic, &small)?
+        );
+
+        // inflating is bounded like any other payload
+        let ciphers = ciphers.with_max_payload_bytes(1024);
+        let result = ciphers.decode::<String>(&pairing.topic, &compressed);
+        assert!(matches!(
+            result,
+            Err(CipherError::PayloadTooLarge { max: 1024, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "redact-logs", not(feature = "wire-trace")))]
+    pub fn test_redacted_logs() -> anyhow::Result<()> {
+        let logs = monedero_relay::CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish();
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let secret = "transfer 10 SOL, memo: secret";
+        tracing::subscriber::with_default(subscriber, || -> anyhow::Result<()> {
+            let encoded = ciphers.encode(&pairing.topic, &secret)?;
+            assert_eq!(secret, ciphers.decode::<String>(&pairing.topic, &encoded)?);
+            Ok(())
+        })?;
+
+        let logs = logs.contents();
+        assert!(logs.contains("serialized payload for topic"), "{logs}");
+        assert!(logs.contains("decoded from topic"), "{logs}");
+        assert_eq!(
+            2,
+            logs.matches("<redacted 31 bytes sha256:").count(),
+            "{logs}"
+        );
+        assert!(!logs.contains("secret"), "{logs}");
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_canonical_json() -> anyhow::Result<()> {
+        /// Serializes as a map in the given entry order
+        struct Ordered(Vec<(&'static str, serde_json::Value)>);
+        impl Serialize for Ordered {
+            fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                s.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+            }
+        }
+        let first = Ordered(vec![
+            ("method", serde_json::json!("wc_sessionRequest")),
+            ("id", serde_json::json!(1)),
+            (
+                "params",
+                serde_json::json!({"z": 1, "a": [{"y": 2, "b": 3}]}),
+            ),
+        ]);
+        let second = Ordered(vec![
+            (
+                "params",
+                serde_json::json!({"a": [{"b": 3, "y": 2}], "z": 1}),
+            ),
+            ("id", serde_json::json!(1)),
+            ("method", serde_json::json!("wc_sessionRequest")),
+        ]);
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let nonce = Nonce::default();
+        let envelope = ciphers.envelope(&pairing.topic);
+        let encode = |ciphers: &Cipher, payload: &Ordered| {
+            ciphers.encode_with_params(&pairing.topic, payload, nonce, envelope)
+        };
+        assert_ne!(encode(&ciphers, &first)?, encode(&ciphers, &second)?);
+
+        let ciphers = ciphers.with_canonical_json(true);
+        let encoded = encode(&ciphers, &first)?;
+        assert_eq!(encoded, encode(&ciphers, &second)?);
+        assert_eq!(
+            r#"{"id":1,"method":"wc_sessionRequest","params":{"a":[{"b":3,"y":2}],"z":1}}"#,
+            ciphers.decode_to_string(&pairing.topic, &encoded)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_decode_truncated() -> anyhow::Result<()> {
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let truncated: [&[u8]; 4] = [&[], &[1u8], &[1u8; 20], &[0u8, 1, 2, 3]];
+        for bytes in truncated {
+            let payload = data_encoding::BASE64.encode(bytes);
+            let result = ciphers.decode_to_string(&pairing.topic, &payload);
+            assert!(
+                matches!(result, Err(CipherError::CorruptedPayload)),
+                "{bytes:?} {result:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_nonce_reuse() -> anyhow::Result<()> {
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let encoded = ciphers.encode(&pairing.topic, &"replay me")?;
+        // guard is off by default
+        let _: String = ciphers.decode(&pairing.topic, &encoded)?;
+        let _: String = ciphers.decode(&pairing.topic, &encoded)?;
+
+        let ciphers = ciphers.with_nonce_guard(NonZeroUsize::new(8).unwrap());
+        let decoded: String = ciphers.decode(&pairing.topic, &encoded)?;
+        assert_eq!("replay me", decoded);
+        let result = ciphers.decode::<String>(&pairing.topic, &encoded);
+        assert!(matches!(result, Err(CipherError::NonceReuse(t)) if t == pairing.topic));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_envelope_per_topic() -> anyhow::Result<()> {
+        let pairing = create_pairing();
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let public_key = ciphers.public_key().unwrap();
+        assert_eq!(Type::Type1(public_key), ciphers.envelope(&pairing.topic));
+
+        let encoded =
+            data_encoding::BASE64.decode(ciphers.encode(&pairing.topic, &"hi")?.as_bytes())?;
+        assert_eq!(1, encoded[0]);
+        assert_eq!(public_key.as_bytes(), &encoded[1..TYPE1_ENVELOPE_LENGTH]);
+        let decoded: String =
+            ciphers.decode(&pairing.topic, &ciphers.encode(&pairing.topic, &"hi")?)?;
+        assert_eq!("hi", decoded);
+
+        let peer = SessionKey::from_osrng(public_key.as_bytes())?;
+        let (session_topic, _) =
+            ciphers.create_common_topic(&pairing.topic, String::from(&peer.public_key()))?;
+        assert_eq!(Type::Type0, ciphers.envelope(&session_topic));
+        let encoded =
+            data_encoding::BASE64.decode(ciphers.encode(&session_topic, &"hi")?.as_bytes())?;
+        assert_eq!(0, encoded[0]);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_session_limit() -> anyhow::Result<()> {
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?.with_max_sessions(2);
+        let pairing = create_pairing();
+        ciphers.set_pairing(Some(pairing.clone()))?;
+        let public_key = ciphers.public_key().unwrap();
+        let peers = (0..3)
+            .map(|_| SessionKey::from_osrng(public_key.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()?;
+        ciphers.create_common_topic(&pairing.topic, String::from(&peers[0].public_key()))?;
+        ciphers.create_common_topic(&pairing.topic, String::from(&peers[1].public_key()))?;
+        assert_eq!(2, ciphers.session_count());
+
+        let result =
+            ciphers.create_common_topic(&pairing.topic, String::from(&peers[2].public_key()));
+        assert!(matches!(result, Err(CipherError::SessionLimitReached(2))));
+        assert_eq!(2, ciphers.session_count());
+
+        // re-deriving an existing session doesn't count against the limit
+        ciphers.create_common_topic(&pairing.topic, String::from(&peers[1].public_key()))?;
+
+        // the limit is per pairing
+        let second = create_pairing();
+        ciphers.add_pairing(second.clone())?;
+        let peer = SessionKey::from_osrng(PublicKey::from(&second.params.sym_key).as_bytes())?;
+        ciphers.create_common_topic(&second.topic, String::from(&peer.public_key()))?;
+        assert_eq!(1, ciphers.pairing_session_count(&second.topic));
+        assert_eq!(3, ciphers.session_count());
+
+        // deleting a session frees its slot
+        let (deleted, _) =
+            ciphers.create_common_topic(&pairing.topic, String::from(&peers[0].public_key()))?;
+        ciphers.delete_session(&deleted)?;
+        assert_eq!(1, ciphers.pairing_session_count(&pairing.topic));
+        ciphers.create_common_topic(&pairing.topic, String::from(&peers[2].public_key()))?;
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_proposer_key_sessions() -> anyhow::Result<()> {
+        let store = Arc::new(KvStorage::mem());
+        let ciphers = Cipher::new(store.clone(), None)?;
+        let proposer = StaticSecret::random_from_rng(rand::thread_rng());
+        ciphers.set_proposer_key(&proposer)?;
+        let pairing = create_pairing();
+        ciphers.set_pairing(Some(pairing.clone()))?;
+
+        // the wallet answers with the public key of its pairing key
+        let hex = |key: &StaticSecret| {
+            data_encoding::HEXLOWER_PERMISSIVE.encode(PublicKey::from(key).as_bytes())
+        };
+        let (topic, _) = ciphers.create_common_topic_with(
+            &pairing.topic,
+            &proposer,
+            hex(&pairing.params.sym_key),
+        )?;
+        let (wallet_topic, _) = Cipher::derive_sym_key(&pairing.params.sym_key, &hex(&proposer))?;
+        assert_eq!(topic, wallet_topic);
+
+        let restored = Cipher::new(store, None)?;
+        assert!(restored.is_subscribed(&topic));
+        assert!(restored.self_test()?.is_empty());
+        assert!(restored.is_subscribed(&topic));
+        let blob = restored.export("correct horse")?;
+        let imported = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        imported.import(&blob, "correct horse")?;
+        assert!(imported.is_subscribed(&topic));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_add_pairing() -> anyhow::Result<()> {
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        let first = create_pairing();
+        let second = create_pairing();
+        ciphers.add_pairing(first.clone())?;
+        ciphers.add_pairing(second.clone())?;
+        ciphers.add_pairing(second.clone())?;
+        assert_eq!(Some(first.topic), ciphers.pairing().map(|p| p.topic));
+        assert_eq!(2, ciphers.subscriptions().len());
+        assert!(ciphers.is_pairing_topic(&second.topic));
+        let encoded = ciphers.encode(&second.topic, &"hi")?;
+        assert_eq!("hi", ciphers.decode::<String>(&second.topic, &encoded)?);
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_self_test_pairings() -> anyhow::Result<()> {
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        let first = create_pairing();
+        let second = create_pairing();
+        ciphers.add_pairing(first)?;
+        ciphers.add_pairing(second.clone())?;
+        let peer = SessionKey::from_osrng(PublicKey::from(&second.params.sym_key).as_bytes())?;
+        let (session_topic, _) =
+            ciphers.create_common_topic(&second.topic, String::from(&peer.public_key()))?;
+        assert!(ciphers.self_test()?.is_empty());
+        assert!(ciphers.is_subscribed(&session_topic));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_settlement_by_topic() -> anyhow::Result<()> {
+        let ciphers = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        let settled: Vec<SessionSettled> = (0..2)
+            .map(|i| SessionSettled {
+                topic: Topic::generate(),
+                namespaces: monedero_domain::namespaces::Namespaces::default(),
+                expiry: i,
+            })
+            .collect();
+        for s in &settled {
+            ciphers.set_settlement(&s.topic, s.clone())?;
+        }
+        for s in &settled {
+            let found = ciphers.settlement(&s.topic)?.map(|f| (f.topic, f.expiry));
+            assert_eq!(Some((s.topic.clone(), s.expiry)), found);
+        }
+        assert!(ciphers.settlement(&Topic::generate())?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_self_test() -> anyhow::Result<()> {
+        let store = Arc::new(KvStorage::mem());
+        let ciphers = Cipher::new(store.clone(), None)?;
+        ciphers.set_pairing(Some(create_pairing()))?;
+        let peer = SessionKey::from_osrng(ciphers.public_key().unwrap().as_bytes())?;
+        let (session_topic, _) = ciphers.create_common_topic(
+            &ciphers.pairing().unwrap().topic,
+            String::from(&peer.public_key()),
+        )?;
+        drop(ciphers);
+
+        let ciphers = Cipher::new(store.clone(), None)?;
+        assert!(ciphers.self_test()?.is_empty());
+        assert!(ciphers.is_subscribed(&session_topic));
+        drop(ciphers);
+
+        // stored peer key replaced, the derived key no longer matches the topic
+        let other = SessionKey::from_osrng(&[0u8; 32])?;
+        store.set(
+            Cipher::storage_session_key(&session_topic),
+            String::from(&other.public_key()),
+        )?;
+        let ciphers = Cipher::new(store.clone(), None)?;
+        assert_eq!(vec![session_topic.clone()], ciphers.self_test()?);
+        assert!(!ciphers.is_subscribed(&session_topic));
+        assert_eq!(1, ciphers.session_topics());
+        assert!(store
+            .get::<String>(Cipher::storage_session_key(&session_topic))?
+            .is_none());
+
+        // undecodable peer key doesn't prevent restoring the pairing
+        let (session_topic, _) = ciphers.create_common_topic(
+            &ciphers.pairing().unwrap().topic,
+            String::from(&peer.public_key()),
+        )?;
+        store.set(
+            Cipher::storage_session_key(&session_topic),
+            String::from("not hex"),
+        )?;
+        drop(ciphers);
+        let ciphers = Cipher::new(store, None)?;
+        assert!(ciphers.pairing().is_some());
+        assert_eq!(vec![session_topic], ciphers.self_test()?);
+        Ok(())
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     #[test]
     pub fn test_cipher_storage_os() -> anyhow::Result<()> {
@@ -537,7 +1418,8 @@ mod tests {
         tracing::info!("adding session");
         let session_key = SessionKey::from_osrng(ciphers.public_key().unwrap().as_bytes())?;
         let responder_pk = session_key.public_key();
-        let (session_topic, _) = ciphers.create_common_topic(String::from(&responder_pk))?;
+        let (session_topic, _) =
+            ciphers.create_common_topic(&pairing_topic, String::from(&responder_pk))?;
         assert_eq!(session_topic, session_key.generate_topic());
         assert_eq!(ciphers.session_topics(), 2);
 
@@ -548,7 +1430,7 @@ mod tests {
             .get::<Topic>(Cipher::storage_session_key(&session_topic))?
             .is_none());
         // put session back
-        let _ = ciphers.create_common_topic(String::from(&responder_pk))?;
+        let _ = ciphers.create_common_topic(&pairing_topic, String::from(&responder_pk))?;
         drop(ciphers);
 
         // Restore sessions
@@ -567,7 +1449,8 @@ mod tests {
                 .as_bytes(),
         )?;
         let responder_pk = session_key.public_key();
-        let (session_topic, _) = ciphers.create_common_topic(String::from(&responder_pk))?;
+        let (session_topic, _) =
+            ciphers.create_common_topic(&pairing_topic, String::from(&responder_pk))?;
 
         let now = chrono::Utc::now();
         let mut settlement = SessionSettled {
@@ -581,6 +1464,10 @@ mod tests {
 
         // get settlements
         assert_eq!(1, ciphers.settlements()?.len());
+        assert_eq!(
+            Some(settlement.expiry),
+            ciphers.settlement(&session_topic)?.map(|s| s.expiry)
+        );
 
         let past = now - chrono::Duration::hours(1);
         settlement.expiry = past.timestamp();
@@ -0,0 +1,269 @@
+//! Passphrase protected export of pairing, session keys and settlements, to
+//! back up or move a wallet to another device.
+//!
+//! Layout: `version (1) | salt (16) | nonce (12) | ChaCha20Poly1305(json)`
+
+use {
+    super::{Cipher, DecodedSymKey, NONCE_LENGTH},
+    crate::CipherError,
+    chacha20poly1305::{aead::Aead, AeadCore, ChaCha20Poly1305, KeyInit},
+    monedero_domain::{Pairing, SessionSettled},
+    monedero_relay::Topic,
+    rand::RngCore,
+    serde::{Deserialize, Serialize},
+    sha2::Sha256,
+    x25519_dalek::StaticSecret,
+};
+
+/// Bump when [`Backup`] changes, `import` rejects unknown versions
+pub const BACKUP_VERSION: u8 = 1;
+const SALT_LENGTH: usize = 16;
+const PBKDF2_ROUNDS: u32 = 100_000;
+const HEADER_LENGTH: usize = 1 + SALT_LENGTH + NONCE_LENGTH;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionBackup {
+    topic: Topic,
+    controller_pk: String,
+    settlement: Option<SessionSettled>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Backup {
+    pairing: Option<Pairing>,
+    /// Sessions a dapp proposed with it derive from it rather than the
+    /// pairing key
+    #[serde(default)]
+    proposer_key: Option<DecodedSymKey>,
+    sessions: Vec<SessionBackup>,
+}
+
+fn backup_cipher(passphrase: &str, salt: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    ChaCha20Poly1305::new((&key).into())
+}
+
+fn seal(backup: &Backup, passphrase: &str) -> Result<Vec<u8>, CipherError> {
+    let mut salt = [0u8; SALT_LENGTH];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut rand::thread_rng());
+    let sealed = backup_cipher(passphrase, &salt)
+        .encrypt(&nonce, serde_json::to_vec(backup)?.as_slice())
+        .map_err(|_| CipherError::Corrupted)?;
+
+    let mut blob = Vec::with_capacity(HEADER_LENGTH + sealed.len());
+    blob.push(BACKUP_VERSION);
+    blob.extend(salt);
+    blob.extend(nonce);
+    blob.extend(sealed);
+    Ok(blob)
+}
+
+fn open(blob: &[u8], passphrase: &str) -> Result<Backup, CipherError> {
+    let (&version, rest) = blob.split_first().ok_or(CipherError::CorruptedPayload)?;
+    if version != BACKUP_VERSION {
+        return Err(CipherError::UnsupportedBackupVersion(version));
+    }
+    let (salt, rest) = rest
+        .split_first_chunk::<SALT_LENGTH>()
+        .ok_or(CipherError::CorruptedPayload)?;
+    let (nonce, sealed) = rest
+        .split_first_chunk::<NONCE_LENGTH>()
+        .ok_or(CipherError::CorruptedPayload)?;
+    let json = backup_cipher(passphrase, salt)
+        .decrypt(nonce.into(), sealed)
+        .map_err(|_| CipherError::InvalidBackup)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+impl Cipher {
+    /// Serialize and encrypt everything needed to restore this keystore with
+    /// [`Self::import`]
+    pub fn export(&self, passphrase: &str) -> Result<Vec<u8>, CipherError> {
+        let topics: Vec<Topic> = self
+            .storage
+            .get(Self::storage_sessions())?
+            .unwrap_or_default();
+        let mut sessions = Vec::with_capacity(topics.len());
+        for topic in topics {
+            let Some(controller_pk) = self
+                .storage
+                .get::<String>(Self::storage_session_key(&topic))?
+            else {
+                continue;
+            };
+            let settlement = self.storage.get(Self::storage_settlement(&topic))?;
+            sessions.push(SessionBackup {
+                topic,
+                controller_pk,
+                settlement,
+            });
+        }
+        seal(
+            &Backup {
+                pairing: self.pairing(),
+                proposer_key: self
+                    .proposer_key()?
+                    .map(|key| DecodedSymKey::from_key(&key.to_bytes())),
+                sessions,
+            },
+            passphrase,
+        )
+    }
+
+    /// Replace the current pairing and sessions with the contents of a blob
+    /// created by [`Self::export`]. The whole backup is checked before
+    /// anything is replaced, a failed import leaves the keystore as it was.
+    pub fn import(&self, blob: &[u8], passphrase: &str) -> Result<(), CipherError> {
+        let backup = open(blob, passphrase)?;
+        let proposer_key = backup.proposer_key.map(|key| StaticSecret::from(key.0));
+        let mut sessions = Vec::with_capacity(backup.sessions.len());
+        if !backup.sessions.is_empty() {
+            let pairing_key = backup
+                .pairing
+                .as_ref()
+                .map(|p| p.params.sym_key.clone())
+                .ok_or(CipherError::NonExistingPairing)?;
+            let keys: Vec<StaticSecret> = std::iter::once(pairing_key)
+                .chain(proposer_key.clone())
+                .collect();
+            for session in backup.sessions {
+                let expanded_key =
+                    Self::derive_session_key(&keys, &session.topic, &session.controller_pk)
+                        .map_err(|_| CipherError::InvalidBackup)?;
+                let settled_elsewhere = session
+                    .settlement
+                    .as_ref()
+                    .is_some_and(|s| s.topic != session.topic);
+                if settled_elsewhere {
+                    return Err(CipherError::InvalidBackup);
+                }
+                sessions.push((session, expanded_key));
+            }
+        }
+
+        self.set_pairing(backup.pairing)?;
+        if let Some(key) = proposer_key {
+            self.set_proposer_key(&key)?;
+        }
+        let mut topics = Vec::with_capacity(sessions.len());
+        for (session, expanded_key) in sessions {
+            let topic = session.topic;
+            self.storage
+                .set(Self::storage_session_key(&topic), session.controller_pk)?;
+            if let Some(settlement) = session.settlement {
+                self.set_settlement(&topic, settlement)?;
+            }
+            if let Some(pairing) = self.pairing() {
+                self.track_session(&pairing.topic, &topic);
+            }
+            self.register(&topic, &expanded_key);
+            topics.push(topic);
+        }
+        self.storage.set(Self::storage_sessions(), topics)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::session::SessionKey, monedero_store::KvStorage, std::sync::Arc};
+
+    #[test]
+    pub fn test_export_import() -> anyhow::Result<()> {
+        let source = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        source.set_pairing(Some(Pairing::default()))?;
+        let session_key = SessionKey::from_osrng(source.public_key().unwrap().as_bytes())?;
+        let (topic, _) = source.create_common_topic(
+            &source.pairing().unwrap().topic,
+            String::from(&session_key.public_key()),
+        )?;
+        source.set_settlement(&topic, SessionSettled {
+            topic: topic.clone(),
+            namespaces: monedero_domain::namespaces::Namespaces::default(),
+            expiry: chrono::Utc::now().timestamp() + 3600,
+        })?;
+
+        let blob = source.export("correct horse")?;
+        let restored = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        assert!(matches!(
+            restored.import(&blob, "wrong horse"),
+            Err(CipherError::InvalidBackup)
+        ));
+        restored.import(&blob, "correct horse")?;
+        assert_eq!(source.pairing_uri(), restored.pairing_uri());
+        assert_eq!(1, restored.settlements()?.len());
+
+        let encoded = source.encode(&topic, &"still here")?;
+        let decoded: String = restored.decode(&topic, &encoded)?;
+        assert_eq!("still here", decoded);
+
+        let mut future = blob;
+        future[0] = BACKUP_VERSION + 1;
+        assert!(matches!(
+            restored.import(&future, "correct horse"),
+            Err(CipherError::UnsupportedBackupVersion(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    pub fn test_failed_import_keeps_state() -> anyhow::Result<()> {
+        let cipher = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        cipher.set_pairing(Some(Pairing::default()))?;
+        let session_key = SessionKey::from_osrng(cipher.public_key().unwrap().as_bytes())?;
+        let (topic, _) = cipher.create_common_topic(
+            &cipher.pairing().unwrap().topic,
+            String::from(&session_key.public_key()),
+        )?;
+        let pairing_uri = cipher.pairing_uri();
+
+        // a session whose topic doesn't derive from its key
+        let mismatched = seal(
+            &Backup {
+                pairing: Some(Pairing::default()),
+                proposer_key: None,
+                sessions: vec![SessionBackup {
+                    topic: Topic::generate(),
+                    controller_pk: String::from(&session_key.public_key()),
+                    settlement: None,
+                }],
+            },
+            "correct horse",
+        )?;
+        // sessions without the pairing they derive from
+        let orphaned = seal(
+            &Backup {
+                pairing: None,
+                proposer_key: None,
+                sessions: vec![SessionBackup {
+                    topic: topic.clone(),
+                    controller_pk: String::from(&session_key.public_key()),
+                    settlement: None,
+                }],
+            },
+            "correct horse",
+        )?;
+        assert!(matches!(
+            cipher.import(&mismatched, "correct horse"),
+            Err(CipherError::InvalidBackup)
+        ));
+        assert!(matches!(
+            cipher.import(&orphaned, "correct horse"),
+            Err(CipherError::NonExistingPairing)
+        ));
+        assert!(matches!(
+            cipher.import(&cipher.export("correct horse")?, "wrong horse"),
+            Err(CipherError::InvalidBackup)
+        ));
+
+        assert_eq!(pairing_uri, cipher.pairing_uri());
+        assert!(cipher.is_subscribed(&topic));
+        let encoded = cipher.encode(&topic, &"still here")?;
+        assert_eq!("still here", cipher.decode::<String>(&topic, &encoded)?);
+        Ok(())
+    }
+}
@@ -40,4 +40,28 @@ pub enum CipherError {
 
     #[error("No Pairing exits")]
     NonExistingPairing,
+
+    #[error("payload of {size} bytes exceeds maximum of {max} bytes")]
+    PayloadTooLarge { size: usize, max: usize },
+
+    #[error("failed to compress or inflate payload: {0}")]
+    Compression(#[from] std::io::Error),
+
+    #[error("payload nests deeper than {0} levels")]
+    JsonTooDeep(usize),
+
+    #[error("nonce was already used on topic {0}")]
+    NonceReuse(Topic),
+
+    #[error("unsupported backup version {0}")]
+    UnsupportedBackupVersion(u8),
+
+    #[error("backup is corrupted or the passphrase is wrong")]
+    InvalidBackup,
+
+    #[error("pairing reached its limit of {0} sessions")]
+    SessionLimitReached(usize),
+
+    #[error("stored key for session {0} is unusable")]
+    SelfTestFailed(Topic),
 }
@@ -1,4 +1,5 @@
 use {
+    chrono::{DateTime, Utc},
     derive_more::{AsMut, AsRef},
     monedero_relay::ed25519_dalek::SecretKey,
     serde::{Deserialize, Serialize},
@@ -11,6 +12,7 @@ pub use {
         Message,
         MessageId,
         MessageIdGenerator,
+        MessageIdSource,
         PairingTopic,
         ProjectId,
         SessionTopic,
@@ -20,7 +22,7 @@ pub use {
 };
 
 pub mod pairing_uri;
-pub use pairing_uri::Pairing;
+pub use pairing_uri::{Pairing, PairingUri};
 
 const MULTICODEC_ED25519_LENGTH: usize = 32;
 
@@ -51,3 +53,43 @@ pub struct SessionSettled {
     /// Expiry should be between .now() + TTL.
     pub expiry: i64,
 }
+
+impl SessionSettled {
+    /// [`Self::expiry`] as a date
+    #[must_use]
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.expiry, 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    /// Time left until [`Self::expires_at`], zero once expired
+    #[must_use]
+    pub fn time_remaining(&self) -> chrono::Duration {
+        (self.expires_at() - Utc::now()).max(chrono::Duration::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, chrono::Duration};
+
+    fn settlement(expiry: DateTime<Utc>) -> SessionSettled {
+        SessionSettled {
+            topic: Topic::generate(),
+            namespaces: monedero_namespaces::Namespaces::default(),
+            expiry: expiry.timestamp(),
+        }
+    }
+
+    #[test]
+    fn test_time_remaining() {
+        let expiry = Utc::now() + Duration::hours(1);
+        let settled = settlement(expiry);
+        assert_eq!(expiry.timestamp(), settled.expires_at().timestamp());
+        let remaining = settled.time_remaining();
+        assert!(remaining <= Duration::hours(1));
+        assert!(remaining > Duration::hours(1) - Duration::seconds(5));
+
+        let expired = settlement(Utc::now() - Duration::hours(1));
+        assert_eq!(Duration::zero(), expired.time_remaining());
+    }
+}
@@ -9,7 +9,7 @@ use {
         fmt::{Debug, Display, Formatter},
         str::FromStr,
     },
-    url::Url,
+    url::{form_urlencoded, Url},
     x25519_dalek::StaticSecret,
 };
 
@@ -42,6 +42,12 @@ pub struct Params {
     pub relay_protocol: String,
     pub sym_key: StaticSecret,
     pub relay_data: Option<String>,
+    /// Unix timestamp after which the pairing URI is no longer valid
+    #[serde(default)]
+    pub expiry_timestamp: Option<u64>,
+    /// Methods the pairing supports, e.g. `[wc_sessionPropose]`, kept as is
+    #[serde(default)]
+    pub methods: Option<String>,
 }
 
 impl Default for Params {
@@ -50,10 +56,64 @@ impl Default for Params {
             relay_protocol: RELAY_PROTOCOL.to_string(),
             sym_key: StaticSecret::random_from_rng(rand::thread_rng()),
             relay_data: None,
+            expiry_timestamp: None,
+            methods: None,
         }
     }
 }
 
+/// Parts of a pairing URI, see [`Pairing::uri_parts`]. [`Display`] writes them
+/// back into the URI, query values percent-encoded.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PairingUri {
+    pub topic: Topic,
+    pub version: String,
+    pub relay_protocol: String,
+    pub sym_key: DecodedSymKey,
+    pub relay_data: Option<String>,
+    pub expiry_timestamp: Option<u64>,
+    pub methods: Option<String>,
+}
+
+impl Debug for PairingUri {
+    /// Debug with key masked.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PairingUri")
+            .field("topic", &self.topic)
+            .field("version", &self.version)
+            .field("relay_protocol", &self.relay_protocol)
+            .field("sym_key", &"***")
+            .field("relay_data", &self.relay_data)
+            .field("expiry_timestamp", &self.expiry_timestamp)
+            .field("methods", &self.methods)
+            .finish()
+    }
+}
+
+impl Display for PairingUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let encode = |v: &str| form_urlencoded::byte_serialize(v.as_bytes()).collect::<String>();
+        write!(
+            f,
+            "wc:{}@{}?relay-protocol={}&symKey={}",
+            self.topic,
+            self.version,
+            encode(&self.relay_protocol),
+            self.sym_key
+        )?;
+        if let Some(relay_data) = &self.relay_data {
+            write!(f, "&relay-data={}", encode(relay_data))?;
+        }
+        if let Some(expiry) = self.expiry_timestamp {
+            write!(f, "&expiryTimestamp={expiry}")?;
+        }
+        if let Some(methods) = &self.methods {
+            write!(f, "&methods={}", encode(methods))?;
+        }
+        Ok(())
+    }
+}
+
 /// https://github.com/ethereum/EIPs/blob/master/EIPS/eip-1328.md
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Pairing {
@@ -73,6 +133,54 @@ impl Default for Pairing {
 }
 
 impl Pairing {
+    /// Pairing whose topic and sym key are derived from `seed`, so tests get
+    /// the same pairing on every run. Never use it outside of tests, anyone
+    /// knowing the seed can read the pairing.
+    #[cfg(any(test, feature = "test-util"))]
+    #[must_use]
+    pub fn deterministic(seed: u64) -> Self {
+        use rand::{rngs::StdRng, RngCore, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut topic = [0u8; 32];
+        rng.fill_bytes(&mut topic);
+        Self {
+            topic: Topic::from(data_encoding::HEXLOWER.encode(&topic)),
+            version: "2".to_string(),
+            params: Params {
+                sym_key: StaticSecret::random_from_rng(&mut rng),
+                ..Params::default()
+            },
+        }
+    }
+
+    /// Every part of the URI this pairing was parsed from, unlike
+    /// [`Display`] which only writes the topic, protocol and key
+    pub fn uri_parts(&self) -> PairingUri {
+        PairingUri {
+            topic: self.topic.clone(),
+            version: self.version.clone(),
+            relay_protocol: self.params.relay_protocol.clone(),
+            sym_key: DecodedSymKey::from_key(&self.params.sym_key.to_bytes()),
+            relay_data: self.params.relay_data.clone(),
+            expiry_timestamp: self.params.expiry_timestamp,
+            methods: self.params.methods.clone(),
+        }
+    }
+
+    /// Methods advertised by the URI's `methods` parameter, flattened out of
+    /// their `[..]` groups. `None` when the URI has none, meaning the pairing
+    /// puts no constraint on the methods a session may use.
+    pub fn methods(&self) -> Option<Vec<String>> {
+        self.params.methods.as_deref().map(|methods| {
+            methods
+                .split(['[', ']', ','])
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(String::from)
+                .collect()
+        })
+    }
+
     fn parse_topic_and_version(path: &str) -> Result<(Topic, String), ParseError> {
         let caps = Regex::new(r"^(?P<topic>[[:word:]-]+)@(?P<version>\d+)$")
             .expect("invalid regex")
@@ -98,11 +206,19 @@ impl Pairing {
         let mut relay_protocol: Option<String> = None;
         let mut sym_key: Option<String> = None;
         let mut relay_data: Option<String> = None;
+        let mut expiry_timestamp: Option<u64> = None;
+        let mut methods: Option<String> = None;
         for (k, v) in queries {
             match k.as_ref() {
                 "relay-protocol" => relay_protocol = Some((*v).to_owned()),
                 "symKey" => sym_key = Some((*v).to_owned()),
                 "relay-data" => relay_data = Some((*v).to_owned()),
+                "expiryTimestamp" => {
+                    expiry_timestamp = Some(v.parse().map_err(|_| {
+                        ParseError::UnexpectedParameter((*k).to_owned(), (*v).to_owned())
+                    })?);
+                }
+                "methods" => methods = Some((*v).to_owned()),
                 _ => {
                     return Err(ParseError::UnexpectedParameter(
                         (*k).to_owned(),
@@ -118,6 +234,8 @@ impl Pairing {
             relay_protocol: relay_protocol.ok_or(ParseError::RelayProtocolNotFound)?,
             sym_key: StaticSecret::from(s),
             relay_data,
+            expiry_timestamp,
+            methods,
         })
     }
 }
@@ -197,10 +315,74 @@ mod tests {
                 sym_key: hex!("7ff3e362f825ab868e20e767fe580d0311181632707e7c878cbeca0238d45b8b")
                     .into(),
                 relay_data: None,
+                expiry_timestamp: None,
+                methods: None,
             },
         };
         let expected = Pairing::from_str(uri).unwrap();
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn uri_parts_round_trip() -> Result<(), ParseError> {
+        let uri = "wc:c9e6d30fb34afe70a15c14e9337ba8e4d5a35dd695c39b94884b0ee60c69d168@2?\
+                   relay-protocol=irn&\
+                   symKey=7ff3e362f825ab868e20e767fe580d0311181632707e7c878cbeca0238d45b8b&\
+                   relay-data=some+data&expiryTimestamp=1705329826&methods=%5Bwc_sessionPropose%\
+                   5D%2C%5Bwc_authRequest%5D";
+
+        let parts = Pairing::from_str(uri)?.uri_parts();
+        assert_eq!("2", parts.version);
+        assert_eq!("irn", parts.relay_protocol);
+        assert_eq!(Some("some data"), parts.relay_data.as_deref());
+        assert_eq!(Some(1_705_329_826), parts.expiry_timestamp);
+        assert_eq!(
+            Some("[wc_sessionPropose],[wc_authRequest]"),
+            parts.methods.as_deref()
+        );
+        assert_eq!(uri, parts.to_string());
+        assert_eq!(parts, Pairing::from_str(&parts.to_string())?.uri_parts());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_methods() -> Result<(), ParseError> {
+        let uri = "wc:c9e6d30fb34afe70a15c14e9337ba8e4d5a35dd695c39b94884b0ee60c69d168@2?\
+                   relay-protocol=irn&\
+                   symKey=7ff3e362f825ab868e20e767fe580d0311181632707e7c878cbeca0238d45b8b";
+        assert_eq!(None, Pairing::from_str(uri)?.methods());
+
+        let with_methods = format!(
+            "{uri}&methods=%5Bwc_sessionPropose%5D%2C%5Bwc_authRequest%2Cwc_authBatchRequest%5D"
+        );
+        assert_eq!(
+            Some(vec![
+                String::from("wc_sessionPropose"),
+                String::from("wc_authRequest"),
+                String::from("wc_authBatchRequest"),
+            ]),
+            Pairing::from_str(&with_methods)?.methods()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deterministic_pairing() {
+        let pairing = Pairing::deterministic(42);
+        let again = Pairing::deterministic(42);
+        assert_eq!(pairing.topic, again.topic);
+        assert_eq!(
+            pairing.params.sym_key.to_bytes(),
+            again.params.sym_key.to_bytes()
+        );
+        assert_eq!(pairing.to_string(), again.to_string());
+
+        let other = Pairing::deterministic(43);
+        assert_ne!(pairing.topic, other.topic);
+        assert_ne!(
+            pairing.params.sym_key.to_bytes(),
+            other.params.sym_key.to_bytes()
+        );
+    }
 }
@@ -8,7 +8,7 @@ use {
             ProjectId,
         },
         init_tracing,
-        rpc::{RequestMethod, RequestParams, SessionRequestRequest},
+        rpc::{RequestParams, SessionRequestBuilder},
         ClientSession,
         Dapp,
         KvStorage,
@@ -40,35 +40,30 @@ async fn propose(dapp: &Dapp) -> anyhow::Result<(Pairing, ClientSession)> {
     Ok((p, session))
 }
 
-async fn pair_ping(dapp: Dapp) {
-    loop {
-        info!("sending pair ping");
-        if let Err(e) = dapp.pair_ping().await {
-            error!("pair ping failed! {e}");
-        }
-        tokio::time::sleep(Duration::from_secs(30)).await;
-    }
-}
-
 async fn sign_message(session: ClientSession) {
-    if !session.namespaces().0.contains_key(&NamespaceName::Solana) {
+    let namespaces = session.namespaces();
+    let Some(sol_namespace) = namespaces.0.get(&NamespaceName::Solana) else {
         return;
-    }
-    let sol_namespace = session.namespaces().0.get(&NamespaceName::Solana).unwrap();
+    };
     for a in &sol_namespace.accounts.0 {
         let addr = &a.address;
         info!("found solana address {addr}");
-        let params: RequestParams = RequestParams::SessionRequest(SessionRequestRequest {
-            request: RequestMethod {
-                method: Method::Solana(SolanaMethod::SignMessage),
-                params: json!({
-                    "message": "37u9WtQpcm6ULa3VtWDFAWoQc1hUvybPrA3dtx99tgHvvcE7pKRZjuGmn7VX2tC3JmYDYGG7",
-                    "pubkey": addr,
-                }),
-                expiry: None,
-            },
-            chain_id: a.chain.clone(),
-        });
+        let request = SessionRequestBuilder::new(
+            a.chain.clone(),
+            Method::Solana(SolanaMethod::SignMessage),
+        )
+        .params(json!({
+            "message": "37u9WtQpcm6ULa3VtWDFAWoQc1hUvybPrA3dtx99tgHvvcE7pKRZjuGmn7VX2tC3JmYDYGG7",
+            "pubkey": addr,
+        }))
+        .build();
+        let params = match request {
+            Ok(r) => RequestParams::SessionRequest(r),
+            Err(e) => {
+                error!("invalid request {e}");
+                continue;
+            }
+        };
         info!(
             "signing a personal message\n{}",
             serde_json::to_string_pretty(&params).unwrap()
@@ -97,8 +92,6 @@ async fn do_dapp_stuff(dapp: Dapp) {
         Ok((_, s)) => s,
     };
     info!("settled {:#?}", session.namespaces());
-    let pinger = dapp.clone();
-    tokio::spawn(pair_ping(pinger));
     tokio::spawn(sign_message(session.clone()));
     loop {
         info!("sending session ping");
@@ -113,7 +106,11 @@ async fn do_dapp_stuff(dapp: Dapp) {
 async fn dapp_test() -> anyhow::Result<()> {
     let p = ProjectId::from("987f2292c12194ae69ddb6c52ceb1d62");
     let store = KvStorage::file(None)?;
-    let pairing_mgr = ReownBuilder::new(p).store(store).build().await?;
+    let pairing_mgr = ReownBuilder::new(p)
+        .store(store)
+        .keepalive_interval(Some(Duration::from_secs(30)))
+        .build()
+        .await?;
     let dapp = Dapp::new(pairing_mgr.clone(), Metadata {
         name: "monedero-mesh".to_string(),
         description: "reown but for rust".to_string(),
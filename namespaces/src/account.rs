@@ -1,5 +1,5 @@
 use {
-    crate::chain_id::ChainId,
+    crate::{chain_id::ChainId, AccountError},
     serde::{Deserialize, Serialize},
     serde_with::{DeserializeFromStr, SerializeDisplay},
     std::{
@@ -47,11 +47,70 @@ impl FromStr for Account {
     }
 }
 
+/// Length of a Solana public key
+const SOLANA_ADDRESS_LEN: usize = 32;
+/// Hex digits in an EIP155 address, after the `0x` prefix
+const EIP155_ADDRESS_HEX_LEN: usize = 40;
+
+impl Account {
+    /// Checks the address is well formed for the chain's namespace: base58
+    /// public key for Solana, `0x` hex for EIP155. Other namespaces aren't
+    /// checked.
+    pub fn validate(&self) -> Result<(), AccountError> {
+        match &self.chain {
+            ChainId::Solana(_) => {
+                let valid = bs58::decode(&self.address)
+                    .into_vec()
+                    .is_ok_and(|key| key.len() == SOLANA_ADDRESS_LEN);
+                if !valid {
+                    return Err(AccountError::InvalidSolanaAddress(self.address.clone()));
+                }
+            }
+            ChainId::EIP155(_) => {
+                let valid = self.address.strip_prefix("0x").is_some_and(|hex| {
+                    hex.len() == EIP155_ADDRESS_HEX_LEN
+                        && hex.bytes().all(|b| b.is_ascii_hexdigit())
+                });
+                if !valid {
+                    return Err(AccountError::InvalidEip155Address(self.address.clone()));
+                }
+            }
+            ChainId::Other(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Accounts added and removed between two [`Accounts`], see
+/// [`Accounts::diff`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountsDiff {
+    pub added: Vec<Account>,
+    pub removed: Vec<Account>,
+}
+
+impl AccountsDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
 impl Accounts {
     #[must_use]
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// What changed going from `self` to `other`: accounts only in `other`
+    /// are added, accounts only in `self` are removed. Both lists are sorted.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> AccountsDiff {
+        AccountsDiff {
+            added: other.0.difference(&self.0).cloned().collect(),
+            removed: self.0.difference(&other.0).cloned().collect(),
+        }
+    }
 }
 
 impl Display for Account {
@@ -59,3 +118,75 @@ impl Display for Account {
         write!(f, "{}:{}", self.chain, self.address)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(accounts: &[&str]) -> anyhow::Result<Accounts> {
+        Ok(Accounts(
+            accounts
+                .iter()
+                .map(|a| Account::from_str(a))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    #[test]
+    fn test_accounts_diff() -> anyhow::Result<()> {
+        let a = "eip155:1:0xBA5BA3955463ADcc7aa3E33bbdfb8A68e0933dD8";
+        let b = "eip155:1:0x1456225dE90927193F7A171E64a600416f96f2C8";
+        let c =
+            "solana:4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZ:6LmSRCiu3z6NCSpF19oz1pHXkYkN4jWbj9K1nVELpDkT";
+
+        let before = accounts(&[a, b])?;
+        let after = accounts(&[b, c])?;
+        let diff = before.diff(&after);
+        assert_eq!(diff.added, vec![Account::from_str(c)?]);
+        assert_eq!(diff.removed, vec![Account::from_str(a)?]);
+
+        let diff = before.diff(&accounts(&[a, b, c])?);
+        assert_eq!(diff.added, vec![Account::from_str(c)?]);
+        assert!(diff.removed.is_empty());
+
+        let diff = before.diff(&accounts(&[b])?);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, vec![Account::from_str(a)?]);
+
+        assert!(before.diff(&before.clone()).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_account_validate() -> anyhow::Result<()> {
+        let sol =
+            "solana:4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZ:6LmSRCiu3z6NCSpF19oz1pHXkYkN4jWbj9K1nVELpDkT";
+        let eth = "eip155:1:0xBA5BA3955463ADcc7aa3E33bbdfb8A68e0933dD8";
+        Account::from_str(sol)?.validate()?;
+        Account::from_str(eth)?.validate()?;
+        // not an account for those namespaces, but no format to check
+        Account::from_str("near:mainnet:alice.near")?.validate()?;
+        let cosmos = "cosmos:cosmoshub-4:cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+        assert_eq!(cosmos, Account::from_str(cosmos)?.to_string());
+
+        let bad_sol = Account::from_str(
+            "solana:4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZ:0xBA5BA3955463ADcc7aa3E33bbdfb8A68e0933dD8",
+        )?;
+        assert_eq!(
+            bad_sol.validate(),
+            Err(AccountError::InvalidSolanaAddress(bad_sol.address))
+        );
+        // base58, but not 32 bytes
+        let short_sol = Account::from_str("solana:4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZ:6LmSRCiu3z6N")?;
+        assert!(short_sol.validate().is_err());
+
+        let bad_eth = Account::from_str("eip155:1:6LmSRCiu3z6NCSpF19oz1pHXkYkN4jWbj9K1nVELpDkT")?;
+        assert_eq!(
+            bad_eth.validate(),
+            Err(AccountError::InvalidEip155Address(bad_eth.address))
+        );
+        let short_eth = Account::from_str("eip155:1:0xBA5BA3955463")?;
+        assert!(short_eth.validate().is_err());
+        Ok(())
+    }
+}
@@ -185,7 +185,8 @@ impl FromStr for ChainId {
             SOLANA_TEST => Ok(Self::Solana(ChainType::Test)),
             _ => {
                 tracing::debug!("unknown chain {}", s);
-                Ok(Self::Other(s.to_string()))
+                // drop the account, if any, keeping the namespace as written
+                Ok(Self::Other(format!("{}:{id}", components[0])))
             }
         }
     }
@@ -239,6 +240,10 @@ mod tests {
         assert_eq!(solana.to_string(), SOLANA_DEV);
         assert_eq!(solana, SOLANA_DEV.parse()?);
 
+        let cosmos = ChainId::from_str("cosmos:cosmoshub-4:cosmos1account")?;
+        assert_eq!(ChainId::Other(String::from("cosmos:cosmoshub-4")), cosmos);
+        assert_eq!(cosmos, cosmos.to_string().parse()?);
+
         // let solana = ChainId::from_str(SOLANA_TEST_OLD)?;
         // assert!(matches!(solana, ChainId::Solana(ChainType::Test)));
         // assert_eq!(solana.to_string(), SOLANA_TEST_OLD);
@@ -0,0 +1,66 @@
+use {
+    crate::{Events, Methods, NamespaceName, Namespaces},
+    std::collections::BTreeMap,
+};
+
+/// Methods and events a dapp requests per namespace when proposing a
+/// session.
+///
+/// Namespaces without an entry keep the defaults from
+/// `Methods::from(&NamespaceName)` and `Events::from(&NamespaceName)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceDefaults(BTreeMap<NamespaceName, (Methods, Events)>);
+
+impl NamespaceDefaults {
+    #[must_use]
+    pub fn with(mut self, name: NamespaceName, methods: Methods, events: Events) -> Self {
+        self.0.insert(name, (methods, events));
+        self
+    }
+
+    /// Replace methods and events of every namespace that has an entry
+    pub fn apply(&self, namespaces: &mut Namespaces) {
+        for (name, ns) in &mut namespaces.0 {
+            if let Some((methods, events)) = self.0.get(name) {
+                ns.methods = methods.clone();
+                ns.events = events.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{ChainId, ChainType, Method, SolanaMethod},
+        std::collections::BTreeSet,
+    };
+
+    #[test]
+    fn test_apply_defaults() -> anyhow::Result<()> {
+        let sign = Methods(BTreeSet::from([Method::Solana(SolanaMethod::SignMessage)]));
+        let defaults = NamespaceDefaults::default().with(
+            NamespaceName::Solana,
+            sign.clone(),
+            Events(BTreeSet::new()),
+        );
+        let chains = [
+            ChainId::Solana(ChainType::Main),
+            ChainId::EIP155(alloy_chains::Chain::mainnet()),
+        ];
+        let mut namespaces: Namespaces = chains.iter().into();
+        defaults.apply(&mut namespaces);
+
+        let solana = namespaces
+            .get(&NamespaceName::Solana)
+            .ok_or(crate::Error::NamespaceNotFound)?;
+        assert_eq!(solana.methods, sign);
+        assert!(solana.events.is_empty());
+        let eip = namespaces
+            .get(&NamespaceName::EIP155)
+            .ok_or(crate::Error::NamespaceNotFound)?;
+        assert_eq!(eip.methods, Methods::from(&NamespaceName::EIP155));
+        Ok(())
+    }
+}
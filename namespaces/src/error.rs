@@ -7,6 +7,8 @@ pub enum Error {
     InvalidMethod(String),
     #[error("Invalid account format {0:#?}")]
     InvalidAccountFormat(String),
+    #[error(transparent)]
+    InvalidAccount(#[from] AccountError),
     #[error("Invalid chain ID {0:#?}")]
     InvalidChainId(String),
 
@@ -16,3 +18,23 @@ pub enum Error {
     #[error("chainId has incorrect syntax {0:#?}")]
     MalformedChainId(String),
 }
+
+/// Address of an [`crate::Account`] doesn't match its chain's format
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AccountError {
+    #[error("Solana address is not a base58 public key {0:#?}")]
+    InvalidSolanaAddress(String),
+
+    #[error("EIP155 address is not 0x followed by 40 hex digits {0:#?}")]
+    InvalidEip155Address(String),
+}
+
+/// Settled [`crate::Namespace`] breaking an invariant of the session
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NamespaceError {
+    #[error("account {0} is not on a chain of its namespace")]
+    OrphanAccount(crate::Account),
+
+    #[error("chain {0} lacks the required method {1}")]
+    MissingMethod(crate::ChainId, crate::Method),
+}
@@ -37,9 +37,19 @@ impl Deref for Events {
 //               "connect",
 //               "message"
 #[derive(
-    Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, SerializeDisplay, DeserializeFromStr,
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    SerializeDisplay,
+    DeserializeFromStr,
 )]
 pub enum Event {
+    #[default]
     AccountsChanged,
     ChainChanged,
     Other(String),
@@ -78,9 +88,3 @@ impl Display for Event {
         }
     }
 }
-
-impl Default for Event {
-    fn default() -> Self {
-        Self::AccountsChanged
-    }
-}
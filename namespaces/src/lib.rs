@@ -15,15 +15,23 @@ use {
 
 mod account;
 mod chain_id;
+mod defaults;
 mod error;
 mod event;
 mod method;
 mod name;
 
 pub use {
-    crate::{account::*, chain_id::*, event::*, method::*, name::NamespaceName},
+    crate::{
+        account::*,
+        chain_id::*,
+        defaults::NamespaceDefaults,
+        event::*,
+        method::*,
+        name::{NamespaceName, NamespaceNames},
+    },
     alloy_chains::Chain as AlloyChain,
-    error::Error,
+    error::{AccountError, Error, NamespaceError},
 };
 
 #[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
@@ -37,6 +45,47 @@ impl Namespaces {
             .collect::<Vec<_>>()
             .join(", ")
     }
+
+    /// Checks the address format of every account, see [`Account::validate`]
+    pub fn validate_accounts(&self) -> Result<(), AccountError> {
+        self.values()
+            .flat_map(|ns| ns.accounts.iter())
+            .try_for_each(Account::validate)
+    }
+
+    /// Checks each namespace with [`Namespace::validate`], and that its chains
+    /// have the methods `required` for their namespace
+    pub fn validate(&self, required: &Self) -> Result<(), NamespaceError> {
+        for (name, ns) in self.iter() {
+            ns.validate()?;
+            let Some(req) = required.get(name) else {
+                continue;
+            };
+            let missing = req.methods.0.difference(&ns.methods.0).next();
+            if let (Some(method), Some(chain)) = (missing, ns.chains.0.first()) {
+                return Err(NamespaceError::MissingMethod(chain.clone(), method.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether every namespace of `required` is here with at least its
+    /// chains, methods and events
+    pub fn satisfies(&self, required: &Self) -> bool {
+        required.iter().all(|(name, req)| {
+            self.get(name).is_some_and(|ns| {
+                req.chains.0.is_subset(&ns.chains.0)
+                    && req.methods.0.is_subset(&ns.methods.0)
+                    && req.events.0.is_subset(&ns.events.0)
+            })
+        })
+    }
+
+    /// Whether `method` was granted in the namespace of `chain_id`
+    pub fn authorizes(&self, chain_id: &ChainId, method: &Method) -> bool {
+        self.get(&NamespaceName::from(chain_id))
+            .is_some_and(|ns| ns.methods.0.contains(method))
+    }
 }
 
 impl Debug for Namespaces {
@@ -77,6 +126,18 @@ pub struct Namespace {
     // pub extensions: Option<Vec<Self>>,
 }
 
+impl Namespace {
+    /// Checks every account is on one of the namespace's chains
+    pub fn validate(&self) -> Result<(), NamespaceError> {
+        self.accounts
+            .iter()
+            .find(|account| !self.chains.0.contains(&account.chain))
+            .map_or(Ok(()), |orphan| {
+                Err(NamespaceError::OrphanAccount(orphan.clone()))
+            })
+    }
+}
+
 impl Namespaces {
     pub fn chains(&self) -> Chains {
         let mut chains = BTreeSet::new();
@@ -135,6 +196,38 @@ impl Namespaces {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    /// Replace the accounts of `chain` with `accounts`.
+    /// Returns true if the namespace of `chain` exists and was modified.
+    pub fn replace_accounts(
+        &mut self,
+        chain: &ChainId,
+        accounts: impl IntoIterator<Item = Account>,
+    ) -> bool {
+        let Some(ns) = self.0.get_mut(&NamespaceName::from(chain)) else {
+            return false;
+        };
+        let replaced: BTreeSet<Account> = ns
+            .accounts
+            .iter()
+            .filter(|a| a.chain != *chain)
+            .cloned()
+            .chain(accounts)
+            .collect();
+        if replaced == ns.accounts.0 {
+            return false;
+        }
+        ns.accounts = Accounts(replaced);
+        true
+    }
+
+    /// Add `chain` to its namespace.
+    /// Returns true if the namespace exists and did not contain `chain`
+    pub fn add_chain(&mut self, chain: ChainId) -> bool {
+        self.0
+            .get_mut(&NamespaceName::from(&chain))
+            .is_some_and(|ns| ns.chains.0.insert(chain))
+    }
 }
 
 #[cfg(test)]
@@ -212,6 +305,67 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_validate() -> anyhow::Result<()> {
+        let holesky = ChainId::EIP155(alloy_chains::Chain::holesky());
+        let sepolia = ChainId::EIP155(alloy_chains::Chain::sepolia());
+        let required: Namespaces = std::iter::once(&holesky).into();
+        let mut namespaces = required.clone();
+        let account: Account =
+            format!("{holesky}:0xac56ad762E1F5335cF9e1B0F5ab78a75a93f291A").parse()?;
+        namespaces.replace_accounts(&holesky, [account]);
+        namespaces.validate(&required)?;
+
+        let orphan: Account =
+            format!("{sepolia}:0xac56ad762E1F5335cF9e1B0F5ab78a75a93f291A").parse()?;
+        let mut broken = namespaces.clone();
+        let ns = broken
+            .get_mut(&NamespaceName::EIP155)
+            .ok_or(Error::NamespaceNotFound)?;
+        ns.accounts.0.insert(orphan.clone());
+        assert_eq!(ns.validate(), Err(NamespaceError::OrphanAccount(orphan)));
+
+        let personal_sign = Method::EIP155(EipMethod::PersonalSign);
+        let mut broken = namespaces;
+        broken
+            .get_mut(&NamespaceName::EIP155)
+            .ok_or(Error::NamespaceNotFound)?
+            .methods
+            .0
+            .remove(&personal_sign);
+        assert_eq!(
+            broken.validate(&required),
+            Err(NamespaceError::MissingMethod(holesky, personal_sign))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_accounts() -> anyhow::Result<()> {
+        let holesky = ChainId::EIP155(alloy_chains::Chain::holesky());
+        let sepolia = ChainId::EIP155(alloy_chains::Chain::sepolia());
+        let mut namespaces: Namespaces = [holesky.clone(), sepolia.clone()].iter().into();
+        let old: Account = format!("{holesky}:0xold").parse()?;
+        let other: Account = format!("{sepolia}:0xother").parse()?;
+        assert!(namespaces.replace_accounts(&holesky, [old, other.clone()]));
+
+        let new: Account = format!("{holesky}:0xnew").parse()?;
+        assert!(namespaces.replace_accounts(&holesky, [new.clone()]));
+        assert!(!namespaces.replace_accounts(&holesky, [new.clone()]));
+        let accounts = &namespaces
+            .get(&NamespaceName::EIP155)
+            .ok_or(Error::NamespaceNotFound)?
+            .accounts;
+        assert_eq!(accounts.0, BTreeSet::from([new, other]));
+
+        let solana = ChainId::Solana(ChainType::Main);
+        assert!(!namespaces.replace_accounts(&solana, []));
+        assert!(!namespaces.add_chain(solana));
+        assert!(!namespaces.add_chain(sepolia));
+        assert!(namespaces.add_chain(ChainId::EIP155(alloy_chains::Chain::mainnet())));
+        Ok(())
+    }
+
     #[test]
     #[allow(
         clippy::unwrap_used,
@@ -336,4 +490,43 @@ mod tests {
         assert!(found.is_some());
         Ok(())
     }
+
+    #[test]
+    fn test_authorizes() {
+        let eth = ChainId::EIP155(alloy_chains::Chain::mainnet());
+        let base = ChainId::EIP155(alloy_chains::Chain::base_mainnet());
+        let namespaces: Namespaces = [eth.clone(), base.clone()].iter().into();
+        assert!(namespaces.authorizes(&eth, &Method::EIP155(EipMethod::PersonalSign)));
+        assert!(namespaces.authorizes(&base, &Method::EIP155(EipMethod::SendTransaction)));
+        assert!(!namespaces.authorizes(
+            &eth,
+            &Method::EIP155(EipMethod::Other(String::from("eth_accounts")))
+        ));
+        assert!(!namespaces.authorizes(
+            &ChainId::Solana(ChainType::Main),
+            &Method::Solana(SolanaMethod::SignMessage)
+        ));
+    }
+
+    #[test]
+    fn test_satisfies() {
+        let eth = ChainId::EIP155(alloy_chains::Chain::mainnet());
+        let base = ChainId::EIP155(alloy_chains::Chain::base_mainnet());
+        let sol = ChainId::Solana(ChainType::Main);
+        let required: Namespaces = std::iter::once(&eth).into();
+        let wider: Namespaces = [eth, base, sol].iter().into();
+        assert!(wider.satisfies(&required));
+        assert!(required.satisfies(&required));
+        assert!(!required.satisfies(&wider));
+
+        let mut fewer_methods = required.clone();
+        if let Some(ns) = fewer_methods.get_mut(&NamespaceName::EIP155) {
+            ns.methods
+                .0
+                .remove(&Method::EIP155(EipMethod::PersonalSign));
+        }
+        assert!(!fewer_methods.satisfies(&required));
+        assert!(Namespaces::default().satisfies(&Namespaces::default()));
+        assert!(!Namespaces::default().satisfies(&required));
+    }
 }
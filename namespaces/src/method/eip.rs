@@ -71,6 +71,21 @@ impl EipMethod {
             Method::EIP155(Self::Sign),
         ])
     }
+
+    /// Every method this crate can parse, excluding [`Self::Other`]
+    #[must_use]
+    pub fn supported() -> BTreeSet<Method> {
+        BTreeSet::from([
+            Method::EIP155(Self::SignTransaction),
+            Method::EIP155(Self::Sign),
+            Method::EIP155(Self::PersonalSign),
+            Method::EIP155(Self::PersonalSignExt),
+            Method::EIP155(Self::SignTypedData),
+            Method::EIP155(Self::SignTypedDataV4),
+            Method::EIP155(Self::SendTransaction),
+            Method::EIP155(Self::SendTransactionExt),
+        ])
+    }
 }
 
 impl FromStr for EipMethod {
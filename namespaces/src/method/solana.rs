@@ -13,11 +13,17 @@ use {
 
 const SIGN_MESSAGE: &str = "solana_signMessage";
 const SIGN_TRANSACTION: &str = "solana_signTransaction";
+const SIGN_ALL_TRANSACTIONS: &str = "solana_signAllTransactions";
+const SIGN_AND_SEND_TRANSACTION: &str = "solana_signAndSendTransaction";
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
 pub enum SolanaMethod {
     SignMessage,
     SignTransaction,
+    /// Batch signing, one approval for many transactions
+    SignAllTransactions,
+    /// The wallet signs and submits, returning only the signature
+    SignAndSendTransaction,
     Other(String),
 }
 
@@ -38,6 +44,8 @@ impl Display for SolanaMethod {
         match self {
             Self::SignMessage => write!(f, "{SIGN_MESSAGE}"),
             Self::SignTransaction => write!(f, "{SIGN_TRANSACTION}"),
+            Self::SignAllTransactions => write!(f, "{SIGN_ALL_TRANSACTIONS}"),
+            Self::SignAndSendTransaction => write!(f, "{SIGN_AND_SEND_TRANSACTION}"),
             Self::Other(m) => write!(f, "{m}"),
         }
     }
@@ -50,6 +58,8 @@ impl FromStr for SolanaMethod {
         match s {
             SIGN_TRANSACTION => Ok(Self::SignTransaction),
             SIGN_MESSAGE => Ok(Self::SignMessage),
+            SIGN_ALL_TRANSACTIONS => Ok(Self::SignAllTransactions),
+            SIGN_AND_SEND_TRANSACTION => Ok(Self::SignAndSendTransaction),
             _ => Ok(Self::Other(s.to_string())),
         }
     }
@@ -63,6 +73,17 @@ impl SolanaMethod {
             Method::Solana(Self::SignMessage),
         ])
     }
+
+    /// Every method this crate can parse, excluding [`Self::Other`]
+    #[must_use]
+    pub fn supported() -> BTreeSet<Method> {
+        BTreeSet::from([
+            Method::Solana(Self::SignMessage),
+            Method::Solana(Self::SignTransaction),
+            Method::Solana(Self::SignAllTransactions),
+            Method::Solana(Self::SignAndSendTransaction),
+        ])
+    }
 }
 
 #[cfg(test)]
@@ -79,10 +100,36 @@ mod tests {
             SolanaMethod::SignMessage,
             SIGN_MESSAGE.parse::<SolanaMethod>()?
         );
+        assert_eq!(
+            SolanaMethod::SignAllTransactions,
+            SIGN_ALL_TRANSACTIONS.parse::<SolanaMethod>()?
+        );
+        assert_eq!(
+            SolanaMethod::SignAndSendTransaction,
+            SIGN_AND_SEND_TRANSACTION.parse::<SolanaMethod>()?
+        );
         assert!(matches!(
             "solana_signAndSend".parse::<SolanaMethod>()?,
             SolanaMethod::Other(_)
         ));
         Ok(())
     }
+
+    #[test]
+    fn test_serde_sign_all_transactions() -> anyhow::Result<()> {
+        let json = serde_json::to_string(&SolanaMethod::SignAllTransactions)?;
+        assert_eq!(r#""solana_signAllTransactions""#, json);
+        let method: Method = serde_json::from_str(&json)?;
+        assert_eq!(Method::Solana(SolanaMethod::SignAllTransactions), method);
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_sign_and_send_transaction() -> anyhow::Result<()> {
+        let json = serde_json::to_string(&SolanaMethod::SignAndSendTransaction)?;
+        assert_eq!(r#""solana_signAndSendTransaction""#, json);
+        let method: Method = serde_json::from_str(&json)?;
+        assert_eq!(Method::Solana(SolanaMethod::SignAndSendTransaction), method);
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
 use {
-    crate::chain_id::{ChainId, Chains},
+    crate::{
+        chain_id::{ChainId, Chains},
+        method::{EipMethod, Methods, SolanaMethod},
+    },
     serde::{Deserialize, Serialize},
     serde_with::{DeserializeFromStr, SerializeDisplay},
     std::{
@@ -10,21 +13,16 @@ use {
     },
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
 pub enum NamespaceName {
     EIP155,
+    #[default]
     Solana,
     // Tezos,
     // Near,
     Other(String),
 }
 
-impl Default for NamespaceName {
-    fn default() -> Self {
-        Self::Solana
-    }
-}
-
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct NamespaceNames(pub BTreeSet<NamespaceName>);
@@ -41,6 +39,19 @@ impl Ord for NamespaceName {
     }
 }
 
+impl NamespaceName {
+    /// All methods of this namespace known to this crate, unlike
+    /// [`Methods::from`] which only returns the defaults
+    #[must_use]
+    pub fn supported_methods(&self) -> Methods {
+        match self {
+            Self::EIP155 => Methods(EipMethod::supported()),
+            Self::Solana => Methods(SolanaMethod::supported()),
+            Self::Other(_) => Methods(BTreeSet::new()),
+        }
+    }
+}
+
 impl Display for NamespaceName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -117,7 +128,7 @@ impl From<NamespaceName> for String {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, crate::method::Method};
 
     #[test]
     fn test_namespace_name() {
@@ -131,4 +142,43 @@ mod tests {
         let s: NamespaceName = "solana".into();
         assert_eq!(NamespaceName::Solana, s);
     }
+
+    #[test]
+    fn test_supported_methods() {
+        let solana = NamespaceName::Solana.supported_methods();
+        assert_eq!(4, solana.len());
+        for m in solana.iter() {
+            match m {
+                Method::Solana(
+                    SolanaMethod::SignMessage
+                    | SolanaMethod::SignTransaction
+                    | SolanaMethod::SignAllTransactions
+                    | SolanaMethod::SignAndSendTransaction,
+                ) => {}
+                _ => panic!("unexpected solana method {m}"),
+            }
+        }
+        let eip = NamespaceName::EIP155.supported_methods();
+        assert_eq!(8, eip.len());
+        for m in eip.iter() {
+            match m {
+                Method::EIP155(
+                    EipMethod::SignTransaction
+                    | EipMethod::Sign
+                    | EipMethod::PersonalSign
+                    | EipMethod::PersonalSignExt
+                    | EipMethod::SignTypedData
+                    | EipMethod::SignTypedDataV4
+                    | EipMethod::SendTransaction
+                    | EipMethod::SendTransactionExt,
+                ) => {}
+                _ => panic!("unexpected eip155 method {m}"),
+            }
+        }
+        assert!(SolanaMethod::defaults().is_subset(&solana));
+        assert!(EipMethod::defaults().is_subset(&eip));
+        assert!(NamespaceName::Other(String::from("blah"))
+            .supported_methods()
+            .is_empty());
+    }
 }
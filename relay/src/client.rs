@@ -1,9 +1,22 @@
 use {
-    crate::{ConnectionHandler, ConnectionOptions, Result, SubscriptionId, Topic},
+    crate::{
+        ClientError,
+        CloseFrame,
+        ConnectionHandler,
+        ConnectionOptions,
+        Message,
+        Result,
+        SubscriptionId,
+        Topic,
+        MAX_FETCH_PAGES,
+        RATE_LIMITED_RETRY_AFTER,
+    },
     reown_relay_client::{
+        error::Error as WcError,
         websocket::{Client as WcClient, ConnectionHandler as WcHandler, PublishedMessage},
         ConnectionOptions as WcOptions,
     },
+    reown_relay_rpc::rpc::Error as RpcError,
     std::{
         fmt::{Debug, Display, Formatter},
         sync::Arc,
@@ -55,8 +68,11 @@ impl<T: ConnectionHandler> WcHandler for WrapperHandler<T> {
         self.handler.connected();
     }
 
-    fn disconnected(&mut self, _frame: Option<reown_relay_client::websocket::CloseFrame<'static>>) {
-        self.handler.disconnected(None);
+    fn disconnected(&mut self, frame: Option<reown_relay_client::websocket::CloseFrame<'static>>) {
+        self.handler.disconnected(frame.map(|f| CloseFrame {
+            code: f.code.into(),
+            reason: f.reason,
+        }));
     }
 
     fn message_received(&mut self, message: PublishedMessage) {
@@ -82,6 +98,9 @@ impl Client {
 
 impl Client {
     /// Publishes a message over the network on given topic.
+    ///
+    /// Fails with [`ClientError::RateLimited`] when the relay answers with
+    /// "too many requests".
     pub async fn publish(
         &self,
         topic: Topic,
@@ -92,19 +111,26 @@ impl Client {
     ) -> Result<()> {
         self.wc
             .publish(topic, message, None, tag, ttl, prompt)
-            .await?;
+            .await
+            .map_err(|e| match e {
+                WcError::Response(RpcError::TooManyRequests) => ClientError::RateLimited {
+                    retry_after: RATE_LIMITED_RETRY_AFTER,
+                },
+                e => e.into(),
+            })?;
         Ok(())
     }
 
     /// Subscribes on topic to receive messages.
-    /// The request is resolved optimistically as soon as the relay receives it.
+    /// Resolves with the subscription id once the relay acknowledges the
+    /// request.
     pub async fn subscribe(&self, topic: Topic) -> Result<SubscriptionId> {
         let id = self.wc.subscribe(topic).await?;
         Ok(id)
     }
 
-    /// Subscribes on multiple topics to receive messages. The request is
-    /// resolved optimistically as soon as the relay receives it.
+    /// Subscribes on multiple topics to receive messages. Resolves with the
+    /// subscription ids once the relay acknowledges the request.
     pub async fn batch_subscribe(
         &self,
         topics: impl Into<Vec<Topic>> + Send,
@@ -119,6 +145,21 @@ impl Client {
         Ok(())
     }
 
+    /// Messages held in the relay mailbox for `topic`, following
+    /// `has_more` until the relay has sent them all, for at most
+    /// [`MAX_FETCH_PAGES`] pages
+    pub async fn fetch(&self, topic: Topic) -> Result<Vec<Message>> {
+        let mut messages = Vec::new();
+        for _ in 0..MAX_FETCH_PAGES {
+            let response = self.wc.fetch(topic.clone()).await?;
+            messages.extend(response.messages.into_iter().map(Message::from));
+            if !response.has_more {
+                return Ok(messages);
+            }
+        }
+        Err(ClientError::FetchPageLimit(MAX_FETCH_PAGES))
+    }
+
     pub async fn connect(&self, opts: &ConnectionOptions) -> Result<()> {
         let wc: WcOptions = WcOptions {
             address: String::from(&opts.address),
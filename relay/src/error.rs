@@ -1,6 +1,7 @@
 use {
     crate::Topic,
-    reown_relay_rpc::rpc::{PublishError, SubscriptionError},
+    reown_relay_rpc::rpc::{GenericError, PublishError, SubscriptionError},
+    std::time::Duration,
 };
 
 #[allow(clippy::module_name_repetitions)]
@@ -30,6 +31,17 @@ pub enum ClientError {
     #[error(transparent)]
     PublicationError(#[from] reown_relay_client::error::Error<PublishError>),
 
+    #[error(transparent)]
+    FetchError(#[from] reown_relay_client::error::Error<GenericError>),
+
+    /// The relay still had messages after [`crate::MAX_FETCH_PAGES`] pages
+    #[error("relay mailbox still has messages after {0} pages")]
+    FetchPageLimit(usize),
+
+    /// The relay refused the request for exceeding its rate limit
+    #[error("rate limited by the relay, retry after {retry_after:?}")]
+    RateLimited { retry_after: Duration },
+
     #[error(transparent)]
     BindError(#[from] tokio::io::Error),
 }
@@ -1,5 +1,5 @@
 pub use {
-    reown_relay_client::MessageIdGenerator,
+    message_id::{MessageIdGenerator, MessageIdSource},
     reown_relay_rpc::{
         auth::*,
         domain::{
@@ -15,7 +15,7 @@ pub use {
 };
 use {
     reown_relay_client::{websocket::PublishedMessage, Authorization},
-    reown_relay_rpc::auth::ed25519_dalek::SigningKey,
+    reown_relay_rpc::{auth::ed25519_dalek::SigningKey, rpc::SubscriptionData},
     serde::{Deserialize, Serialize},
     std::{
         borrow::Cow,
@@ -32,22 +32,44 @@ pub const AUTH_URL: &str = "https://cartera-mesh.com";
 
 mod client;
 mod error;
+mod message_id;
 #[cfg(not(target_family = "wasm"))]
 mod mock;
-pub use client::Client;
+mod transport;
 #[cfg(not(target_family = "wasm"))]
-pub use mock::MockRelay;
+pub use mock::{CapturedLogs, MockRelay};
+pub use {
+    client::Client,
+    transport::{SharedTransport, Transport, TransportFactory, WebSocketTransport},
+};
 pub type PairingTopic = Topic;
 pub type SessionTopic = Topic;
 pub use error::ClientError;
 pub type Result<T> = std::result::Result<T, ClientError>;
 
 pub fn shorten_topic(id: &Topic) -> String {
-    let mut id = format!("{id}");
-    if id.len() > 10 {
-        id = String::from(&id[0..9]);
+    ShortTopic(id).to_string()
+}
+
+/// Displays a topic as its first 9 characters, enough to tell topics apart
+/// in logs without the noise of the full 64. Wire traces keep full topics.
+#[derive(Clone, Copy)]
+pub struct ShortTopic<'a>(pub &'a Topic);
+
+impl Display for ShortTopic<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let topic = self.0.to_string();
+        if topic.len() > 10 {
+            return f.write_str(&topic[0..9]);
+        }
+        f.write_str(&topic)
+    }
+}
+
+impl Debug for ShortTopic<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
     }
-    id
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -105,6 +127,23 @@ impl From<PublishedMessage> for Message {
     }
 }
 
+/// Message fetched from the relay mailbox, see [`Transport::fetch`]. The
+/// relay doesn't send an id for these, so a fresh one is generated.
+impl From<SubscriptionData> for Message {
+    fn from(value: SubscriptionData) -> Self {
+        Self {
+            id: MessageIdGenerator::new().next(),
+            subscription_id: SubscriptionId::from(value.topic.as_ref()),
+            topic: value.topic,
+            message: value.message,
+            tag: value.tag,
+            published_at: chrono::DateTime::from_timestamp_millis(value.published_at)
+                .unwrap_or_default(),
+            received_at: chrono::Utc::now(),
+        }
+    }
+}
+
 impl Display for Message {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -151,6 +190,12 @@ impl ConnectionOptions {
         Self::create(RELAY_ADDRESS, project_id, serialized)
     }
 
+    /// Replace the auth token used on the next connect, e.g. after the relay
+    /// closed the socket with [`RelayDisconnectReason::AuthExpired`]
+    pub fn set_auth(&mut self, serialized: SerializedAuthToken) {
+        self.auth = Authorization::Query(serialized);
+    }
+
     fn create(address: &str, project_id: ProjectId, serialized: SerializedAuthToken) -> Self {
         Self {
             address: address.into(),
@@ -165,10 +210,74 @@ impl ConnectionOptions {
 /// A struct representing the close command.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CloseFrame<'t> {
+    /// The websocket close code.
+    pub code: u16,
     /// The reason as text string.
     pub reason: Cow<'t, str>,
 }
 
+/// Close code sent by the relay when the auth token expired or was rejected
+pub const CLOSE_AUTH_EXPIRED: u16 = 3000;
+/// Close code sent by the relay when the client should back off
+pub const CLOSE_RATE_LIMITED: u16 = 1013;
+/// [`ClientError::RateLimited`] delay, the relay's "too many requests"
+/// response carries no hint of its own
+pub const RATE_LIMITED_RETRY_AFTER: Duration = Duration::from_secs(1);
+/// Mailbox pages a fetch follows before failing with
+/// [`ClientError::FetchPageLimit`], in case the relay never stops answering
+/// `has_more`
+pub const MAX_FETCH_PAGES: usize = 100;
+
+/// Why the relay closed the socket, derived from the websocket close code.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RelayDisconnectReason {
+    /// Closed without a close frame, e.g. network failure
+    Dropped,
+    /// Normal closure (1000)
+    Normal,
+    /// Auth token expired or rejected ([`CLOSE_AUTH_EXPIRED`]), reconnect with
+    /// a fresh token
+    AuthExpired,
+    /// Too many requests ([`CLOSE_RATE_LIMITED`]), back off before
+    /// reconnecting
+    RateLimited,
+    /// Relay is going away (1001) or restarting (1012)
+    ServerShutdown,
+    Other(u16),
+}
+
+impl RelayDisconnectReason {
+    #[must_use]
+    pub const fn from_code(code: u16) -> Self {
+        match code {
+            1000 => Self::Normal,
+            CLOSE_AUTH_EXPIRED => Self::AuthExpired,
+            CLOSE_RATE_LIMITED => Self::RateLimited,
+            1001 | 1012 => Self::ServerShutdown,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl From<Option<&CloseFrame<'_>>> for RelayDisconnectReason {
+    fn from(frame: Option<&CloseFrame<'_>>) -> Self {
+        frame.map_or(Self::Dropped, |f| Self::from_code(f.code))
+    }
+}
+
+impl Display for RelayDisconnectReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dropped => write!(f, "dropped"),
+            Self::Normal => write!(f, "normal"),
+            Self::AuthExpired => write!(f, "auth expired"),
+            Self::RateLimited => write!(f, "rate limited"),
+            Self::ServerShutdown => write!(f, "server shutdown"),
+            Self::Other(code) => write!(f, "close code {code}"),
+        }
+    }
+}
+
 pub struct NoopHandler;
 
 impl ConnectionHandler for NoopHandler {
@@ -193,6 +302,28 @@ impl ConnectionHandler for LogHandler {
     }
 }
 
+impl ConnectionHandler for Box<dyn ConnectionHandler> {
+    fn connected(&mut self) {
+        (**self).connected();
+    }
+
+    fn disconnected(&mut self, frame: Option<CloseFrame<'static>>) {
+        (**self).disconnected(frame);
+    }
+
+    fn message_received(&mut self, message: Message) {
+        (**self).message_received(message);
+    }
+
+    fn inbound_error(&mut self, error: ClientError) {
+        (**self).inbound_error(error);
+    }
+
+    fn outbound_error(&mut self, error: ClientError) {
+        (**self).outbound_error(error);
+    }
+}
+
 /// Handlers for the RPC events.
 pub trait ConnectionHandler: Send + 'static {
     /// Called when a connection to the Relay is established.
@@ -1,7 +1,11 @@
 use {
-    dashmap::DashSet,
+    crate::Topic,
+    dashmap::DashMap,
     futures_util::stream::SplitSink,
-    reown_relay_rpc::rpc::{Payload, Publish},
+    reown_relay_rpc::{
+        domain::MessageId,
+        rpc::{Payload, Publish},
+    },
     std::{
         fmt::{Debug, Display},
         sync::Arc,
@@ -14,6 +18,32 @@ mod client;
 mod server;
 pub use server::MockRelay;
 
+/// Tracing output kept in memory, for tests asserting on logs. Pass
+/// `move || logs.clone()` as the writer of a `tracing_subscriber` fmt
+/// subscriber.
+#[derive(Clone, Default)]
+pub struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl CapturedLogs {
+    /// Everything logged so far
+    #[allow(clippy::missing_panics_doc)]
+    pub fn contents(&self) -> String {
+        let bytes = self.0.lock().expect("logs lock").clone();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().expect("logs lock").extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 struct WsPublishedMessage {
     client_id: u16, // port
@@ -38,7 +68,21 @@ impl Debug for WsPublishedMessage {
     }
 }
 
-type PendingMessages = Arc<DashSet<Publish>>;
+/// Publishing client and request id, tells identical publishes apart
+type PublishKey = (u16, MessageId);
+type PendingMessages = Arc<DashMap<PublishKey, Publish>>;
+/// Every publish per topic, for fetches
+type MessageHistory = Arc<DashMap<Topic, Vec<ArchivedMessage>>>;
+
+#[derive(Clone)]
+struct ArchivedMessage {
+    publish: Publish,
+    /// Milliseconds since the epoch
+    published_at: i64,
+    /// Auth token of the publisher, fetches skip the requester's own
+    /// messages like the relay mailbox does
+    publisher: Option<String>,
+}
 type WsSender = Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, Message>>>;
 
 #[cfg(test)]
@@ -49,6 +93,7 @@ mod test {
             default_connection_opts,
             mock_connection_opts,
             Client,
+            ClientError,
             ConnectionHandler,
             LogHandler,
             NoopHandler,
@@ -88,7 +133,7 @@ mod test {
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
     async fn mock_relay() -> anyhow::Result<()> {
         init_tracing();
-        let _ = MockRelay::start().await?;
+        let relay = MockRelay::start().await?;
         let messages_1: MockMessages = Arc::new(std::sync::RwLock::new(VecDeque::new()));
         let handler_1 = EchoHandler {
             messages: messages_1.clone(),
@@ -136,6 +181,14 @@ mod test {
         yield_ms(100).await;
         let num_messages = { messages_1.read().expect("could not unlock").len() };
         assert_eq!(1, num_messages);
+
+        assert_eq!(2, client_1.fetch(topic.clone()).await?.len());
+        relay.fetch_has_more(true);
+        let result = client_1.fetch(topic.clone()).await;
+        assert!(matches!(
+            result,
+            Err(ClientError::FetchPageLimit(crate::MAX_FETCH_PAGES))
+        ));
         client_1.disconnect().await?;
         Ok(())
     }
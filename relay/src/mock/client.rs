@@ -1,16 +1,18 @@
 use {
-    super::{MockRelay, PendingMessages, WsPublishedMessage, WsSender},
-    crate::Topic,
+    super::{MessageHistory, MockRelay, PendingMessages, PublishKey, WsPublishedMessage, WsSender},
+    crate::{MessageIdGenerator, Topic},
     dashmap::DashSet,
-    reown_relay_client::MessageIdGenerator,
     reown_relay_rpc::{
         domain::{MessageId, SubscriptionId},
-        rpc::{Params, Payload, Publish},
+        rpc::{FetchResponse, Params, Payload, Publish},
     },
     std::{
         fmt::{Debug, Display},
         hash::{Hash, Hasher},
-        sync::Arc,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
         time::Duration,
     },
     tokio::sync::broadcast::Receiver,
@@ -21,10 +23,17 @@ use {
 #[derive(Clone)]
 pub struct WsClient {
     pub id: u16,
+    token: Option<String>,
     topics: Arc<DashSet<Topic>>,
     ws_sender: WsSender,
     generator: MessageIdGenerator,
     pending: PendingMessages,
+    history: MessageHistory,
+    redeliver: Arc<AtomicBool>,
+    fetch_has_more: Arc<AtomicBool>,
+    /// Publishes forwarded to this client, one can be both pending when it
+    /// subscribes and broadcast after
+    delivered: Arc<DashSet<PublishKey>>,
     // sent: SentMessages,
 }
 
@@ -54,17 +63,26 @@ impl Debug for WsClient {
 }
 
 impl WsClient {
+    pub fn ws_sender(&self) -> WsSender {
+        self.ws_sender.clone()
+    }
+
     fn fmt_common(&self) -> String {
         format!("[wsclient-{}]({})", self.id, self.topics.len())
     }
 
-    pub fn new(relay: &MockRelay, id: u16, ws_sender: WsSender) -> Self {
+    pub fn new(relay: &MockRelay, id: u16, token: Option<String>, ws_sender: WsSender) -> Self {
         let me = Self {
             id,
+            token,
             ws_sender,
             topics: Arc::new(DashSet::new()),
             generator: relay.generator.clone(),
             pending: relay.pending.clone(),
+            history: relay.history.clone(),
+            redeliver: relay.redeliver.clone(),
+            fetch_has_more: relay.fetch_has_more.clone(),
+            delivered: Arc::new(DashSet::new()),
         };
         let listener = me.clone();
         tokio::spawn(listener.handle_message(relay.tx.subscribe()));
@@ -82,15 +100,15 @@ impl WsClient {
             }
             let id = published_message.payload.id();
             if published_message.client_id == self.id {
-                self.handle_own_message(id, published_message);
+                self.handle_own_message(id, &published_message);
                 continue;
             }
-            self.handle_published_message(id, published_message);
+            self.handle_published_message(id, &published_message);
         }
     }
 
     #[tracing::instrument(level = Level::DEBUG, skip(published_message))]
-    fn handle_published_message(&self, id: MessageId, published_message: WsPublishedMessage) {
+    fn handle_published_message(&self, id: MessageId, published_message: &WsPublishedMessage) {
         match &published_message.payload {
             Payload::Request(ref req) => {
                 if let Params::Publish(ref p) = req.params {
@@ -101,22 +119,27 @@ impl WsClient {
                         );
                         return;
                     }
-                    self.pending.remove(p);
+                    let key = (published_message.client_id, id);
+                    self.pending.remove(&key);
                     debug!(
                         "forwarding request from client_id:{} with",
                         published_message.client_id,
                     );
-                    self.send_message(vec![p.clone()]);
+                    self.send_message(vec![(key, p.clone())]);
                 } else {
                     debug!("not handling message");
                 }
             }
             Payload::Response(res) => debug!("not handling response payload {:?}", res),
-        };
+        }
     }
 
-    fn send_message(&self, messages: Vec<Publish>) {
-        for p in messages {
+    fn send_message(&self, messages: Vec<(PublishKey, Publish)>) {
+        for (key, p) in messages {
+            if !self.delivered.insert(key) {
+                debug!("{self} already got this message");
+                continue;
+            }
             let forward_id = self.generator.next();
             let now = chrono::Utc::now().timestamp();
             let subscription_id = SubscriptionId::from(p.topic.as_ref());
@@ -131,21 +154,24 @@ impl WsClient {
     }
 
     fn check_pending(&self, topic: &Topic) {
-        let to_send: Vec<Publish> = self
+        if !self.redeliver.load(Ordering::SeqCst) {
+            return;
+        }
+        let to_send: Vec<(PublishKey, Publish)> = self
             .pending
             .iter()
             .filter(|m| m.topic == *topic)
-            .map(|m| m.clone())
+            .map(|m| (*m.key(), m.value().clone()))
             .collect();
         debug!("found {} to send", to_send.len());
-        for p in &to_send {
-            self.pending.remove(p);
+        for (key, _) in &to_send {
+            self.pending.remove(key);
         }
         self.send_message(to_send);
     }
 
     #[tracing::instrument(level = Level::DEBUG)]
-    fn handle_own_message(&self, id: MessageId, published_message: WsPublishedMessage) {
+    fn handle_own_message(&self, id: MessageId, published_message: &WsPublishedMessage) {
         debug!("handle my own message");
         match published_message.payload {
             Payload::Request(ref req) => match &req.params {
@@ -172,14 +198,33 @@ impl WsClient {
                     tokio::spawn(MockRelay::handle_ack(id, self.ws_sender.clone(), true));
                     self.topics.remove(&s.topic);
                 }
-                Params::Publish(p) => {
+                Params::Publish(_) => {
                     debug!("responding to my own published message");
-                    self.pending.insert(p.clone());
                     tokio::spawn(MockRelay::handle_ack(id, self.ws_sender.clone(), true));
                 }
+                Params::FetchMessages(f) => {
+                    let messages = self
+                        .history
+                        .get(&f.topic)
+                        .map(|h| {
+                            h.iter()
+                                .filter(|m| m.publisher != self.token)
+                                .map(|m| {
+                                    let sub_id = SubscriptionId::from(m.publish.topic.as_ref());
+                                    m.publish.as_subscription(sub_id, m.published_at).data
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    let response = FetchResponse {
+                        messages,
+                        has_more: self.fetch_has_more.load(Ordering::SeqCst),
+                    };
+                    tokio::spawn(MockRelay::handle_ack(id, self.ws_sender.clone(), response));
+                }
                 _ => {}
             },
             Payload::Response(_) => {}
-        };
+        }
     }
 }
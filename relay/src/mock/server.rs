@@ -1,20 +1,53 @@
 use {
-    super::{client::WsClient, PendingMessages, WsPublishedMessage},
-    crate::MOCK_RELAY_ADDRESS,
-    dashmap::{DashMap, DashSet},
+    super::{
+        client::WsClient,
+        ArchivedMessage,
+        MessageHistory,
+        PendingMessages,
+        PublishKey,
+        WsPublishedMessage,
+        WsSender,
+    },
+    crate::{MessageIdGenerator, MOCK_RELAY_ADDRESS},
+    dashmap::DashMap,
     futures_util::{stream::SplitSink, SinkExt, StreamExt},
-    reown_relay_client::MessageIdGenerator,
     reown_relay_rpc::{
         domain::MessageId,
-        rpc::{Payload, Response, SuccessfulResponse},
+        rpc::{
+            self,
+            ErrorData,
+            ErrorResponse,
+            Params,
+            Payload,
+            Publish,
+            PublishError,
+            Response,
+            SuccessfulResponse,
+        },
     },
     serde::Serialize,
-    std::{fmt::Debug, net::SocketAddr, sync::Arc, time::Duration},
+    std::{
+        fmt::Debug,
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
     tokio::{
         net::{TcpListener, TcpStream},
         sync::Mutex,
     },
-    tokio_tungstenite::{accept_async, tungstenite::Message, WebSocketStream},
+    tokio_tungstenite::{
+        accept_hdr_async,
+        tungstenite::{
+            handshake::server::{Request, Response as HandshakeResponse},
+            protocol::{frame::coding::CloseCode, CloseFrame},
+            Message,
+        },
+        WebSocketStream,
+    },
     tracing::{debug, error, info, Level},
 };
 
@@ -23,8 +56,14 @@ use {
 pub struct MockRelay {
     pub(super) clients: Arc<DashMap<u16, WsClient>>,
     pub(super) pending: PendingMessages,
+    pub(super) history: MessageHistory,
+    pub(super) redeliver: Arc<AtomicBool>,
+    pub(super) fetch_has_more: Arc<AtomicBool>,
     pub(super) tx: tokio::sync::broadcast::Sender<WsPublishedMessage>,
     pub(super) generator: MessageIdGenerator,
+    rate_limited: Arc<AtomicUsize>,
+    auth_tokens: Arc<std::sync::Mutex<Vec<String>>>,
+    publishes: Arc<std::sync::Mutex<Vec<Publish>>>,
 }
 
 impl Debug for MockRelay {
@@ -41,9 +80,15 @@ impl MockRelay {
         let (tx, _rx) = tokio::sync::broadcast::channel::<WsPublishedMessage>(100);
         let me = Self {
             clients: Arc::new(DashMap::new()),
-            pending: Arc::new(DashSet::new()),
+            pending: Arc::new(DashMap::new()),
+            history: Arc::new(DashMap::new()),
+            redeliver: Arc::new(AtomicBool::new(true)),
+            fetch_has_more: Arc::new(AtomicBool::new(false)),
             tx,
             generator: MessageIdGenerator::new(),
+            rate_limited: Arc::new(AtomicUsize::new(0)),
+            auth_tokens: Arc::new(std::sync::Mutex::new(Vec::new())),
+            publishes: Arc::new(std::sync::Mutex::new(Vec::new())),
         };
 
         tokio::spawn(Self::run(me.clone(), listener));
@@ -67,6 +112,110 @@ impl MockRelay {
         }
     }
 
+    /// Auth tokens presented by clients, in connection order
+    #[allow(clippy::missing_panics_doc)]
+    pub fn auth_tokens(&self) -> Vec<String> {
+        self.auth_tokens.lock().expect("auth tokens lock").clone()
+    }
+
+    /// Every publish received, in arrival order, including rate limited ones
+    #[allow(clippy::missing_panics_doc)]
+    pub fn publishes(&self) -> Vec<Publish> {
+        self.publishes.lock().expect("publishes lock").clone()
+    }
+
+    /// Answer the next `count` publishes with a "too many requests" error
+    /// instead of relaying them
+    pub fn rate_limit_publishes(&self, count: usize) {
+        self.rate_limited.store(count, Ordering::SeqCst);
+    }
+
+    /// Whether messages published while nobody was subscribed are sent to
+    /// the next subscriber, on by default. They can always be fetched.
+    pub fn redeliver_on_subscribe(&self, enabled: bool) {
+        self.redeliver.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Answer every fetch with `has_more` set, like a relay whose mailbox
+    /// never drains
+    pub fn fetch_has_more(&self, enabled: bool) {
+        self.fetch_has_more.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Relay a request from client `client_id` to every client, publishes
+    /// are recorded and may be rate limited
+    fn handle_payload(
+        &self,
+        payload: Payload,
+        client_id: u16,
+        token: Option<&String>,
+        ws_sender: &WsSender,
+    ) {
+        match &payload {
+            Payload::Request(req) => {
+                if let Params::Publish(publish) = &req.params {
+                    self.publishes
+                        .lock()
+                        .expect("publishes lock")
+                        .push(publish.clone());
+                    if self.take_rate_limit() {
+                        debug!("rate limiting publish from {client_id}");
+                        let error = rpc::Error::<PublishError>::TooManyRequests.into();
+                        tokio::spawn(Self::handle_error(req.id, ws_sender.clone(), error));
+                        return;
+                    }
+                    self.archive((client_id, req.id), publish, token.cloned());
+                }
+                let msg = WsPublishedMessage {
+                    client_id,
+                    payload,
+                    close: false,
+                };
+                debug!("broadcast payload from client id {client_id}");
+                let _ = self.tx.send(msg);
+            }
+            Payload::Response(response) => {
+                debug!("recv response {:?}", response);
+            }
+        }
+    }
+
+    /// Keep `publish` for subscribers yet to come and for fetches. Done
+    /// before the broadcast, so a client subscribing right after finds it
+    /// whichever order the client tasks run in.
+    fn archive(&self, key: PublishKey, publish: &Publish, publisher: Option<String>) {
+        self.pending.insert(key, publish.clone());
+        self.history
+            .entry(publish.topic.clone())
+            .or_default()
+            .push(ArchivedMessage {
+                publish: publish.clone(),
+                published_at: chrono::Utc::now().timestamp_millis(),
+                publisher,
+            });
+    }
+
+    /// Consume one of the publishes set by [`Self::rate_limit_publishes`]
+    fn take_rate_limit(&self) -> bool {
+        self.rate_limited
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Close every client connection with the websocket close `code`
+    pub async fn close_all(&self, code: u16, reason: &str) {
+        let senders: Vec<_> = self.clients.iter().map(|c| c.ws_sender()).collect();
+        for sender in senders {
+            let frame = CloseFrame {
+                code: CloseCode::from(code),
+                reason: reason.to_owned().into(),
+            };
+            if let Err(e) = sender.lock().await.send(Message::Close(Some(frame))).await {
+                error!("failed to close client {e}");
+            }
+        }
+    }
+
     #[allow(clippy::missing_panics_doc)]
     pub async fn forward(
         payload: Payload,
@@ -100,14 +249,48 @@ impl MockRelay {
         }
     }
 
+    #[allow(clippy::missing_panics_doc)]
+    pub async fn handle_error(
+        id: MessageId,
+        ws_sender: Arc<Mutex<SplitSink<WebSocketStream<TcpStream>, Message>>>,
+        error: ErrorData,
+    ) {
+        let payload: Payload = Payload::Response(Response::Error(ErrorResponse::new(id, error)));
+        let payload = serde_json::to_string(&payload).expect("never");
+        let mut ws_sender = ws_sender.lock().await;
+        if ws_sender.send(Message::text(&payload)).await.is_err() {
+            error!("client has closed connection");
+        }
+    }
+
     /// Handles individual WebSocket connections.
     #[tracing::instrument(level = Level::INFO, skip(stream, addr))]
     async fn handle_connection(&self, stream: tokio::net::TcpStream, addr: SocketAddr) {
-        match accept_async(stream).await {
+        let auth_tokens = self.auth_tokens.clone();
+        let client_token = Arc::new(std::sync::Mutex::new(None));
+        let handshake_token = client_token.clone();
+        // the error type is the `ErrorResponse` tungstenite's `Callback` expects
+        #[allow(clippy::result_large_err)]
+        let record_auth = move |req: &Request, res: HandshakeResponse| {
+            let token = req
+                .uri()
+                .query()
+                .and_then(|q| q.split('&').find_map(|kv| kv.strip_prefix("auth=")));
+            if let Some(token) = token {
+                auth_tokens
+                    .lock()
+                    .expect("auth tokens lock")
+                    .push(token.to_owned());
+                *handshake_token.lock().expect("client token lock") = Some(token.to_owned());
+            }
+            Ok(res)
+        };
+        match accept_hdr_async(stream, record_auth).await {
             Ok(ws_stream) => {
                 let (ws_sender, mut ws_receiver) = ws_stream.split();
                 let ws_sender = Arc::new(Mutex::new(ws_sender));
-                let ws_client = WsClient::new(self, addr.port(), ws_sender);
+                let token = client_token.lock().expect("client token lock").take();
+                let ws_client = WsClient::new(self, addr.port(), token.clone(), ws_sender.clone());
                 info!("created new ws client {ws_client}");
                 self.clients.insert(ws_client.id, ws_client);
                 while let Some(msg) = ws_receiver.next().await {
@@ -116,27 +299,17 @@ impl MockRelay {
                             if !msg.is_text() {
                                 continue;
                             }
-                            let payload =
-                                serde_json::from_str::<Payload>(msg.to_text().expect("no"));
-                            match payload {
-                                Ok(payload) => match &payload {
-                                    Payload::Request(_) => {
-                                        let msg = WsPublishedMessage {
-                                            client_id: addr.port(),
-                                            payload,
-                                            close: false,
-                                        };
-                                        debug!("broadcast payload from client id {}", addr.port());
-                                        let _ = self.tx.send(msg);
-                                    }
-                                    Payload::Response(response) => {
-                                        debug!("recv response {:?}", response);
-                                    }
-                                },
+                            match serde_json::from_str::<Payload>(msg.to_text().expect("no")) {
+                                Ok(payload) => self.handle_payload(
+                                    payload,
+                                    addr.port(),
+                                    token.as_ref(),
+                                    &ws_sender,
+                                ),
                                 Err(e) => {
                                     error!("invalid payload {e}");
                                 }
-                            };
+                            }
                         }
                         Err(e) => {
                             error!("WebSocket error: {e}");
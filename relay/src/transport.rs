@@ -0,0 +1,106 @@
+use {
+    crate::{Client, ConnectionHandler, ConnectionOptions, Message, Result, SubscriptionId, Topic},
+    async_trait::async_trait,
+    std::{sync::Arc, time::Duration},
+};
+
+/// Publish/subscribe access to a relay. Inbound messages and connection
+/// changes go to the [`ConnectionHandler`] the transport was created with,
+/// see [`TransportFactory`].
+///
+/// [`Client`], the WebSocket relay client, is the default implementation.
+#[async_trait]
+pub trait Transport: Send + Sync + 'static {
+    async fn connect(&self, opts: &ConnectionOptions) -> Result<()>;
+
+    async fn disconnect(&self) -> Result<()>;
+
+    /// Publishes `message` on `topic`, the relay keeps it for `ttl`
+    async fn publish(
+        &self,
+        topic: Topic,
+        message: Arc<str>,
+        tag: u32,
+        ttl: Duration,
+        prompt: bool,
+    ) -> Result<()>;
+
+    /// Resolves with the subscription id once the relay acknowledges it
+    async fn subscribe(&self, topic: Topic) -> Result<SubscriptionId>;
+
+    /// Like [`Self::subscribe`] for many topics, ids are in the order of
+    /// `topics`
+    async fn batch_subscribe(&self, topics: Vec<Topic>) -> Result<Vec<SubscriptionId>>;
+
+    async fn unsubscribe(&self, topic: Topic) -> Result<()>;
+
+    /// Messages the relay still holds for `topic`, delivered or not.
+    /// Transports without a mailbox return none.
+    async fn fetch(&self, _topic: Topic) -> Result<Vec<Message>> {
+        Ok(Vec::new())
+    }
+}
+
+pub type SharedTransport = Arc<dyn Transport>;
+
+/// Creates a [`Transport`] that reports to `handler`
+pub trait TransportFactory: Send + Sync + 'static {
+    fn create(&self, handler: Box<dyn ConnectionHandler>) -> SharedTransport;
+}
+
+impl<F> TransportFactory for F
+where
+    F: Fn(Box<dyn ConnectionHandler>) -> SharedTransport + Send + Sync + 'static,
+{
+    fn create(&self, handler: Box<dyn ConnectionHandler>) -> SharedTransport {
+        self(handler)
+    }
+}
+
+/// Creates WebSocket [`Client`]s
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WebSocketTransport;
+
+impl TransportFactory for WebSocketTransport {
+    fn create(&self, handler: Box<dyn ConnectionHandler>) -> SharedTransport {
+        Arc::new(Client::new(handler))
+    }
+}
+
+#[async_trait]
+impl Transport for Client {
+    async fn connect(&self, opts: &ConnectionOptions) -> Result<()> {
+        Self::connect(self, opts).await
+    }
+
+    async fn disconnect(&self) -> Result<()> {
+        Self::disconnect(self).await
+    }
+
+    async fn publish(
+        &self,
+        topic: Topic,
+        message: Arc<str>,
+        tag: u32,
+        ttl: Duration,
+        prompt: bool,
+    ) -> Result<()> {
+        Self::publish(self, topic, message, tag, ttl, prompt).await
+    }
+
+    async fn subscribe(&self, topic: Topic) -> Result<SubscriptionId> {
+        Self::subscribe(self, topic).await
+    }
+
+    async fn batch_subscribe(&self, topics: Vec<Topic>) -> Result<Vec<SubscriptionId>> {
+        Self::batch_subscribe(self, topics).await
+    }
+
+    async fn unsubscribe(&self, topic: Topic) -> Result<()> {
+        Self::unsubscribe(self, topic).await
+    }
+
+    async fn fetch(&self, topic: Topic) -> Result<Vec<Message>> {
+        Self::fetch(self, topic).await
+    }
+}
@@ -1,21 +1,33 @@
 use {
     crate::{
-        actors::{AddRequest, ClearPairing},
+        actors::{AddRequest, CancelRequest, ClearPairing, RegisteredComponents},
         rpc::Response,
     },
     dashmap::DashMap,
     monedero_domain::MessageId,
-    monedero_relay::MessageIdGenerator,
+    monedero_relay::MessageIdSource,
     std::sync::Arc,
     tokio::sync::oneshot,
     tracing::{debug, error, warn},
     xtra::{Context, Handler},
 };
 
-#[derive(Default, xtra::Actor)]
+/// `None` is sent to a request that was cancelled
+type PendingResponse = oneshot::Sender<Option<Response>>;
+
+#[derive(xtra::Actor)]
 pub struct InboundResponseActor {
-    pending: Arc<DashMap<MessageId, oneshot::Sender<Response>>>,
-    generator: MessageIdGenerator,
+    pending: Arc<DashMap<MessageId, PendingResponse>>,
+    generator: Arc<dyn MessageIdSource>,
+}
+
+impl InboundResponseActor {
+    pub(crate) fn new(generator: Arc<dyn MessageIdSource>) -> Self {
+        Self {
+            pending: Arc::new(DashMap::new()),
+            generator,
+        }
+    }
 }
 
 impl Handler<ClearPairing> for InboundResponseActor {
@@ -27,16 +39,41 @@ impl Handler<ClearPairing> for InboundResponseActor {
 }
 
 impl Handler<AddRequest> for InboundResponseActor {
-    type Return = (MessageId, oneshot::Receiver<Response>);
+    type Return = (MessageId, oneshot::Receiver<Option<Response>>);
 
     async fn handle(&mut self, _message: AddRequest, _ctx: &mut Context<Self>) -> Self::Return {
         let id = self.generator.next();
-        let (tx, rx) = oneshot::channel::<Response>();
+        let (tx, rx) = oneshot::channel::<Option<Response>>();
         self.pending.insert(id, tx);
         (id, rx)
     }
 }
 
+impl Handler<CancelRequest> for InboundResponseActor {
+    /// Whether the request was still awaiting its response
+    type Return = bool;
+
+    async fn handle(&mut self, message: CancelRequest, _ctx: &mut Context<Self>) -> Self::Return {
+        let Some((_, tx)) = self.pending.remove(&message.0) else {
+            return false;
+        };
+        let _ = tx.send(None);
+        true
+    }
+}
+
+impl Handler<RegisteredComponents> for InboundResponseActor {
+    type Return = usize;
+
+    async fn handle(
+        &mut self,
+        _message: RegisteredComponents,
+        _ctx: &mut Context<Self>,
+    ) -> Self::Return {
+        self.pending.len()
+    }
+}
+
 impl Handler<Response> for InboundResponseActor {
     type Return = ();
 
@@ -44,7 +81,7 @@ impl Handler<Response> for InboundResponseActor {
         debug!("handing a response with message id {}", message.id);
         if let Some((_, tx)) = self.pending.remove(&message.id) {
             let id = message.id;
-            if tx.send(message).is_err() {
+            if tx.send(Some(message)).is_err() {
                 warn!("oneshot channel for id {} hash closed", id);
             }
             return;
@@ -9,15 +9,24 @@ mod transport;
 pub use {
     crate::actors::session::SessionRequestHandlerActor,
     inbound::InboundResponseActor,
-    request::RequestHandlerActor,
+    request::{Interceptor, RequestHandlerActor},
     transport::TransportActor,
 };
 use {
-    crate::{actors::proposal::ProposalActor, rpc::RequestParams, Result},
+    crate::{
+        actors::{proposal::ProposalActor, request::Interceptors},
+        rpc::RequestParams,
+        session::{SessionEvent, SESSION_EVENTS_CAPACITY},
+        Result,
+    },
     monedero_cipher::Cipher,
-    monedero_domain::Topic,
-    monedero_relay::Client,
-    std::fmt::{Display, Formatter},
+    monedero_domain::{MessageId, Topic},
+    monedero_relay::{MessageIdSource, SharedTransport},
+    std::{
+        fmt::{Display, Formatter},
+        sync::Arc,
+    },
+    tokio::sync::broadcast,
     xtra::{Actor, Address, Mailbox},
 };
 
@@ -28,14 +37,23 @@ pub struct Actors {
     transport_actor: Address<TransportActor>,
     session_actor: Address<SessionRequestHandlerActor>,
     proposal_actor: Address<ProposalActor>,
+    session_events: broadcast::Sender<SessionEvent>,
+    interceptors: Interceptors,
 }
 
 pub struct ClearPairing;
 pub struct Unsubscribe(pub Topic);
-pub struct SendRequest(pub(crate) Topic, pub(crate) RequestParams);
-pub struct SessionPing;
+pub struct SendRequest(
+    pub(crate) Topic,
+    pub(crate) RequestParams,
+    /// Overrides the method's IRN ttl (seconds)
+    pub(crate) Option<u64>,
+);
 pub struct AddRequest;
 pub struct ClearSession(pub Topic);
+/// Stop waiting for the response to a request, see
+/// [`crate::ClientSession::cancel_request`]
+pub struct CancelRequest(pub MessageId);
 
 impl Display for SendRequest {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -48,11 +66,18 @@ impl Display for SendRequest {
     }
 }
 
-/// Get number of sessions/pair managers are active
+/// Get number of sessions/pair managers are active, or of requests awaiting
+/// a response for [`InboundResponseActor`]
 pub struct RegisteredComponents;
 
+/// Get the actors of the active sessions
+pub struct ActiveSessions;
+
+/// Get a [`crate::session::SessionDebug`] of a session
+pub struct DebugSession;
+
 impl Actors {
-    pub(crate) async fn register_client(&self, relay: Client) -> Result<()> {
+    pub(crate) async fn register_client(&self, relay: SharedTransport) -> Result<()> {
         let _ = self.request_actor.send(relay).await?;
         Ok(())
     }
@@ -69,21 +94,26 @@ where
 }
 
 impl Actors {
-    pub(crate) fn init(cipher: Cipher) -> Self {
-        let inbound_response_actor = actor_spawn(InboundResponseActor::default());
+    pub(crate) fn init(cipher: Cipher, message_ids: Arc<dyn MessageIdSource>) -> Self {
+        let inbound_response_actor = actor_spawn(InboundResponseActor::new(message_ids));
         let transport_actor = actor_spawn(TransportActor::new(
             cipher.clone(),
             inbound_response_actor.clone(),
         ));
+        let (session_events, _) = broadcast::channel(SESSION_EVENTS_CAPACITY);
         let session_actor = actor_spawn(SessionRequestHandlerActor::new(
             transport_actor.clone(),
-            cipher,
+            cipher.clone(),
+            session_events.clone(),
         ));
         let proposal_actor = actor_spawn(ProposalActor::new(transport_actor.clone()));
+        let interceptors = Interceptors::default();
         let request_actor = actor_spawn(RequestHandlerActor::new(
             transport_actor.clone(),
             session_actor.clone(),
             proposal_actor.clone(),
+            cipher,
+            interceptors.clone(),
         ));
 
         Self {
@@ -92,6 +122,8 @@ impl Actors {
             transport_actor,
             session_actor,
             proposal_actor,
+            session_events,
+            interceptors,
         }
     }
 }
@@ -116,4 +148,12 @@ impl Actors {
     pub fn proposal(&self) -> Address<ProposalActor> {
         self.proposal_actor.clone()
     }
+
+    pub fn session_events(&self) -> broadcast::Receiver<SessionEvent> {
+        self.session_events.subscribe()
+    }
+
+    pub(crate) fn register_interceptor(&self, method: &str, interceptor: Interceptor) {
+        self.interceptors.insert(String::from(method), interceptor);
+    }
 }
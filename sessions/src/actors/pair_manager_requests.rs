@@ -5,6 +5,7 @@ use {
         spawn_task,
         PairingManager,
         Result,
+        ShortTopic,
         Topic,
     },
     monedero_domain::MessageId,
@@ -20,7 +21,9 @@ impl RequestHandlerActor {
             if let Err(err) = me.responder.send(resp).await {
                 warn!(
                     "Failed to send response for id {} on topic {} {}",
-                    id, topic, err
+                    id,
+                    ShortTopic(&topic),
+                    err
                 );
             }
         });
@@ -40,7 +43,7 @@ impl RequestHandlerActor {
         let mgr = self
             .pair_managers
             .as_ref()
-            .ok_or(crate::Error::NoPairManager(topic.clone()))?;
+            .ok_or_else(|| crate::SessionError::NoPairManager(topic.clone()))?;
         let response: RpcResponse = mgr.send(request).await.map(|r| RpcResponse {
             id,
             topic: topic.clone(),
@@ -1,6 +1,7 @@
 use {
     crate::{
         actors::{actor_spawn, TransportActor},
+        dapp::PeerSettlement,
         rpc::{
             ErrorParams,
             IntoUnknownError,
@@ -9,7 +10,9 @@ use {
             RpcRequest,
             RpcResponse,
         },
+        wallet::PairingProposal,
         Dapp,
+        ShortTopic,
         Wallet,
     },
     monedero_domain::SessionSettled,
@@ -57,7 +60,9 @@ impl ProposalActor {
         if let Err(err) = self.responder.send(resp).await {
             warn!(
                 "Failed to send response for id {} on topic {} {}",
-                id, topic, err
+                id,
+                ShortTopic(&topic),
+                err
             );
         }
     }
@@ -83,7 +88,7 @@ impl Handler<RpcRequest> for ProposalActor {
                         unknown
                     }
                     Some(wallet) => wallet
-                        .send(args)
+                        .send(PairingProposal(topic.clone(), args))
                         .await
                         .map(|payload| RpcResponse { id, topic, payload })
                         .unwrap_or(unknown),
@@ -97,10 +102,13 @@ impl Handler<RpcRequest> for ProposalActor {
                         unknown
                     }
                     Some(dapp) => dapp
-                        .send(SessionSettled {
-                            topic: topic.clone(),
-                            namespaces: args.namespaces,
-                            expiry: args.expiry,
+                        .send(PeerSettlement {
+                            settled: SessionSettled {
+                                topic: topic.clone(),
+                                namespaces: args.namespaces,
+                                expiry: args.expiry,
+                            },
+                            peer: args.controller.metadata,
                         })
                         .await
                         .map(|payload| RpcResponse { id, topic, payload })
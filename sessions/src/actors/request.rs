@@ -7,23 +7,37 @@ use {
             RegisteredComponents,
             TransportActor,
         },
-        rpc::{Request, RequestParams, RpcRequest},
+        rpc::{Request, RequestParams, ResponseParamsError, RpcRequest, RpcResponse, SdkErrors},
         spawn_task,
         PairingManager,
         Result,
+        ShortTopic,
+    },
+    dashmap::DashMap,
+    monedero_cipher::Cipher,
+    monedero_domain::Topic,
+    monedero_relay::SharedTransport,
+    std::{
+        fmt::{Debug, Formatter},
+        sync::Arc,
     },
-    monedero_relay::Client,
-    std::fmt::{Debug, Formatter},
     tracing::{debug, warn},
     xtra::prelude::*,
 };
 
+/// Custom handling of an inbound request, see
+/// [`crate::PairingManager::register_interceptor`]
+pub type Interceptor = Arc<dyn Fn(RpcRequest) -> Option<RpcResponse> + Send + Sync>;
+pub type Interceptors = Arc<DashMap<String, Interceptor>>;
+
 #[derive(Clone, Actor)]
 pub struct RequestHandlerActor {
     pub(super) pair_managers: Option<Address<PairingManager>>,
     pub(super) responder: Address<TransportActor>,
     session_handler: Address<SessionRequestHandlerActor>,
     proposal_handler: Address<ProposalActor>,
+    cipher: Cipher,
+    interceptors: Interceptors,
 }
 impl Debug for RequestHandlerActor {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -56,10 +70,10 @@ impl Handler<PairingManager> for RequestHandlerActor {
     }
 }
 
-impl Handler<Client> for RequestHandlerActor {
+impl Handler<SharedTransport> for RequestHandlerActor {
     type Return = Result<()>;
 
-    async fn handle(&mut self, message: Client, _ctx: &mut Context<Self>) -> Self::Return {
+    async fn handle(&mut self, message: SharedTransport, _ctx: &mut Context<Self>) -> Self::Return {
         self.send_client(message).await
     }
 }
@@ -69,16 +83,35 @@ impl RequestHandlerActor {
         responder: Address<TransportActor>,
         session_handler: Address<SessionRequestHandlerActor>,
         proposal_handler: Address<ProposalActor>,
+        cipher: Cipher,
+        interceptors: Interceptors,
     ) -> Self {
         Self {
             pair_managers: None,
             responder,
             session_handler,
             proposal_handler,
+            cipher,
+            interceptors,
         }
     }
 
-    pub(crate) async fn send_client(&self, relay: Client) -> Result<()> {
+    /// Proposals are only valid on the pairing topic, settlements only on the
+    /// session topic derived from the proposal.
+    fn misrouted(&self, topic: &Topic, params: &RequestParams) -> Option<ResponseParamsError> {
+        let on_pairing = self.cipher.is_pairing_topic(topic);
+        match params {
+            RequestParams::SessionPropose(_) if !on_pairing => Some(
+                ResponseParamsError::SessionPropose(SdkErrors::InvalidMethod.into()),
+            ),
+            RequestParams::SessionSettle(_) if on_pairing => Some(
+                ResponseParamsError::SessionSettle(SdkErrors::InvalidSessionSettleRequest.into()),
+            ),
+            _ => None,
+        }
+    }
+
+    pub(crate) async fn send_client(&self, relay: SharedTransport) -> Result<()> {
         Ok(self.responder.send(relay).await?)
     }
 }
@@ -91,6 +124,24 @@ impl Handler<RpcRequest> for RequestHandlerActor {
         let id = message.payload.id;
         let topic = message.topic.clone();
         debug!("handing request {id}");
+        if let Some(err) = self.misrouted(&topic, &message.payload.params) {
+            warn!(
+                "rejecting {} on unexpected topic {}",
+                message.payload.params,
+                ShortTopic(&topic)
+            );
+            self.send_response(RpcResponse::unknown(id, topic, err));
+            return;
+        }
+        let interceptor = self
+            .interceptors
+            .get(message.payload.params.method())
+            .map(|i| Arc::clone(i.value()));
+        if let Some(response) = interceptor.and_then(|i| i(message.clone())) {
+            debug!("request {id} handled by interceptor");
+            self.send_response(response);
+            return;
+        }
         match message.payload.params {
             RequestParams::PairDelete(args) => {
                 self.handle_pair_mgr_request(id, topic.clone(), args).await;
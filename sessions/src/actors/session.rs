@@ -1,6 +1,6 @@
 use {
     crate::{
-        actors::{actor_spawn, ClearPairing, ClearSession, SessionPing, TransportActor},
+        actors::{actor_spawn, ActiveSessions, ClearPairing, ClearSession, TransportActor},
         rpc::{
             ErrorParams,
             RequestParams,
@@ -9,10 +9,12 @@ use {
             RpcRequest,
             RpcResponse,
             RpcResponsePayload,
+            SdkErrors,
         },
-        session::ClientSession,
+        session::{ClientSession, SessionEvent},
         spawn_task,
         RegisteredComponents,
+        ShortTopic,
         Topic,
     },
     dashmap::DashMap,
@@ -22,6 +24,7 @@ use {
         sync::Arc,
         time::Duration,
     },
+    tokio::sync::broadcast,
     tracing::{error, warn},
     xtra::prelude::*,
 };
@@ -32,6 +35,7 @@ pub struct SessionRequestHandlerActor {
     pub(super) sessions: Arc<DashMap<Topic, Address<ClientSession>>>,
     pub(super) responder: Address<TransportActor>,
     pub(super) cipher: Cipher,
+    pub(super) events: broadcast::Sender<SessionEvent>,
 }
 
 impl Debug for SessionRequestHandlerActor {
@@ -41,11 +45,16 @@ impl Debug for SessionRequestHandlerActor {
 }
 
 impl SessionRequestHandlerActor {
-    pub(crate) fn new(responder: Address<TransportActor>, cipher: Cipher) -> Self {
+    pub(crate) fn new(
+        responder: Address<TransportActor>,
+        cipher: Cipher,
+        events: broadcast::Sender<SessionEvent>,
+    ) -> Self {
         Self {
             sessions: Arc::new(DashMap::new()),
             responder,
             cipher,
+            events,
         }
     }
 }
@@ -78,8 +87,12 @@ impl Handler<ClientSession> for SessionRequestHandlerActor {
             .cipher
             .set_settlement(&topic, (*message.settled).clone())
         {
-            error!("failed to set settlement for {topic} error:{e}");
+            error!(
+                "failed to set settlement for {} error:{e}",
+                ShortTopic(&topic)
+            );
         }
+        self.emit(SessionEvent::Settled((*message.settled).clone()));
     }
 }
 
@@ -95,13 +108,25 @@ impl Handler<RegisteredComponents> for SessionRequestHandlerActor {
     }
 }
 
+impl Handler<ActiveSessions> for SessionRequestHandlerActor {
+    type Return = Vec<Address<ClientSession>>;
+
+    async fn handle(&mut self, _message: ActiveSessions, _ctx: &mut Context<Self>) -> Self::Return {
+        self.sessions.iter().map(|s| s.value().clone()).collect()
+    }
+}
+
 impl Handler<RpcRequest> for SessionRequestHandlerActor {
     type Return = ();
 
     #[tracing::instrument(skip(_ctx), level = "info", fields(message = message.to_string()))]
     async fn handle(&mut self, message: RpcRequest, _ctx: &mut Context<Self>) -> Self::Return {
         match message.payload.params {
-            RequestParams::SessionUpdate(_args) => {
+            RequestParams::SessionUpdate(args) => {
+                self.emit(SessionEvent::Updated(
+                    message.topic.clone(),
+                    args.namespaces,
+                ));
                 let response = RpcResponse {
                     id: message.payload.id,
                     topic: message.topic,
@@ -113,23 +138,29 @@ impl Handler<RpcRequest> for SessionRequestHandlerActor {
                     warn!("responder actor is not responding {e}");
                 }
             }
-            RequestParams::SessionExtend(_args) => {
-                let response = RpcResponse {
-                    id: message.payload.id,
-                    topic: message.topic,
-                    payload: RpcResponsePayload::Success(ResponseParamsSuccess::SessionExtend(
-                        true,
-                    )),
-                };
-                if let Err(e) = self.responder.send(response).await {
-                    warn!("responder actor is not responding {e}");
-                }
+            RequestParams::SessionExtend(args) => {
+                self.handle_session_extend(message.payload.id, message.topic, args)
+                    .await;
             }
             RequestParams::SessionRequest(args) => {
-                self.handle_session_request(message.payload.id, message.topic, args)
+                if self.is_authorized(&message.topic, &args) {
+                    self.handle_session_request(message.payload.id, message.topic, args)
+                        .await;
+                } else {
+                    warn!(
+                        "rejecting unauthorized request on {} {args}",
+                        ShortTopic(&message.topic)
+                    );
+                    self.send_response(RpcResponse::unknown(
+                        message.payload.id,
+                        message.topic,
+                        ResponseParamsError::SessionRequest(SdkErrors::UnauthorizedMethod.into()),
+                    ))
                     .await;
+                }
             }
-            RequestParams::SessionEvent(_args) => {
+            RequestParams::SessionEvent(args) => {
+                self.handle_session_event(message.topic.clone(), args).await;
                 let response = RpcResponse {
                     id: message.payload.id,
                     topic: message.topic,
@@ -161,27 +192,24 @@ impl Handler<RpcRequest> for SessionRequestHandlerActor {
                 });
             }
             RequestParams::SessionPing(()) => {
-                let unknown = RpcResponse::unknown(
-                    message.payload.id,
-                    message.topic.clone(),
-                    ResponseParamsError::SessionPing(ErrorParams::unknown()),
-                );
-
-                let response: RpcResponse = match self.sessions.get(&message.topic) {
-                    None => unknown,
-                    Some(cs) => cs
-                        .send(SessionPing)
-                        .await
-                        .map(|payload| RpcResponse {
-                            id: message.payload.id,
-                            topic: message.topic,
-                            payload,
-                        })
-                        .unwrap_or(unknown),
+                // answered here, so a session busy with a request or its
+                // handler cannot fail the peer's liveness check
+                let response = if self.sessions.contains_key(&message.topic) {
+                    RpcResponse {
+                        id: message.payload.id,
+                        topic: message.topic,
+                        payload: RpcResponsePayload::Success(ResponseParamsSuccess::SessionPing(
+                            true,
+                        )),
+                    }
+                } else {
+                    RpcResponse::unknown(
+                        message.payload.id,
+                        message.topic,
+                        ResponseParamsError::SessionPing(ErrorParams::unknown()),
+                    )
                 };
-                if let Err(e) = self.responder.send(response).await {
-                    warn!("responder actor is not responding {e}");
-                }
+                self.send_response(response).await;
             }
             _ => warn!(
                 "session request actor should not have received request {:#?}",
@@ -1,9 +1,21 @@
 use {
     crate::{
         actors::{SessionRequestHandlerActor, Unsubscribe},
-        rpc::{IntoUnknownError, RpcResponse, RpcResponsePayload},
+        rpc::{
+            IntoUnknownError,
+            ResponseParamsError,
+            ResponseParamsSuccess,
+            RpcResponse,
+            RpcResponsePayload,
+            SdkErrors,
+            SessionEventRequest,
+            SessionExtendRequest,
+            SessionRequestRequest,
+        },
+        session::SessionEvent,
         ClientSession,
         Result,
+        ShortTopic,
         Topic,
     },
     monedero_domain::MessageId,
@@ -17,7 +29,9 @@ impl SessionRequestHandlerActor {
         if let Err(err) = self.responder.send(resp).await {
             warn!(
                 "Failed to send response for id {} on topic {} {}",
-                id, topic, err
+                id,
+                ShortTopic(&topic),
+                err
             );
         }
     }
@@ -37,7 +51,7 @@ impl SessionRequestHandlerActor {
         let mgr = self
             .sessions
             .get(&topic)
-            .ok_or(crate::Error::NoClientSession(topic.clone()))?;
+            .ok_or_else(|| crate::SessionError::NoClientSession(topic.clone()))?;
         let response: RpcResponse = mgr.send(request).await.map(|r| RpcResponse {
             id,
             topic: topic.clone(),
@@ -47,12 +61,109 @@ impl SessionRequestHandlerActor {
         Ok(())
     }
 
+    /// Publish to [`SessionEvent`] subscribers, having none is not an error
+    pub(super) fn emit(&self, event: SessionEvent) {
+        let _ = self.events.send(event);
+    }
+
     pub(super) async fn handle_session_delete(&self, topic: Topic) {
-        self.sessions.remove(&topic);
+        let removed = self.sessions.remove(&topic).is_some();
         if let Err(e) = self.responder.send(Unsubscribe(topic.clone())).await {
-            warn!("failed to unsubscribe to {topic} '{e}'");
+            warn!("failed to unsubscribe to {} '{e}'", ShortTopic(&topic));
         }
         let _ = self.cipher.delete_session(&topic);
+        // subscribers see the session gone from storage as well
+        if removed {
+            self.emit(SessionEvent::Deleted(topic));
+        }
+    }
+
+    /// Forward a peer event to its session and publish any namespace change
+    pub(super) async fn handle_session_event(&self, topic: Topic, event: SessionEventRequest) {
+        let Some(session) = self.sessions.get(&topic).map(|s| s.clone()) else {
+            warn!("no session for event on topic {}", ShortTopic(&topic));
+            return;
+        };
+        match session.send(event).await {
+            Ok(Some(settled)) => {
+                if let Err(e) = self.cipher.set_settlement(&topic, settled.clone()) {
+                    warn!(
+                        "failed to store updated settlement for {} error:{e}",
+                        ShortTopic(&topic)
+                    );
+                }
+                self.emit(SessionEvent::Updated(topic, settled.namespaces));
+            }
+            Ok(None) => {}
+            Err(e) => warn!(
+                "failed to send event to session {} '{e}'",
+                ShortTopic(&topic)
+            ),
+        }
+    }
+
+    /// Whether the request's method was granted in the settled namespace of
+    /// its chain; sessions without a stored settlement authorize nothing
+    pub(super) fn is_authorized(&self, topic: &Topic, args: &SessionRequestRequest) -> bool {
+        match self.cipher.settlement(topic) {
+            Ok(Some(settled)) => settled
+                .namespaces
+                .authorizes(&args.chain_id, &args.request.method),
+            Ok(None) => false,
+            Err(e) => {
+                warn!(
+                    "failed to load settlement for {} error:{e}",
+                    ShortTopic(topic)
+                );
+                false
+            }
+        }
+    }
+
+    /// Store the peer's new expiry, rejecting expiries in the past or beyond
+    /// [`crate::rpc::MAX_SESSION_EXTEND_SECS`]
+    pub(super) async fn handle_session_extend(
+        &self,
+        id: MessageId,
+        topic: Topic,
+        args: SessionExtendRequest,
+    ) {
+        let now = u64::try_from(chrono::Utc::now().timestamp()).unwrap_or_default();
+        let payload = match self.extend_settlement(&topic, &args, now) {
+            Ok(expiry) => {
+                self.emit(SessionEvent::Extended(topic.clone(), expiry));
+                RpcResponsePayload::Success(ResponseParamsSuccess::SessionExtend(true))
+            }
+            Err(e) => {
+                warn!(
+                    "rejecting extend to {} for {}: {e}",
+                    args.expiry,
+                    ShortTopic(&topic)
+                );
+                RpcResponsePayload::Error(ResponseParamsError::SessionExtend(
+                    SdkErrors::InvalidExtendRequest.into(),
+                ))
+            }
+        };
+        self.send_response(RpcResponse { id, topic, payload }).await;
+    }
+
+    fn extend_settlement(
+        &self,
+        topic: &Topic,
+        args: &SessionExtendRequest,
+        now: u64,
+    ) -> Result<i64> {
+        if !args.is_valid(now) {
+            return Err(crate::rpc::ParamsError::InvalidExpiry(args.expiry).into());
+        }
+        let mut settled = self
+            .cipher
+            .settlement(topic)?
+            .ok_or_else(|| crate::SessionError::NoClientSession(topic.clone()))?;
+        settled.expiry = i64::try_from(args.expiry).unwrap_or(i64::MAX);
+        self.cipher.set_settlement(topic, settled)?;
+        Ok(i64::try_from(args.expiry).unwrap_or(i64::MAX))
     }
 
     pub(super) async fn handle_session_request<M>(&self, id: MessageId, topic: Topic, request: M)
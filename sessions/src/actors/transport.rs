@@ -1,6 +1,13 @@
 use {
     crate::{
-        actors::{AddRequest, ClearPairing, InboundResponseActor, SendRequest, Unsubscribe},
+        actors::{
+            AddRequest,
+            CancelRequest,
+            ClearPairing,
+            InboundResponseActor,
+            SendRequest,
+            Unsubscribe,
+        },
         rpc::{
             IrnMetadata,
             RelayProtocolMetadata,
@@ -11,10 +18,11 @@ use {
         },
         spawn_task,
         Result,
+        ShortTopic,
     },
     monedero_cipher::Cipher,
     monedero_domain::MessageId,
-    monedero_relay::Client,
+    monedero_relay::SharedTransport,
     std::{
         fmt::{Debug, Formatter},
         sync::Arc,
@@ -28,7 +36,7 @@ use {
 #[derive(Clone, xtra::Actor)]
 pub struct TransportActor {
     cipher: Cipher,
-    relay: Option<Client>,
+    relay: Option<SharedTransport>,
     inbound_response_actor: Address<InboundResponseActor>,
 }
 
@@ -50,7 +58,7 @@ impl Handler<ClearPairing> for TransportActor {
     }
 }
 
-async fn send_response(result: RpcResponse, cipher: Cipher, relay: Client) {
+async fn send_response(result: RpcResponse, cipher: Cipher, relay: SharedTransport) {
     let irn_metadata: IrnMetadata = match &result.payload {
         RpcResponsePayload::Success(s) => s.irn_metadata(),
         RpcResponsePayload::Error(e) => e.irn_metadata(),
@@ -89,7 +97,7 @@ async fn send_response(result: RpcResponse, cipher: Cipher, relay: Client) {
             {
                 error!(
                     "failed to publish payload  error: '{e}' on topic {}",
-                    result.topic
+                    ShortTopic(&result.topic)
                 );
             }
         }
@@ -97,7 +105,7 @@ async fn send_response(result: RpcResponse, cipher: Cipher, relay: Client) {
             error!("failed to encrypt payload {err}");
             debug!("failed encrypting {:#?}", response);
         }
-    };
+    }
 }
 
 impl TransportActor {
@@ -117,15 +125,23 @@ impl Handler<Unsubscribe> for TransportActor {
     type Return = Result<()>;
 
     async fn handle(&mut self, message: Unsubscribe, _ctx: &mut Context<Self>) -> Self::Return {
-        let relay = self.relay.as_ref().ok_or(crate::Error::NoClient)?;
+        let relay = self.relay.as_ref().ok_or(crate::TransportError::NoClient)?;
         Ok(relay.unsubscribe(message.0).await?)
     }
 }
 
-impl Handler<Client> for TransportActor {
+impl Handler<CancelRequest> for TransportActor {
+    type Return = Result<bool>;
+
+    async fn handle(&mut self, message: CancelRequest, _ctx: &mut Context<Self>) -> Self::Return {
+        Ok(self.inbound_response_actor.send(message).await?)
+    }
+}
+
+impl Handler<SharedTransport> for TransportActor {
     type Return = ();
 
-    async fn handle(&mut self, message: Client, _ctx: &mut Context<Self>) -> Self::Return {
+    async fn handle(&mut self, message: SharedTransport, _ctx: &mut Context<Self>) -> Self::Return {
         self.relay = Some(message);
     }
 }
@@ -134,7 +150,7 @@ impl Handler<RpcResponse> for TransportActor {
     type Return = Result<()>;
 
     async fn handle(&mut self, message: RpcResponse, _ctx: &mut Context<Self>) -> Self::Return {
-        let relay = self.relay.clone().ok_or(crate::Error::NoClient)?;
+        let relay = self.relay.clone().ok_or(crate::TransportError::NoClient)?;
         let cipher = self.cipher.clone();
         spawn_task(async move {
             send_response(message, cipher, relay).await;
@@ -144,17 +160,21 @@ impl Handler<RpcResponse> for TransportActor {
 }
 
 impl Handler<SendRequest> for TransportActor {
-    type Return = Result<(MessageId, Duration, oneshot::Receiver<Response>)>;
+    type Return = Result<(MessageId, Duration, oneshot::Receiver<Option<Response>>)>;
 
     #[tracing::instrument(skip(_ctx), level = "info", fields(message = message.to_string()))]
     async fn handle(&mut self, message: SendRequest, _ctx: &mut Context<Self>) -> Self::Return {
-        let relay = self.relay.as_ref().ok_or(crate::Error::NoClient)?;
+        let relay = self.relay.as_ref().ok_or(crate::TransportError::NoClient)?;
         let (id, rx) = self.inbound_response_actor.send(AddRequest).await?;
 
         let topic = message.0;
         let params = message.1;
-        let irn_metadata = params.irn_metadata();
+        let mut irn_metadata = params.irn_metadata();
+        if let Some(ttl) = message.2 {
+            irn_metadata = irn_metadata.with_ttl(ttl);
+        }
         let request = Request::new(id, params);
+        crate::wire::outbound_request(&topic, &request);
         let encrypted = self.cipher.encode(&topic, &request)?;
         let ttl = Duration::from_secs(irn_metadata.ttl);
         relay
@@ -1,39 +1,81 @@
 mod session_settle;
 
+pub use session_settle::PeerSettlement;
 use {
     crate::{
-        rpc::{Metadata, RequestParams, SessionProposeRequest, SessionProposeResponse},
+        rpc::{
+            Metadata,
+            RelayProtocol,
+            RequestParams,
+            SessionProposeRequest,
+            SessionProposeResponse,
+        },
         session::{Category, PendingSession},
         spawn_task,
-        Error::NoPairingTopic,
         PairingManager,
         ProposeFuture,
         Result,
+        SessionError::NoPairingTopic,
         SessionHandler,
     },
-    monedero_domain::{namespaces::Namespaces, Pairing, PairingTopic, SessionSettled},
+    monedero_domain::{
+        namespaces::{NamespaceDefaults, Namespaces},
+        Pairing,
+        PairingTopic,
+        SessionSettled,
+    },
     std::{
         fmt::{Debug, Display, Formatter},
+        str::FromStr,
         sync::Arc,
     },
+    tokio::sync::mpsc,
     tracing::{error, info},
-    x25519_dalek::PublicKey,
+    x25519_dalek::{PublicKey, StaticSecret},
 };
 
+/// Whether [`Dapp::propose`] mints a new proposer key for every proposal, see
+/// [`crate::ReownBuilder::proposer_key_policy`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProposerKeyPolicy {
+    /// Fresh key per proposal, so wallets cannot link proposals together
+    #[default]
+    Ephemeral,
+    /// Reuse one key for every proposal, persisted so it survives restarts.
+    /// It is kept apart from the pairings, each proposal still gets a fresh
+    /// symKey as the pairing URI discloses it.
+    Persistent,
+}
+
+/// Progress of a proposal made with [`Dapp::propose_with_progress`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStage {
+    /// The relay accepted the proposal, the wallet has yet to answer
+    ProposalSent,
+    /// The wallet answered the proposal, approving or rejecting it
+    WalletResponded,
+    /// The session is settled, the [`ProposeFuture`] is about to resolve
+    Settled,
+}
+
 #[derive(Clone, xtra::Actor)]
 pub struct Dapp {
     manager: PairingManager,
     pending: Arc<PendingSession>,
     md: Metadata,
+    defaults: Arc<NamespaceDefaults>,
+    /// Set under [`ProposerKeyPolicy::Persistent`]
+    proposer_key: Option<StaticSecret>,
 }
 
 fn common_display(dapp: &Dapp) -> String {
     format!(
         "{} pairing:{}",
         dapp.md.name,
-        dapp.manager
-            .topic()
-            .map_or("unknown".to_string(), |topic| crate::shorten_topic(&topic))
+        dapp.manager.topic().map_or_else(
+            || "unknown".to_string(),
+            |topic| crate::shorten_topic(&topic)
+        )
     )
 }
 
@@ -49,40 +91,98 @@ impl Debug for Dapp {
     }
 }
 
-async fn await_settlement_response(dapp: &Dapp, params: RequestParams) -> Result<()> {
+async fn await_settlement_response(
+    dapp: &Dapp,
+    topic: &PairingTopic,
+    params: RequestParams,
+) -> Result<()> {
     let response = dapp
         .manager
-        .publish_request::<SessionProposeResponse>(params)
+        .topic_transport()
+        .publish_request_notify::<SessionProposeResponse>(topic.clone(), params, None, |_| {
+            dapp.pending.progress(topic, ProposalStage::ProposalSent);
+        })
+        .await;
+    if matches!(response, Ok(_) | Err(crate::Error::RpcError(_))) {
+        dapp.pending.progress(topic, ProposalStage::WalletResponded);
+    }
+    dapp.manager
+        .register_wallet_pk(topic, response?, dapp.proposer_key.as_ref())
         .await?;
-    dapp.manager.register_wallet_pk(response).await?;
     Ok(())
 }
 
 #[tracing::instrument(skip(topic, params), level = "debug")]
 async fn begin_settlement_flow(dapp: Dapp, topic: PairingTopic, params: RequestParams) {
-    if let Err(e) = await_settlement_response(&dapp, params).await {
+    if let Err(e) = await_settlement_response(&dapp, &topic, params).await {
         dapp.pending.error(&topic, e);
     }
 }
 
-fn public_key(pairing: &Pairing) -> String {
-    let pk = PublicKey::from(&pairing.params.sym_key);
+/// Pairing for a new proposal, always with a fresh symKey as it is shared in
+/// the pairing URI
+fn new_pairing(relay_data: Option<String>) -> Pairing {
+    let mut pairing = Pairing::default();
+    pairing.params.relay_data = relay_data;
+    pairing
+}
+
+/// The stored proposer key, else a new one. Stored for the next restart.
+fn load_proposer_key(manager: &PairingManager) -> Result<StaticSecret> {
+    let ciphers = manager.ciphers();
+    let key = ciphers
+        .proposer_key()?
+        .unwrap_or_else(|| StaticSecret::random_from_rng(rand::thread_rng()));
+    ciphers.set_proposer_key(&key)?;
+    Ok(key)
+}
+
+/// Public key the proposal offers, the persistent proposer key if there is
+/// one, else the key of `pairing`
+fn public_key(proposer_key: Option<&StaticSecret>, pairing: &Pairing) -> String {
+    let pk = PublicKey::from(proposer_key.unwrap_or(&pairing.params.sym_key));
     data_encoding::HEXLOWER_PERMISSIVE.encode(pk.as_bytes())
 }
 
 async fn finalize_restore(dapp: Dapp, settled: SessionSettled) -> Result<()> {
+    let pairing_topic = dapp.manager.topic().ok_or(NoPairingTopic)?;
     dapp.pending
-        .settled(&dapp.manager, settled, Category::Dapp, None)
+        .settled(
+            &dapp.manager,
+            &pairing_topic,
+            settled,
+            Metadata::default(),
+            Category::Dapp,
+            None,
+        )
         .await?;
     Ok(())
 }
 
 impl Dapp {
     pub async fn new(manager: PairingManager, md: Metadata) -> Result<Self> {
+        Self::with_namespace_defaults(manager, md, NamespaceDefaults::default()).await
+    }
+
+    /// Like [`Self::new`], with the methods and events [`Self::propose`]
+    /// requests per namespace, e.g. only `solana_signMessage` for a dapp that
+    /// never sends transactions
+    pub async fn with_namespace_defaults(
+        manager: PairingManager,
+        md: Metadata,
+        defaults: NamespaceDefaults,
+    ) -> Result<Self> {
+        let proposer_key = match manager.proposer_key_policy() {
+            ProposerKeyPolicy::Persistent => Some(load_proposer_key(&manager)?),
+            ProposerKeyPolicy::Ephemeral => None,
+        };
+        let pending = PendingSession::with_timeout(manager.proposal_timeout());
         let me = Self {
             manager,
-            pending: Arc::new(PendingSession::new()),
+            pending: Arc::new(pending),
             md,
+            defaults: Arc::new(defaults),
+            proposer_key,
         };
         me.manager.actors().proposal().send(me.clone()).await?;
         Ok(me)
@@ -96,11 +196,14 @@ impl Dapp {
         &self,
         settlement: SessionSettled,
         handlers: T,
+        progress: Option<mpsc::UnboundedSender<ProposalStage>>,
     ) -> Result<(Pairing, ProposeFuture)> {
         info!("dapp session restore");
 
         let pairing = self.manager.pairing().ok_or(NoPairingTopic)?;
-        let rx = self.pending.add(pairing.topic.clone(), handlers);
+        let rx = self
+            .pending
+            .add_with_progress(pairing.topic.clone(), handlers, progress);
         let dapp = self.clone();
         spawn_task(async move {
             if let Err(e) = finalize_restore(dapp, settlement).await {
@@ -115,6 +218,10 @@ impl Dapp {
     /// Reference spec: [https://specs.walletconnect.com/2.0/specs/clients/core/pairing]
     /// This function will restore sessions if there is a matching namespace
     /// session Otherwise new pairing session will be established
+    ///
+    /// `chains` are grouped by namespace, each getting the default methods
+    /// and events of its own namespace. Methods and events of namespaces
+    /// configured in [`Self::with_namespace_defaults`] replace those.
     #[tracing::instrument(level = "debug", skip(handlers, chains))]
     pub async fn propose<T>(
         &self,
@@ -124,36 +231,91 @@ impl Dapp {
     where
         T: SessionHandler,
     {
-        let namespaces: Namespaces = chains.into();
+        self.propose_inner(handlers, chains, None).await
+    }
+
+    /// Like [`Self::propose`], reporting each [`ProposalStage`] to `progress`
+    /// so a UI can tell a proposal still in flight from one awaiting the
+    /// wallet. A restored session only reports [`ProposalStage::Settled`].
+    pub async fn propose_with_progress<T>(
+        &self,
+        handlers: T,
+        chains: impl Into<Namespaces> + Send,
+        progress: mpsc::UnboundedSender<ProposalStage>,
+    ) -> Result<(Pairing, ProposeFuture, bool)>
+    where
+        T: SessionHandler,
+    {
+        self.propose_inner(handlers, chains, Some(progress)).await
+    }
+
+    async fn propose_inner<T>(
+        &self,
+        handlers: T,
+        chains: impl Into<Namespaces> + Send,
+        progress: Option<mpsc::UnboundedSender<ProposalStage>>,
+    ) -> Result<(Pairing, ProposeFuture, bool)>
+    where
+        T: SessionHandler,
+    {
+        let mut namespaces: Namespaces = chains.into();
+        self.defaults.apply(&mut namespaces);
 
         if let Some(settled) = self.manager.find_session(&namespaces) {
-            let (p, cs) = self.restore_session(settled, handlers)?;
+            let (p, cs) = self.restore_session(settled, handlers, progress)?;
             return Ok((p, cs, true));
         }
 
         // reset pairing topic to something new
         // normally I would preserve the topic, but buggy walletconnect servers don't
         // handle same pairing session
-        let pairing = Pairing::default();
+        let pairing = new_pairing(self.manager.relay_data());
         self.manager.set_pairing(pairing.clone()).await?;
-        let rx = self.pending.add(pairing.topic.clone(), handlers);
-        let pk = public_key(&pairing);
-        let params = RequestParams::SessionPropose(SessionProposeRequest::new(
-            self.md.clone(),
-            pk,
-            namespaces,
-            None,
-        ));
+        let rx = self
+            .pending
+            .add_with_progress(pairing.topic.clone(), handlers, progress);
+        let pk = public_key(self.proposer_key.as_ref(), &pairing);
+        let relay = RelayProtocol {
+            protocol: pairing.params.relay_protocol.clone(),
+            data: pairing.params.relay_data.clone(),
+        };
+        let params = RequestParams::SessionPropose(
+            SessionProposeRequest::new(self.md.clone(), pk, namespaces, None).with_relay(relay),
+        );
         let dapp = self.clone();
         let topic = pairing.topic.clone();
         spawn_task(async move { begin_settlement_flow(dapp, topic, params).await });
         Ok((pairing, ProposeFuture::new(rx), false))
     }
 
+    /// Pair with an existing pairing URI without sending a new proposal
+    ///
+    /// Useful when the URI was persisted elsewhere (or handed over by the
+    /// wallet). The pairing is set in the cipher store, the pairing topic is
+    /// subscribed and the returned future resolves once a session is settled
+    #[tracing::instrument(level = "debug", skip(handlers))]
+    pub async fn pair_existing<T: SessionHandler>(
+        &self,
+        uri: &str,
+        handlers: T,
+    ) -> Result<ProposeFuture> {
+        let pairing = Pairing::from_str(uri)?;
+        let topic = pairing.topic.clone();
+        self.manager.set_pairing(pairing).await?;
+        let rx = self.pending.add(topic, handlers);
+        Ok(ProposeFuture::new(rx))
+    }
+
     pub fn pairing(&self) -> Option<Pairing> {
         self.manager.pairing()
     }
 
+    /// Proposals still awaiting settlement, see
+    /// [`crate::ReownBuilder::proposal_timeout`]
+    pub fn pending_proposals(&self) -> usize {
+        self.pending.count()
+    }
+
     pub async fn extend(&self, expire: u64) -> Result<bool> {
         self.manager.extend(expire).await
     }
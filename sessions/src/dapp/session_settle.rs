@@ -1,28 +1,44 @@
 use {
     crate::{
-        rpc::{ResponseParamsSuccess, RpcResponsePayload},
+        rpc::{Metadata, ResponseParamsSuccess, RpcResponsePayload},
         session::Category,
         Dapp,
         Result,
+        SessionError,
     },
     monedero_domain::SessionSettled,
     xtra::{Context, Handler},
 };
 
+/// Settlement received from the wallet, with the wallet's metadata
+pub struct PeerSettlement {
+    pub settled: SessionSettled,
+    pub peer: Metadata,
+}
+
 impl Dapp {
-    async fn process_settlement(&self, settled: SessionSettled) -> Result<()> {
+    async fn process_settlement(&self, message: PeerSettlement) -> Result<()> {
+        message.settled.namespaces.validate_accounts()?;
+        let pairing_topic = self.manager.topic().ok_or(SessionError::NoPairingTopic)?;
         self.pending
-            .settled(&self.manager, settled, Category::Dapp, None)
+            .settled(
+                &self.manager,
+                &pairing_topic,
+                message.settled,
+                message.peer,
+                Category::Dapp,
+                None,
+            )
             .await?;
         Ok(())
     }
 }
 
-impl Handler<SessionSettled> for Dapp {
+impl Handler<PeerSettlement> for Dapp {
     type Return = RpcResponsePayload;
 
     #[cfg(not(target_family = "wasm"))]
-    async fn handle(&mut self, message: SessionSettled, _ctx: &mut Context<Self>) -> Self::Return {
+    async fn handle(&mut self, message: PeerSettlement, _ctx: &mut Context<Self>) -> Self::Return {
         use crate::rpc::ResponseParamsError;
 
         match self.process_settlement(message).await {
@@ -37,7 +53,7 @@ impl Handler<SessionSettled> for Dapp {
     }
 
     #[cfg(target_family = "wasm")]
-    async fn handle(&mut self, message: SessionSettled, _ctx: &mut Context<Self>) -> Self::Return {
+    async fn handle(&mut self, message: PeerSettlement, _ctx: &mut Context<Self>) -> Self::Return {
         let me = self.clone();
         crate::spawn_task(async move {
             if let Err(e) = me.process_settlement(message).await {
@@ -1,13 +1,20 @@
-use monedero_domain::Topic;
+pub use {monedero_cipher::CipherError, monedero_relay::ClientError};
+use {monedero_domain::Topic, std::time::Duration};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+
+    #[error(transparent)]
+    Session(#[from] SessionError),
+
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+
     #[error("Failed to receive the proposed value")]
     ReceiveError,
 
-    #[error("client is not initialized")]
-    NoClient,
-
     #[error("failed to get mutex lock")]
     LockError,
 
@@ -17,36 +24,82 @@ pub enum Error {
     #[error(transparent)]
     ActorSendError(#[from] xtra::Error),
 
-    #[error(transparent)]
-    ConnectError(#[from] monedero_relay::ClientError),
-
     #[error(transparent)]
     CorruptedPacket(#[from] serde_json::error::Error),
 
-    #[error("no session account")]
-    NoSessionAccount,
-
     #[error("failed to generate jwt key")]
     JwtError,
 
     #[error(transparent)]
-    CipherError(#[from] monedero_cipher::CipherError),
+    StorageError(#[from] monedero_store::Error),
+
+    #[error("RPC error {0:#?}")]
+    RpcError(serde_json::Value),
 
     #[error(transparent)]
-    StorageError(#[from] monedero_store::Error),
+    ParamsError(#[from] crate::rpc::ParamsError),
 
-    #[error("Timeout waiting for session settlement")]
-    SessionSettlementTimeout,
+    #[error("This error goes back to the origninal request")]
+    RpcErrorFromRequest(crate::rpc::RpcErrorResponse),
+
+    #[error(transparent)]
+    PairingParseError(#[from] monedero_domain::pairing_uri::ParseError),
+
+    #[error(transparent)]
+    InvalidAccount(#[from] monedero_domain::namespaces::AccountError),
+
+    #[error(transparent)]
+    InvalidNamespace(#[from] monedero_domain::namespaces::NamespaceError),
+}
+
+/// Relay connection and request/response delivery errors
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, thiserror::Error)]
+pub enum TransportError {
+    #[error("client is not initialized")]
+    NoClient,
+
+    #[error(transparent)]
+    Connect(Box<ClientError>),
+
+    /// The relay refused a publish for exceeding its rate limit, see
+    /// [`crate::ReownBuilder::retry_rate_limited`]
+    #[error("rate limited by the relay, retry after {retry_after:?}")]
+    RateLimited { retry_after: Duration },
 
     #[error("Failed to recv response from request id: {0}")]
     ResponseChannelError(monedero_domain::MessageId),
 
-    #[error("Timeout waiting for session request")]
-    SessionRequestTimeout,
-
     #[error("Timeout waiting for response")]
     ResponseTimeout,
 
+    /// See [`crate::ClientSession::cancel_request`]
+    #[error("request {0} was cancelled")]
+    Cancelled(monedero_domain::MessageId),
+
+    #[error("Timeout waiting for relay to confirm subscription")]
+    SubscribeTimeout,
+
+    #[error("timeout occurred after {0}")]
+    WaitError(u32),
+
+    #[error("reconnects are paused, the relay keeps failing")]
+    CircuitOpen,
+}
+
+/// Pairing and session lifecycle errors
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("no session account")]
+    NoSessionAccount,
+
+    #[error("Timeout waiting for session settlement")]
+    SettlementTimeout,
+
+    #[error("Timeout waiting for session request")]
+    RequestTimeout,
+
     #[error("recv channel closed for settlement request")]
     SettlementRecvError,
 
@@ -56,11 +109,11 @@ pub enum Error {
     #[error("a party has rejected the settlement")]
     ProposalRejected,
 
-    #[error("Got session settlement but I have no one to send this to!")]
-    SessionSettlementNotFound,
+    #[error("gave up publishing the settlement: {0}")]
+    SettlementPublishFailed(String),
 
-    #[error("RPC error {0:#?}")]
-    RpcError(serde_json::Value),
+    #[error("Got session settlement but I have no one to send this to!")]
+    SettlementNotFound,
 
     #[error("No pairing topic available")]
     NoPairingTopic,
@@ -68,15 +121,6 @@ pub enum Error {
     #[error("No pending handler for settlement on pairing topic {0:#?}")]
     InvalidPendingHandler(Topic),
 
-    #[error(transparent)]
-    ParamsError(#[from] crate::rpc::ParamsError),
-
-    #[error("This error goes back to the origninal request")]
-    RpcErrorFromRequest(crate::rpc::RpcErrorResponse),
-
-    #[error(transparent)]
-    PairingParseError(#[from] monedero_domain::pairing_uri::ParseError),
-
     #[error("No wallet found to handle request on topic {0:#?}")]
     NoWalletHandler(Topic),
 
@@ -86,6 +130,66 @@ pub enum Error {
     #[error("No client session for {0:#?}")]
     NoClientSession(Topic),
 
-    #[error("timeout occurred after {0}")]
-    WaitError(u32),
+    #[error("peer did not accept deleting session {0}")]
+    DeleteRejected(Topic),
+
+    #[error("session has no {0} chain")]
+    NoChain(monedero_domain::namespaces::NamespaceName),
+
+    #[error("session has more than one {0} chain, use publish_request to pick one")]
+    AmbiguousChain(monedero_domain::namespaces::NamespaceName),
+
+    #[error("Session topic {0} is not subscribed. Was it never settled, or deleted?")]
+    NotSubscribed(Topic),
+
+    #[error("namespace update on {0} drops namespaces the dapp required")]
+    UpdateBelowRequired(Topic),
+
+    #[error("peer did not accept the namespace update on {0}")]
+    UpdateRejected(Topic),
+
+    /// A request was published on a session whose settlement is still in
+    /// flight, see [`crate::ClientSession::is_settled`]
+    #[error("session {0} is not settled yet")]
+    NotSettled(Topic),
+
+    /// No session was settled within
+    /// [`crate::ReownBuilder::proposal_timeout`]
+    #[error("proposal on pairing {0} was not settled in time")]
+    ProposalTimeout(Topic),
+}
+
+impl From<ClientError> for TransportError {
+    fn from(value: ClientError) -> Self {
+        match value {
+            ClientError::RateLimited { retry_after } => Self::RateLimited { retry_after },
+            value => Self::Connect(Box::new(value)),
+        }
+    }
+}
+
+impl From<ClientError> for Error {
+    fn from(value: ClientError) -> Self {
+        Self::Transport(value.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, monedero_cipher::Cipher, monedero_store::KvStorage, std::sync::Arc};
+
+    fn encode(cipher: &Cipher, topic: &Topic) -> crate::Result<String> {
+        Ok(cipher.encode(topic, &"payload")?)
+    }
+
+    #[test]
+    fn test_cipher_error() -> anyhow::Result<()> {
+        let cipher = Cipher::new(Arc::new(KvStorage::mem()), None)?;
+        let result = encode(&cipher, &Topic::generate());
+        assert!(matches!(
+            result,
+            Err(Error::Cipher(CipherError::UnknownTopic(_)))
+        ));
+        Ok(())
+    }
 }
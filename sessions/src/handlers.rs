@@ -2,18 +2,19 @@ use {
     crate::{
         rpc::{
             Event,
-            RelayProtocol,
             ResponseParamsSuccess,
             RpcResponsePayload,
             SessionDeleteRequest,
             SessionProposeRequest,
             SessionProposeResponse,
+            SessionProposeResponseBuilder,
             SessionRequestRequest,
         },
+        SdkErrors,
         SocketEvent,
     },
     async_trait::async_trait,
-    monedero_domain::namespaces::Namespaces,
+    monedero_domain::{namespaces::Namespaces, PairingTopic},
     serde_json::json,
 };
 
@@ -31,6 +32,9 @@ pub trait SessionEventHandler: Send + Sync + 'static {
 pub enum WalletRequestResponse {
     Success(serde_json::Value),
     Error(crate::rpc::SdkErrors),
+    /// Like [`Self::Error`] with `message` telling the dapp why, in place of
+    /// the standard message of the error's code
+    ErrorWithMessage(crate::rpc::SdkErrors, String),
 }
 
 #[async_trait]
@@ -38,22 +42,49 @@ pub trait SessionHandler: Send + Sync + 'static + SessionEventHandler {
     async fn request(&self, request: SessionRequestRequest) -> WalletRequestResponse;
 }
 
+/// What the wallet knows about a proposal besides the request itself. The
+/// dapp's metadata is in [`SessionProposeRequest::proposer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalContext {
+    /// Pairing the proposal arrived on
+    pub pairing_topic: PairingTopic,
+    /// Methods the pairing URI advertised, see
+    /// [`monedero_domain::Pairing::methods`]. `None` puts no constraint on
+    /// what the session may use.
+    pub pairing_methods: Option<Vec<String>>,
+}
+
 #[async_trait]
 pub trait WalletSettlementHandler: Send + Sync + 'static {
-    async fn settlement(&self, proposal: SessionProposeRequest)
-        -> Result<Namespaces, crate::Error>;
+    async fn settlement(
+        &self,
+        proposal: SessionProposeRequest,
+        context: ProposalContext,
+    ) -> Result<Namespaces, crate::Error>;
+
+    /// Shape the response the dapp receives for `proposal`, e.g. to carry
+    /// relay protocol data for a non default relay. `response` starts from
+    /// our pairing public key and the relay protocol the proposer offered.
+    fn propose_response(
+        &self,
+        _proposal: &SessionProposeRequest,
+        response: SessionProposeResponseBuilder,
+    ) -> SessionProposeResponseBuilder {
+        response
+    }
 
     async fn verify_settlement(
         &self,
-        _proposal: SessionProposeRequest,
+        proposal: SessionProposeRequest,
         pk: String,
     ) -> (bool, RpcResponsePayload) {
-        let result = RpcResponsePayload::Success(ResponseParamsSuccess::SessionPropose(
-            SessionProposeResponse {
-                relay: RelayProtocol::default(),
-                responder_public_key: pk,
-            },
-        ));
+        let response = self
+            .propose_response(
+                &proposal,
+                SessionProposeResponse::builder(pk).relay(proposal.relay()),
+            )
+            .build();
+        let result = RpcResponsePayload::Success(ResponseParamsSuccess::SessionPropose(response));
         (true, result)
     }
 }
@@ -77,6 +108,31 @@ impl SessionHandler for NoopSessionHandler {
     }
 }
 
+/// Answers every session request with an error, for peers that aren't
+/// expected to receive requests (e.g. a dapp). The requester gets an error
+/// response instead of a made up result.
+#[derive(Debug, Clone, Copy)]
+pub struct RejectingSessionHandler(pub SdkErrors);
+
+impl Default for RejectingSessionHandler {
+    fn default() -> Self {
+        Self(SdkErrors::UnsupportedMethods)
+    }
+}
+
+#[async_trait]
+impl SessionEventHandler for RejectingSessionHandler {}
+
+impl SocketListener for RejectingSessionHandler {}
+
+#[async_trait]
+impl SessionHandler for RejectingSessionHandler {
+    async fn request(&self, request: SessionRequestRequest) -> WalletRequestResponse {
+        tracing::info!("rejecting session request {request} with {:?}", self.0);
+        WalletRequestResponse::Error(self.0)
+    }
+}
+
 pub struct NoopSessionDeleteHandler;
 impl SessionDeleteHandler for NoopSessionDeleteHandler {}
 
@@ -9,17 +9,16 @@ pub mod session;
 mod transport;
 mod wait;
 mod wallet;
+mod wire;
 
-#[cfg(not(target_family = "wasm"))]
-pub use monedero_relay::MockRelay;
 pub use {
     crate::{
         rpc::{SessionProposeRequest, SessionRequestRequest},
-        session::ClientSession,
+        session::{ClientSession, SessionEvent},
     },
-    actors::{Actors, RegisteredComponents},
-    dapp::Dapp,
-    error::Error,
+    actors::{Actors, Interceptor, RegisteredComponents},
+    dapp::{Dapp, ProposalStage, ProposerKeyPolicy},
+    error::{CipherError, Error, SessionError, TransportError},
     handlers::*,
     monedero_domain as domain,
     monedero_relay::{
@@ -27,10 +26,20 @@ pub use {
         default_connection_opts,
         mock_connection_opts,
         ClientError,
+        ConnectionHandler,
+        ConnectionOptions,
+        MessageIdGenerator,
+        MessageIdSource,
+        RelayDisconnectReason,
+        SharedTransport,
+        ShortTopic,
+        Transport,
+        TransportFactory,
         AUTH_URL,
+        RATE_LIMITED_RETRY_AFTER,
     },
     monedero_store::{Error as KvStorageError, KvStorage},
-    pair::{PairingManager, ReownBuilder},
+    pair::{Health, MeshMetrics, PairingManager, ReownBuilder},
     rpc::{Metadata, SdkErrors},
     wallet::Wallet,
 };
@@ -47,13 +56,19 @@ use {
     tokio::sync::oneshot,
     tracing_subscriber::{fmt::format::FmtSpan, EnvFilter},
 };
+#[cfg(not(target_family = "wasm"))]
+pub use {
+    monedero_relay::{CapturedLogs, MockRelay},
+    pair::CircuitBreaker,
+};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub enum SocketEvent {
     Connected,
     #[default]
     Disconnect,
-    ForceDisconnect,
+    /// The relay closed the socket, a reconnect follows
+    ForceDisconnect(RelayDisconnectReason),
 }
 
 impl Display for SocketEvent {
@@ -65,8 +80,8 @@ impl Display for SocketEvent {
             Self::Disconnect => {
                 write!(f, "disconnected")
             }
-            Self::ForceDisconnect => {
-                write!(f, "force disconnect")
+            Self::ForceDisconnect(reason) => {
+                write!(f, "force disconnect ({reason})")
             }
         }
     }
@@ -108,11 +123,7 @@ pub enum SessionEventRequest {
 }
 
 pub(crate) fn shorten_topic(id: &Topic) -> String {
-    let mut id = format!("{id}");
-    if id.len() > 10 {
-        id = String::from(&id[0..9]);
-    }
-    id
+    ShortTopic(id).to_string()
 }
 
 pub fn init_tracing() {
@@ -1,18 +1,49 @@
 use {
-    crate::{auth_token, PairingManager, AUTH_URL},
-    monedero_cipher::Cipher,
-    monedero_domain::ProjectId,
-    monedero_relay::{ConnectionOptions, SerializedAuthToken},
+    super::rate_limit::RetryRateLimited,
+    crate::{auth_token, rpc::SESSION_PROPOSE_TTL, PairingManager, ProposerKeyPolicy, AUTH_URL},
+    monedero_cipher::{cipher::MAX_SESSIONS_PER_PAIRING, Cipher},
+    monedero_domain::{Pairing, ProjectId},
+    monedero_relay::{
+        ConnectionOptions,
+        MessageIdGenerator,
+        MessageIdSource,
+        SerializedAuthToken,
+        SharedTransport,
+        TransportFactory,
+        WebSocketTransport,
+    },
     monedero_store::KvStorage,
-    std::sync::Arc,
+    std::{num::NonZeroUsize, sync::Arc, time::Duration},
     tracing::warn,
 };
 
+/// Nonces remembered per topic when [`ReownBuilder::detect_nonce_reuse`] is
+/// enabled
+const SEEN_NONCES_PER_TOPIC: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct ReownBuilder {
     connect_opts: Option<ConnectionOptions>,
     auth: Option<SerializedAuthToken>,
     project_id: ProjectId,
     store: Option<KvStorage>,
+    detect_nonce_reuse: bool,
+    pub(super) sync_namespaces: bool,
+    pub(super) keepalive_interval: Option<Duration>,
+    pub(super) session_reaper_interval: Option<Duration>,
+    canonical_json: bool,
+    compress_threshold: Option<usize>,
+    max_sessions_per_pairing: usize,
+    pub(super) proposer_key_policy: ProposerKeyPolicy,
+    pub(super) relay_data: Option<String>,
+    pub(super) proposal_timeout: Duration,
+    retry_rate_limited: bool,
+    pub(super) fetch_on_reconnect: bool,
+    transport: Arc<dyn TransportFactory>,
+    pub(super) message_ids: Arc<dyn MessageIdSource>,
+    pub(super) preload_pairings: Vec<Pairing>,
+    #[cfg(not(target_family = "wasm"))]
+    pub(super) circuit_breaker: Option<(u32, Duration)>,
 }
 
 impl ReownBuilder {
@@ -22,6 +53,23 @@ impl ReownBuilder {
             auth: None,
             project_id,
             store: None,
+            detect_nonce_reuse: false,
+            sync_namespaces: false,
+            keepalive_interval: None,
+            session_reaper_interval: None,
+            canonical_json: false,
+            compress_threshold: None,
+            max_sessions_per_pairing: MAX_SESSIONS_PER_PAIRING,
+            proposer_key_policy: ProposerKeyPolicy::Ephemeral,
+            relay_data: None,
+            proposal_timeout: Duration::from_secs(SESSION_PROPOSE_TTL),
+            retry_rate_limited: false,
+            fetch_on_reconnect: false,
+            transport: Arc::new(WebSocketTransport),
+            message_ids: Arc::new(MessageIdGenerator::new()),
+            preload_pairings: Vec::new(),
+            #[cfg(not(target_family = "wasm"))]
+            circuit_breaker: None,
         }
     }
 
@@ -43,6 +91,154 @@ impl ReownBuilder {
         self
     }
 
+    /// Reject inbound messages that replay a nonce already seen on the same
+    /// topic. Off by default as it keeps recent nonces in memory.
+    #[must_use]
+    pub fn detect_nonce_reuse(mut self, enabled: bool) -> Self {
+        self.detect_nonce_reuse = enabled;
+        self
+    }
+
+    /// Apply `accountsChanged` and `chainChanged` session events from the peer
+    /// to [`crate::ClientSession::namespaces`], publishing
+    /// [`crate::SessionEvent::Updated`]. Events are still passed to the
+    /// [`crate::SessionEventHandler`] either way.
+    #[must_use]
+    pub fn sync_namespaces(mut self, enabled: bool) -> Self {
+        self.sync_namespaces = enabled;
+        self
+    }
+
+    /// Ping the pairing topic every `interval` until
+    /// [`PairingManager::shutdown`]. Off (`None`) by default.
+    #[must_use]
+    pub const fn keepalive_interval(mut self, interval: Option<Duration>) -> Self {
+        self.keepalive_interval = interval;
+        self
+    }
+
+    /// Every `interval` until [`PairingManager::shutdown`], send
+    /// `wc_sessionDelete` for sessions past their expiry and drop them,
+    /// publishing [`crate::SessionEvent::Deleted`]. Off (`None`) by default,
+    /// expired sessions are then only noticed on the next start.
+    #[must_use]
+    pub const fn session_reaper_interval(mut self, interval: Option<Duration>) -> Self {
+        self.session_reaper_interval = interval;
+        self
+    }
+
+    /// Serialize outbound payloads with sorted object keys, so peers verifying
+    /// signatures over the serialized bytes see a deterministic encoding. Off
+    /// by default.
+    #[must_use]
+    pub const fn canonical_json(mut self, enabled: bool) -> Self {
+        self.canonical_json = enabled;
+        self
+    }
+
+    /// Gzip outbound payloads longer than `bytes` before encrypting them. Off
+    /// by default, only enable it when the peer also uses this crate, see
+    /// [`Cipher::with_compress_threshold`].
+    #[must_use]
+    pub const fn compress_threshold(mut self, bytes: usize) -> Self {
+        self.compress_threshold = Some(bytes);
+        self
+    }
+
+    /// Reject new sessions with [`crate::CipherError::SessionLimitReached`]
+    /// once the pairing they are proposed on has `max` of them, sessions of
+    /// other pairings don't count. Defaults to [`MAX_SESSIONS_PER_PAIRING`].
+    #[must_use]
+    pub const fn max_sessions_per_pairing(mut self, max: usize) -> Self {
+        self.max_sessions_per_pairing = max;
+        self
+    }
+
+    /// Whether [`crate::Dapp::propose`] mints a new proposer key per proposal
+    /// or reuses one key, kept in the store across restarts.
+    /// [`ProposerKeyPolicy::Ephemeral`] by default, as a reused key lets
+    /// wallets correlate proposals.
+    #[must_use]
+    pub const fn proposer_key_policy(mut self, policy: ProposerKeyPolicy) -> Self {
+        self.proposer_key_policy = policy;
+        self
+    }
+
+    /// Relay protocol data carried by the pairings [`crate::Dapp::propose`]
+    /// creates, for relays that route on it. The wallet echoes it in its
+    /// response and settlement. `None` by default.
+    #[must_use]
+    pub fn relay_data(mut self, data: Option<String>) -> Self {
+        self.relay_data = data;
+        self
+    }
+
+    /// Fail a [`crate::ProposeFuture`] with
+    /// [`crate::SessionError::ProposalTimeout`] when no session is settled
+    /// within `timeout`, e.g. the wallet never scanned the pairing URI.
+    /// Defaults to the relay ttl of the proposal, [`SESSION_PROPOSE_TTL`].
+    #[must_use]
+    pub const fn proposal_timeout(mut self, timeout: Duration) -> Self {
+        self.proposal_timeout = timeout;
+        self
+    }
+
+    /// Retry a publish the relay rejected as rate limited once, after the
+    /// delay it asked for, instead of failing with
+    /// [`crate::TransportError::RateLimited`]. Off by default.
+    #[must_use]
+    pub const fn retry_rate_limited(mut self, enabled: bool) -> Self {
+        self.retry_rate_limited = enabled;
+        self
+    }
+
+    /// After reconnecting and resubscribing, fetch the relay mailbox of every
+    /// topic and handle the messages missed while offline, e.g. a
+    /// `wc_sessionDelete`. Payloads already handled are skipped. Off by
+    /// default.
+    #[must_use]
+    pub const fn fetch_on_reconnect(mut self, enabled: bool) -> Self {
+        self.fetch_on_reconnect = enabled;
+        self
+    }
+
+    /// Stop reconnecting for `cooldown` after `threshold` consecutive failed
+    /// reconnects, counted across every manager in the process, see
+    /// [`crate::CircuitBreaker`]. Off by default.
+    #[cfg(not(target_family = "wasm"))]
+    #[must_use]
+    pub const fn circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some((threshold, cooldown));
+        self
+    }
+
+    /// Relay transport to use instead of the WebSocket relay client, see
+    /// [`TransportFactory`]
+    #[must_use]
+    pub fn transport(mut self, factory: impl TransportFactory) -> Self {
+        self.transport = Arc::new(factory);
+        self
+    }
+
+    /// Ids for the requests this manager publishes, e.g. a fixed sequence to
+    /// correlate with captured relay traffic. [`MessageIdGenerator`] by
+    /// default.
+    #[must_use]
+    pub fn message_id_generator(mut self, generator: impl MessageIdSource) -> Self {
+        self.message_ids = Arc::new(generator);
+        self
+    }
+
+    /// Pairings kept outside the store, e.g. in a database, to add to the
+    /// cipher and subscribe to on [`Self::build`] instead of pairing again.
+    /// The first becomes [`PairingManager::pairing`] when none was saved, see
+    /// [`Cipher::add_pairing`]. Empty by default.
+    #[must_use]
+    pub fn preload_pairings(mut self, pairings: Vec<Pairing>) -> Self {
+        self.preload_pairings = pairings;
+        self
+    }
+
     pub async fn build(&self) -> crate::Result<PairingManager> {
         let auth: SerializedAuthToken = self.auth.as_ref().map_or_else(
             || {
@@ -69,7 +265,28 @@ impl ReownBuilder {
         let store = KvStorage::new();
 
         let store = Arc::new(store);
-        let cipher = Cipher::new(store, None)?;
-        PairingManager::init(opts, cipher).await
+        let mut cipher = Cipher::new(store, None)?
+            .with_canonical_json(self.canonical_json)
+            .with_max_sessions(self.max_sessions_per_pairing);
+        if self.detect_nonce_reuse {
+            cipher = cipher.with_nonce_guard(SEEN_NONCES_PER_TOPIC);
+        }
+        if let Some(threshold) = self.compress_threshold {
+            cipher = cipher.with_compress_threshold(threshold);
+        }
+        PairingManager::init(self, opts, cipher).await
+    }
+
+    /// [`Self::transport`], retrying rate limited publishes under
+    /// [`Self::retry_rate_limited`]
+    pub(super) fn transport_factory(&self) -> Arc<dyn TransportFactory> {
+        if self.retry_rate_limited {
+            let factory = self.transport.clone();
+            Arc::new(move |handler| -> SharedTransport {
+                Arc::new(RetryRateLimited::new(factory.create(handler)))
+            })
+        } else {
+            self.transport.clone()
+        }
     }
 }
@@ -0,0 +1,142 @@
+use std::{
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Reconnect failures shared by every [`crate::PairingManager`] in the process
+static PROCESS_STATE: OnceLock<Arc<Mutex<State>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Attempts allowed, counting consecutive failures
+    Closed(u32),
+    /// No attempts until the cooldown ends
+    Open(Instant),
+    /// A single probe attempt is in flight
+    HalfOpen,
+}
+
+/// Stops reconnect attempts against a relay that keeps failing.
+///
+/// After `threshold` consecutive failures the circuit opens and
+/// [`Self::allow`] refuses attempts for `cooldown`. Then one probe is let
+/// through: success closes the circuit, failure opens it again. The failure
+/// count is process-wide, so many managers reconnecting to the same relay
+/// back off together.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Arc<Mutex<State>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        let state = PROCESS_STATE.get_or_init(|| Arc::new(Mutex::new(State::Closed(0))));
+        Self::with_state(threshold, cooldown, state.clone())
+    }
+
+    fn with_state(threshold: u32, cooldown: Duration, state: Arc<Mutex<State>>) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            state,
+        }
+    }
+
+    /// Whether a connect attempt may be made now. Report its outcome with
+    /// [`Self::success`] or [`Self::failure`].
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match *state {
+            State::Closed(_) => true,
+            State::Open(until) if Instant::now() >= until => {
+                *state = State::HalfOpen;
+                true
+            }
+            State::Open(_) | State::HalfOpen => false,
+        }
+    }
+
+    /// When an open circuit lets the next probe through, `None` unless open
+    pub(crate) fn reopens_at(&self) -> Option<Instant> {
+        let state = *self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state {
+            State::Open(until) => Some(until),
+            State::Closed(_) | State::HalfOpen => None,
+        }
+    }
+
+    /// A breaker with its own state, unlike the process-wide one of
+    /// [`Self::new`]
+    #[cfg(test)]
+    pub(crate) fn isolated(threshold: u32, cooldown: Duration) -> Self {
+        Self::with_state(threshold, cooldown, Arc::new(Mutex::new(State::Closed(0))))
+    }
+
+    pub fn success(&self) {
+        *self.state.lock().unwrap_or_else(PoisonError::into_inner) = State::Closed(0);
+    }
+
+    pub fn failure(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        *state = match *state {
+            State::Closed(failures) if failures + 1 < self.threshold => State::Closed(failures + 1),
+            _ => State::Open(Instant::now() + self.cooldown),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let cooldown = Duration::from_millis(200);
+        let breaker =
+            CircuitBreaker::with_state(3, cooldown, Arc::new(Mutex::new(State::Closed(0))));
+        let mut attempts = 0;
+        let mut attempt = |breaker: &CircuitBreaker, succeed: bool| {
+            if breaker.allow() {
+                attempts += 1;
+                if succeed {
+                    breaker.success();
+                } else {
+                    breaker.failure();
+                }
+            }
+        };
+        for _ in 0..10 {
+            attempt(&breaker, false);
+        }
+        // tripped after the threshold, nothing while cooling down
+        assert!(!breaker.allow());
+
+        std::thread::sleep(cooldown);
+        // a single probe, which fails and reopens the circuit
+        attempt(&breaker, false);
+        attempt(&breaker, true);
+        assert!(!breaker.allow());
+
+        std::thread::sleep(cooldown);
+        attempt(&breaker, true);
+        attempt(&breaker, false);
+        attempt(&breaker, false);
+        // closed again, the count restarted
+        assert!(breaker.allow());
+        assert_eq!(3 + 1 + 3, attempts);
+    }
+
+    #[test]
+    fn test_circuit_breaker_is_process_wide() {
+        let first = CircuitBreaker::new(2, Duration::from_secs(60));
+        let second = CircuitBreaker::new(2, Duration::from_secs(60));
+        first.failure();
+        second.failure();
+        assert!(!first.allow());
+        assert!(!second.allow());
+        first.success();
+        assert!(second.allow());
+    }
+}
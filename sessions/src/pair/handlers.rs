@@ -9,6 +9,7 @@ use {
         },
         spawn_task,
         PairingManager,
+        ShortTopic,
         Topic,
     },
     std::time::Duration,
@@ -63,7 +64,7 @@ impl Handler<PairDeleteRequest> for PairingManager {
 
 impl PairingManager {
     pub(super) async fn cleanup(&self, pairing_topic: Topic) {
-        info!("deleting pairing topic {pairing_topic}");
+        info!("deleting pairing topic {}", ShortTopic(&pairing_topic));
         let _ = self.transport.unsubscribe(pairing_topic).await;
         let topics = self.ciphers.subscriptions();
         for t in topics {
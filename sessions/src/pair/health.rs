@@ -0,0 +1,87 @@
+use {
+    crate::{
+        actors::{ActiveSessions, DebugSession},
+        session::SessionDebug,
+        PairingManager,
+        Result,
+    },
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    std::sync::atomic::Ordering,
+};
+
+/// Snapshot of a [`PairingManager`] for liveness/readiness probes, see
+/// [`PairingManager::health`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    /// The relay socket is open
+    pub relay_connected: bool,
+    /// Session topics with a key, see
+    /// [`monedero_cipher::Cipher::session_count`]
+    pub active_sessions: usize,
+    pub pairing_present: bool,
+}
+
+/// Messages queued in each actor's mailbox, see [`PairingManager::metrics`].
+/// A depth that keeps growing means the actor can't keep up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeshMetrics {
+    /// Inbound requests on the pairing topic
+    pub request_mailbox: usize,
+    /// Outbound requests and responses
+    pub transport_mailbox: usize,
+    /// Inbound responses waiting to be matched to their request
+    pub response_mailbox: usize,
+    pub proposal_mailbox: usize,
+    /// Inbound session requests
+    pub session_mailbox: usize,
+    /// Mailbox capacity, `None` as the mailboxes are unbounded
+    pub mailbox_capacity: Option<usize>,
+}
+
+impl PairingManager {
+    /// Current [`Health`], from in-memory state only so it's cheap to poll
+    pub fn health(&self) -> Health {
+        Health {
+            relay_connected: self.connected.load(Ordering::SeqCst),
+            active_sessions: self.ciphers.session_count(),
+            pairing_present: self.ciphers.pairing().is_some(),
+        }
+    }
+
+    /// Current [`MeshMetrics`], read straight from the mailboxes so it's cheap
+    /// to poll
+    pub fn metrics(&self) -> MeshMetrics {
+        let transport = self.actors.transport();
+        MeshMetrics {
+            request_mailbox: self.actors.request().len(),
+            transport_mailbox: transport.len(),
+            response_mailbox: self.actors.response().len(),
+            proposal_mailbox: self.actors.proposal().len(),
+            session_mailbox: self.actors.session().len(),
+            mailbox_capacity: transport.capacity(),
+        }
+    }
+
+    /// When a message from the peer was last decoded on the current pairing
+    /// topic, a liveness signal that doesn't cost a ping round trip
+    pub fn peer_last_seen(&self) -> Option<DateTime<Utc>> {
+        let topic = self.topic()?;
+        self.last_seen.get(&topic).map(|seen| *seen)
+    }
+
+    /// [`SessionDebug`] of every active session, to find out why a session
+    /// is kept alive. Sessions deleted while collecting are skipped.
+    pub async fn debug_sessions(&self) -> Result<Vec<SessionDebug>> {
+        let sessions = self.actors.session().send(ActiveSessions).await?;
+        let mut debug = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            if let Ok(info) = session.send(DebugSession).await {
+                debug.push(info);
+            }
+        }
+        Ok(debug)
+    }
+}
@@ -1,49 +1,98 @@
 mod builder;
+#[cfg(not(target_family = "wasm"))]
+mod circuit_breaker;
 mod handlers;
+mod health;
 mod pairing;
+mod rate_limit;
 mod registration;
 #[cfg(not(target_family = "wasm"))]
 mod socket_handler;
 #[cfg(target_family = "wasm")]
 mod socket_handler_wasm;
-pub use builder::ReownBuilder;
+mod subscriptions;
+#[cfg(not(target_family = "wasm"))]
+pub use circuit_breaker::CircuitBreaker;
 #[cfg(not(target_family = "wasm"))]
 use socket_handler::handle_socket;
 #[cfg(target_family = "wasm")]
 use socket_handler_wasm::handle_socket;
 use {
     crate::{
-        actors::Actors,
+        actors::{Actors, ClearSession},
+        auth_token,
         relay::RelayHandler,
-        rpc::{PairDeleteRequest, PairExtendRequest, PairPingRequest, RequestParams},
+        rpc::{
+            PairDeleteRequest,
+            PairExtendRequest,
+            PairPingRequest,
+            RequestParams,
+            RpcRequest,
+            RpcResponse,
+            SessionDeleteRequest,
+        },
+        session::SessionEvent,
         spawn_task,
         transport::TopicTransport,
         wait,
         Error,
+        ProposerKeyPolicy,
         Result,
+        SessionError,
+        ShortTopic,
         SocketEvent,
         SocketListener,
+        TransportError,
+        AUTH_URL,
     },
+    chrono::{DateTime, Utc},
+    dashmap::DashMap,
     monedero_cipher::Cipher,
     monedero_domain::{namespaces::Namespaces, Pairing, SessionSettled, SubscriptionId, Topic},
-    monedero_relay::{Client, ConnectionOptions},
+    monedero_relay::{ConnectionHandler, ConnectionOptions, SharedTransport},
     serde::de::DeserializeOwned,
     std::{
         fmt::{Debug, Formatter},
-        sync::Arc,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
     },
-    tokio::sync::mpsc,
-    tracing::{info, warn},
+    tokio::sync::{broadcast, mpsc, watch},
+    tracing::{debug, info, warn},
+};
+pub use {
+    builder::ReownBuilder,
+    health::{Health, MeshMetrics},
 };
 
+/// How long to wait for the relay to acknowledge a subscription
+const SUBSCRIBE_TIMEOUT_MS: u32 = 5000;
+
+/// How long [`PairingManager::disconnect_all`] waits for each peer to accept
+/// `wc_sessionDelete`
+const SESSION_DELETE_TIMEOUT_MS: u32 = 1100;
+
 #[derive(Clone, xtra::Actor)]
 pub struct PairingManager {
-    relay: Client,
-    opts: ConnectionOptions,
+    relay: SharedTransport,
+    opts: Arc<std::sync::RwLock<ConnectionOptions>>,
     ciphers: Cipher,
     transport: TopicTransport,
     actors: Actors,
+    sync_namespaces: bool,
+    proposer_key_policy: ProposerKeyPolicy,
+    relay_data: Option<String>,
+    proposal_timeout: Duration,
+    /// Replays fetched messages, set under [`ReownBuilder::fetch_on_reconnect`]
+    fetch_handler: Option<RelayHandler>,
     pub(super) socket_listeners: Arc<tokio::sync::Mutex<Vec<Box<dyn SocketListener>>>>,
+    shutdown: Arc<watch::Sender<bool>>,
+    connected: Arc<AtomicBool>,
+    last_seen: Arc<DashMap<Topic, DateTime<Utc>>>,
+    #[cfg(not(target_family = "wasm"))]
+    circuit_breaker: Option<CircuitBreaker>,
 }
 
 impl Debug for PairingManager {
@@ -51,21 +100,42 @@ impl Debug for PairingManager {
         let t: String = self
             .topic()
             .map_or_else(|| String::from("none"), |t| crate::shorten_topic(&t));
-        write!(f, "pairing={} projectId={}", t, self.opts.project_id)
+        let project_id = self
+            .opts
+            .read()
+            .map(|o| o.project_id.to_string())
+            .unwrap_or_default();
+        write!(f, "pairing={t} projectId={project_id}")
     }
 }
 
 impl PairingManager {
-    async fn init(opts: ConnectionOptions, ciphers: Cipher) -> Result<Self> {
-        let actors = Actors::init(ciphers.clone());
+    async fn init(
+        builder: &ReownBuilder,
+        opts: ConnectionOptions,
+        ciphers: Cipher,
+    ) -> Result<Self> {
+        let actors = Actors::init(ciphers.clone(), builder.message_ids.clone());
         let (socket_tx, socket_rx) = mpsc::unbounded_channel::<SocketEvent>();
+        let connected = Arc::new(AtomicBool::new(false));
+        let last_seen = Arc::new(DashMap::new());
         let handler = RelayHandler::new(
             ciphers.clone(),
             actors.request(),
             actors.response(),
             socket_tx,
+            connected.clone(),
+            last_seen.clone(),
         );
-        let relay = Client::new(handler);
+        let handler = if builder.fetch_on_reconnect {
+            handler.with_dedup()
+        } else {
+            handler
+        };
+        let fetch_handler = builder.fetch_on_reconnect.then(|| handler.clone());
+        let relay: SharedTransport = Arc::new(subscriptions::Subscriptions::new(
+            builder.transport_factory().create(Box::new(handler)),
+        ));
         actors.register_client(relay.clone()).await?;
         relay.connect(&opts).await?;
 
@@ -73,35 +143,149 @@ impl PairingManager {
 
         let mgr = Self {
             relay,
-            opts,
+            opts: Arc::new(std::sync::RwLock::new(opts)),
             ciphers,
             transport,
             actors: actors.clone(),
+            sync_namespaces: builder.sync_namespaces,
+            proposer_key_policy: builder.proposer_key_policy,
+            relay_data: builder.relay_data.clone(),
+            proposal_timeout: builder.proposal_timeout,
+            fetch_handler,
             socket_listeners: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+            shutdown: Arc::new(watch::channel(false).0),
+            connected,
+            last_seen,
+            #[cfg(not(target_family = "wasm"))]
+            circuit_breaker: builder
+                .circuit_breaker
+                .map(|(threshold, cooldown)| CircuitBreaker::new(threshold, cooldown)),
         };
         actors.request().send(mgr.clone()).await?;
         let socket_handler = mgr.clone();
         spawn_task(handle_socket(socket_handler, socket_rx));
         mgr.open_socket().await?;
         mgr.restore_saved_pairing().await?;
+        mgr.preload_pairings(builder.preload_pairings.clone())
+            .await?;
+        if let Some(interval) = builder.keepalive_interval {
+            spawn_task(mgr.clone().keepalive(interval));
+        }
+        if let Some(interval) = builder.session_reaper_interval {
+            spawn_task(mgr.clone().reap_expired_sessions(interval));
+        }
         Ok(mgr)
     }
 
+    /// Add the pairings of [`ReownBuilder::preload_pairings`] to the cipher
+    /// and subscribe to their topics. Unlike the saved pairing they are not
+    /// checked for a live peer.
+    async fn preload_pairings(&self, pairings: Vec<Pairing>) -> Result<()> {
+        if pairings.is_empty() {
+            return Ok(());
+        }
+        let mut topics = Vec::with_capacity(pairings.len());
+        for pairing in pairings {
+            debug!("preloading pairing {}", ShortTopic(&pairing.topic));
+            topics.push(pairing.topic.clone());
+            self.ciphers.add_pairing(pairing)?;
+        }
+        self.batch_subscribe(topics).await?;
+        Ok(())
+    }
+
+    /// Every `interval` until [`Self::shutdown`], delete sessions whose
+    /// expiry has passed
+    async fn reap_expired_sessions(self, interval: Duration) {
+        let mut stop = self.shutdown.subscribe();
+        let interval_ms = u32::try_from(interval.as_millis()).unwrap_or(u32::MAX);
+        while wait::wait_until(interval_ms, stop.changed()).await.is_err() {
+            let now = Utc::now().timestamp();
+            let expired = self
+                .ciphers
+                .settlements()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|s| s.expiry < now);
+            for settled in expired {
+                info!(
+                    "reaping expired session {}",
+                    crate::shorten_topic(&settled.topic)
+                );
+                if let Err(e) = self.delete_session(settled.topic).await {
+                    debug!("failed to reap session: {e}");
+                }
+            }
+        }
+        debug!("session reaper stopped");
+    }
+
+    /// Ping the pairing topic every `interval` until [`Self::shutdown`]
+    async fn keepalive(self, interval: Duration) {
+        let mut stop = self.shutdown.subscribe();
+        let interval_ms = u32::try_from(interval.as_millis()).unwrap_or(u32::MAX);
+        while wait::wait_until(interval_ms, stop.changed()).await.is_err() {
+            if self.topic().is_none() {
+                continue;
+            }
+            if let Err(e) = self.ping().await {
+                debug!("keepalive ping failed: {e}");
+            }
+        }
+        debug!("keepalive stopped");
+    }
+
     pub async fn register_socket_listener<T: SocketListener>(&self, listener: T) {
         let mut l = self.socket_listeners.lock().await;
         l.push(Box::new(listener));
     }
 
+    /// Intercept inbound requests for `method` (e.g. `wc_sessionPing`) before
+    /// the default handling.
+    ///
+    /// When `handler` returns a response it is sent to the peer and default
+    /// handling is skipped, returning `None` lets the request pass through.
+    /// Registering again for the same method replaces the previous handler.
+    pub fn register_interceptor<F>(&self, method: &str, handler: F)
+    where
+        F: Fn(RpcRequest) -> Option<RpcResponse> + Send + Sync + 'static,
+    {
+        self.actors.register_interceptor(method, Arc::new(handler));
+    }
+
     pub(crate) async fn resubscribe(&self) -> Result<()> {
-        self.pairing().ok_or(Error::NoPairingTopic)?;
+        self.pairing().ok_or(SessionError::NoPairingTopic)?;
         let topics = self.ciphers.subscriptions();
-        self.relay.batch_subscribe(topics).await?;
+        self.batch_subscribe(topics).await?;
+        Ok(())
+    }
+
+    /// Resubscribe after a new connection, then with
+    /// [`ReownBuilder::fetch_on_reconnect`] handle what the relay mailbox
+    /// holds for each topic
+    pub(crate) async fn resume(&self) -> Result<()> {
+        self.resubscribe().await?;
+        let Some(fetch_handler) = &self.fetch_handler else {
+            return Ok(());
+        };
+        for topic in self.ciphers.subscriptions() {
+            let messages = self.relay.fetch(topic.clone()).await?;
+            debug!(
+                "fetched {} messages on {}",
+                messages.len(),
+                crate::shorten_topic(&topic)
+            );
+            let mut fetch_handler = fetch_handler.clone();
+            for message in messages {
+                fetch_handler.message_received(message);
+            }
+        }
         Ok(())
     }
 
     #[allow(dead_code)]
     pub(crate) async fn unsubscribe_all(&self) -> Result<()> {
-        self.pairing().ok_or(Error::NoPairingTopic)?;
+        self.pairing().ok_or(SessionError::NoPairingTopic)?;
         let topics = self.ciphers.subscriptions();
         for topic in topics {
             let _ = self.relay.unsubscribe(topic).await;
@@ -128,18 +312,67 @@ impl PairingManager {
         self.ciphers.clone()
     }
 
+    /// Subscribe to `topic`, returning once the relay has acknowledged the
+    /// subscription with its id.
+    ///
+    /// Fails with [`TransportError::SubscribeTimeout`] if no ack arrives in
+    /// time. Topics already subscribed on the current connection return
+    /// their existing id without asking the relay again.
     pub async fn subscribe(&self, topic: Topic) -> Result<SubscriptionId> {
-        Ok(self.relay.subscribe(topic).await?)
+        wait::wait_until(SUBSCRIBE_TIMEOUT_MS, self.relay.subscribe(topic))
+            .await
+            .map_err(|_| TransportError::SubscribeTimeout)?
+            .map_err(Error::from)
+    }
+
+    /// Like [`Self::subscribe`] for many topics, ids are in the order of
+    /// `topics`. Only topics not already subscribed reach the relay.
+    pub async fn batch_subscribe(&self, topics: Vec<Topic>) -> Result<Vec<SubscriptionId>> {
+        wait::wait_until(SUBSCRIBE_TIMEOUT_MS, self.relay.batch_subscribe(topics))
+            .await
+            .map_err(|_| TransportError::SubscribeTimeout)?
+            .map_err(Error::from)
     }
 
     pub fn actors(&self) -> Actors {
         self.actors.clone()
     }
 
+    /// Subscribe to settle, delete, extend and update notifications of all
+    /// sessions managed by this pairing
+    pub fn session_events(&self) -> broadcast::Receiver<SessionEvent> {
+        self.actors.session_events()
+    }
+
+    pub(crate) const fn sync_namespaces(&self) -> bool {
+        self.sync_namespaces
+    }
+
+    pub(crate) const fn proposer_key_policy(&self) -> ProposerKeyPolicy {
+        self.proposer_key_policy
+    }
+
+    /// See [`ReownBuilder::relay_data`]
+    pub(crate) fn relay_data(&self) -> Option<String> {
+        self.relay_data.clone()
+    }
+
+    /// See [`ReownBuilder::proposal_timeout`]
+    pub(crate) const fn proposal_timeout(&self) -> Duration {
+        self.proposal_timeout
+    }
+
     pub fn pair_key(&self) -> Option<String> {
         self.ciphers.public_key_hex()
     }
 
+    /// Like [`Self::pair_key`], for the pairing on `topic`
+    pub(crate) fn pair_key_for(&self, topic: &Topic) -> Option<String> {
+        let pairing = self.ciphers.pairing_by_topic(topic)?;
+        let pk = x25519_dalek::PublicKey::from(&pairing.params.sym_key);
+        Some(data_encoding::HEXLOWER_PERMISSIVE.encode(pk.as_bytes()))
+    }
+
     pub fn topic(&self) -> Option<Topic> {
         self.ciphers.pairing().map(|p| p.topic)
     }
@@ -149,7 +382,7 @@ impl PairingManager {
     }
 
     pub async fn ping(&self) -> Result<bool> {
-        let t = self.topic().ok_or(Error::NoPairingTopic)?;
+        let t = self.topic().ok_or(SessionError::NoPairingTopic)?;
         self.transport
             .publish_request::<bool>(t, RequestParams::PairPing(PairPingRequest::default()))
             .await
@@ -174,7 +407,7 @@ impl PairingManager {
     }
 
     pub async fn delete(&self) -> Result<bool> {
-        let t = self.topic().ok_or(Error::NoPairingTopic)?;
+        let t = self.topic().ok_or(SessionError::NoPairingTopic)?;
         let result = wait::wait_until(
             1100,
             self.transport.publish_request::<bool>(
@@ -187,9 +420,53 @@ impl PairingManager {
         Ok(result.is_ok())
     }
 
+    /// Send `wc_sessionDelete` on every session topic, one after the other,
+    /// then drop each session locally whether the peer accepted or not. The
+    /// pairing itself is kept.
+    ///
+    /// Returns the outcome of the delete request per session topic.
+    pub async fn disconnect_all(&self) -> Result<Vec<(Topic, Result<()>)>> {
+        let topics: Vec<Topic> = self
+            .ciphers
+            .subscriptions()
+            .into_iter()
+            .filter(|t| !self.ciphers.is_pairing_topic(t))
+            .collect();
+        let mut results = Vec::with_capacity(topics.len());
+        for topic in topics {
+            let result = self.delete_session(topic.clone()).await?;
+            results.push((topic, result));
+        }
+        Ok(results)
+    }
+
+    /// Send `wc_sessionDelete` on `topic`, then drop the session locally
+    /// whether the peer accepted or not. The outer error is only for failing
+    /// to drop it.
+    async fn delete_session(&self, topic: Topic) -> Result<Result<()>> {
+        let result = wait::wait_until(
+            SESSION_DELETE_TIMEOUT_MS,
+            self.transport.publish_request::<bool>(
+                topic.clone(),
+                RequestParams::SessionDelete(SessionDeleteRequest::default()),
+            ),
+        )
+        .await
+        .and_then(|r| r)
+        .and_then(|accepted| {
+            if accepted {
+                Ok(())
+            } else {
+                Err(SessionError::DeleteRejected(topic.clone()).into())
+            }
+        });
+        self.actors.session().send(ClearSession(topic)).await?;
+        Ok(result)
+    }
+
     // Epoch
     pub async fn extend(&self, expiry: u64) -> Result<bool> {
-        let t = self.topic().ok_or(Error::NoPairingTopic)?;
+        let t = self.topic().ok_or(SessionError::NoPairingTopic)?;
         self.transport
             .publish_request::<bool>(
                 t.clone(),
@@ -198,11 +475,11 @@ impl PairingManager {
             .await
     }
 
+    /// Replace the pairing, unless `pairing` is already known, e.g. from
+    /// [`ReownBuilder::preload_pairings`]
     pub async fn set_pairing(&self, pairing: Pairing) -> Result<()> {
-        if let Some(p) = self.pairing() {
-            if p.topic == pairing.topic {
-                return Ok(());
-            }
+        if self.ciphers.is_pairing_topic(&pairing.topic) {
+            return Ok(());
         }
         self.ciphers.set_pairing(Some(pairing.clone()))?;
         self.subscribe(pairing.topic).await?;
@@ -210,17 +487,35 @@ impl PairingManager {
     }
 
     pub async fn publish_request<R: DeserializeOwned>(&self, params: RequestParams) -> Result<R> {
-        let topic = self.topic().ok_or(Error::NoPairingTopic)?;
+        let topic = self.topic().ok_or(SessionError::NoPairingTopic)?;
         self.transport.publish_request(topic, params).await
     }
 
     pub async fn shutdown(&self) -> Result<()> {
+        self.shutdown.send_replace(true);
         self.disconnect_socket().await
     }
 
     #[tracing::instrument(level = "info")]
     pub async fn open_socket(&self) -> Result<()> {
-        self.relay.connect(&self.opts).await?;
+        let opts = self.opts.read().map_err(|_| Error::LockError)?.clone();
+        self.relay.connect(&opts).await?;
+        Ok(())
+    }
+
+    /// Open a new socket and resume the pairing and its sessions on it, see
+    /// [`ReownBuilder::fetch_on_reconnect`]
+    pub async fn reconnect(&self) -> Result<()> {
+        self.open_socket().await?;
+        self.resume().await
+    }
+
+    /// Mint a new relay auth token for the next [`Self::open_socket`]
+    pub(crate) fn refresh_auth(&self) -> Result<()> {
+        self.opts
+            .write()
+            .map_err(|_| Error::LockError)?
+            .set_auth(auth_token(AUTH_URL));
         Ok(())
     }
 
@@ -229,9 +524,17 @@ impl PairingManager {
         if let Err(err) = self.relay.disconnect().await {
             warn!("failed to close socket {err}");
         }
+        // the relay client only reports closes it didn't ask for
+        self.connected.store(false, Ordering::SeqCst);
         Ok(())
     }
 
+    /// See [`ReownBuilder::circuit_breaker`]
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) const fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.circuit_breaker.as_ref()
+    }
+
     pub(crate) fn topic_transport(&self) -> TopicTransport {
         self.transport.clone()
     }
@@ -0,0 +1,71 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::{Message, SubscriptionId, Topic},
+    monedero_relay::{ClientError, ConnectionOptions, SharedTransport, Transport},
+    std::{sync::Arc, time::Duration},
+    tracing::debug,
+};
+
+/// [`Transport`] retrying a publish the relay rejected with
+/// [`ClientError::RateLimited`] once, after the delay the error asks for
+pub struct RetryRateLimited {
+    inner: SharedTransport,
+}
+
+impl RetryRateLimited {
+    pub fn new(inner: SharedTransport) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Transport for RetryRateLimited {
+    async fn connect(&self, opts: &ConnectionOptions) -> monedero_relay::Result<()> {
+        self.inner.connect(opts).await
+    }
+
+    async fn disconnect(&self) -> monedero_relay::Result<()> {
+        self.inner.disconnect().await
+    }
+
+    async fn publish(
+        &self,
+        topic: Topic,
+        message: Arc<str>,
+        tag: u32,
+        ttl: Duration,
+        prompt: bool,
+    ) -> monedero_relay::Result<()> {
+        match self
+            .inner
+            .publish(topic.clone(), message.clone(), tag, ttl, prompt)
+            .await
+        {
+            Err(ClientError::RateLimited { retry_after }) => {
+                debug!("rate limited, retrying publish in {retry_after:?}");
+                tokio::time::sleep(retry_after).await;
+                self.inner.publish(topic, message, tag, ttl, prompt).await
+            }
+            result => result,
+        }
+    }
+
+    async fn subscribe(&self, topic: Topic) -> monedero_relay::Result<SubscriptionId> {
+        self.inner.subscribe(topic).await
+    }
+
+    async fn batch_subscribe(
+        &self,
+        topics: Vec<Topic>,
+    ) -> monedero_relay::Result<Vec<SubscriptionId>> {
+        self.inner.batch_subscribe(topics).await
+    }
+
+    async fn unsubscribe(&self, topic: Topic) -> monedero_relay::Result<()> {
+        self.inner.unsubscribe(topic).await
+    }
+
+    async fn fetch(&self, topic: Topic) -> monedero_relay::Result<Vec<Message>> {
+        self.inner.fetch(topic).await
+    }
+}
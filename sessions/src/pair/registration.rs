@@ -6,6 +6,7 @@ use {
     },
     monedero_domain::{SessionTopic, Topic},
     tracing::info,
+    x25519_dalek::StaticSecret,
 };
 
 impl PairingManager {
@@ -24,21 +25,42 @@ impl PairingManager {
         Ok(())
     }
 
-    async fn register_pk(&self, pk: String) -> Result<SessionTopic> {
-        let (session_topic, _) = self.ciphers.create_common_topic(pk)?;
+    async fn register_pk(
+        &self,
+        pairing_topic: &Topic,
+        pk: String,
+        key: Option<&StaticSecret>,
+    ) -> Result<SessionTopic> {
+        let (session_topic, _) = match key {
+            Some(key) => self
+                .ciphers
+                .create_common_topic_with(pairing_topic, key, pk)?,
+            None => self.ciphers.create_common_topic(pairing_topic, pk)?,
+        };
         // TODO: Do I need the subscriptionId?
         self.subscribe(session_topic.clone()).await?;
         Ok(session_topic)
     }
 
+    /// `proposer_key` is the persistent key the proposal offered, see
+    /// [`crate::ProposerKeyPolicy::Persistent`]
     pub(crate) async fn register_wallet_pk(
         &self,
+        pairing_topic: &Topic,
         controller: SessionProposeResponse,
+        proposer_key: Option<&StaticSecret>,
     ) -> Result<Topic> {
-        self.register_pk(controller.responder_public_key).await
+        self.register_pk(pairing_topic, controller.responder_public_key, proposer_key)
+            .await
     }
 
-    pub(crate) async fn register_dapp_pk(&self, proposer: Proposer) -> Result<Topic> {
-        self.register_pk(proposer.public_key).await
+    /// `pairing_topic` is the pairing the proposal arrived on
+    pub(crate) async fn register_dapp_pk(
+        &self,
+        pairing_topic: &Topic,
+        proposer: Proposer,
+    ) -> Result<Topic> {
+        self.register_pk(pairing_topic, proposer.public_key, None)
+            .await
     }
 }
@@ -1,27 +1,38 @@
 use {
-    crate::{PairingManager, SocketEvent},
+    crate::{
+        CircuitBreaker,
+        Error,
+        PairingManager,
+        RelayDisconnectReason,
+        Result,
+        SocketEvent,
+        TransportError,
+    },
     backoff::{future::retry, ExponentialBackoffBuilder},
-    std::time::Duration,
+    std::{
+        future::Future,
+        time::{Duration, Instant},
+    },
     tokio::sync::mpsc,
     tracing::{debug, info, warn},
 };
 
-async fn retry_backoff(mgr: PairingManager) {
+/// Delay before reconnecting
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+/// Delay before reconnecting when the relay closed with
+/// [`RelayDisconnectReason::RateLimited`]
+const RATE_LIMITED_DELAY: Duration = Duration::from_secs(30);
+/// Time spent retrying before giving up, circuit breaker cooldowns excluded
+const RECONNECT_BUDGET: Duration = Duration::from_secs(60);
+
+async fn retry_backoff(mgr: PairingManager, delay: Duration) {
     info!("reconnecting");
-    tokio::time::sleep(Duration::from_secs(3)).await;
-    let backoff = ExponentialBackoffBuilder::new()
-        .with_max_elapsed_time(Some(Duration::from_secs(60)))
-        .with_initial_interval(Duration::from_secs(3))
-        .build();
-    match retry(backoff, || async {
-        info!("attempting reconnect");
-        Ok(mgr.open_socket().await?)
-    })
-    .await
-    {
+    tokio::time::sleep(delay).await;
+    let breaker = mgr.circuit_breaker();
+    match reconnect(breaker, delay, RECONNECT_BUDGET, || mgr.open_socket()).await {
         Ok(()) => {
             debug!("re-subsribing");
-            if let Err(e) = mgr.resubscribe().await {
+            if let Err(e) = mgr.resume().await {
                 warn!("failed to resubscribe! {e}");
             }
         }
@@ -31,6 +42,58 @@ async fn retry_backoff(mgr: PairingManager) {
     }
 }
 
+/// Retry `connect` with an exponential backoff for up to `budget`. An open
+/// `breaker` stops the backoff, which starts over once the cooldown ends, so
+/// a cooldown longer than `budget` still gets its probe.
+async fn reconnect<F, Fut>(
+    breaker: Option<&CircuitBreaker>,
+    delay: Duration,
+    budget: Duration,
+    connect: F,
+) -> Result<()>
+where
+    F: Fn() -> Fut + Sync,
+    Fut: Future<Output = Result<()>> + Send,
+{
+    loop {
+        let backoff = ExponentialBackoffBuilder::new()
+            .with_max_elapsed_time(Some(budget))
+            .with_initial_interval(delay)
+            .build();
+        let result = retry(backoff, || async {
+            if breaker.is_some_and(|b| !b.allow()) {
+                return Err(backoff::Error::permanent(
+                    TransportError::CircuitOpen.into(),
+                ));
+            }
+            info!("attempting reconnect");
+            let result = connect().await;
+            if let Some(b) = breaker {
+                if result.is_ok() {
+                    b.success();
+                } else {
+                    b.failure();
+                }
+            }
+            Ok(result?)
+        })
+        .await;
+        match result {
+            Err(Error::Transport(TransportError::CircuitOpen)) => {
+                // half-open when another manager's probe is in flight
+                let wait = breaker
+                    .and_then(CircuitBreaker::reopens_at)
+                    .map_or(delay, |until| {
+                        until.saturating_duration_since(Instant::now())
+                    });
+                debug!("circuit open, waiting {wait:?} before reconnecting");
+                tokio::time::sleep(wait).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 pub async fn handle_socket(mgr: PairingManager, mut rx: mpsc::UnboundedReceiver<SocketEvent>) {
     while let Some(message) = rx.recv().await {
         match message {
@@ -40,15 +103,66 @@ pub async fn handle_socket(mgr: PairingManager, mut rx: mpsc::UnboundedReceiver<
                     listener.handle_socket_event(message.clone()).await;
                 }
             }
-            SocketEvent::ForceDisconnect => {
+            SocketEvent::ForceDisconnect(reason) => {
                 let l = mgr.socket_listeners.lock().await;
                 for listener in l.iter() {
                     listener.handle_socket_event(message.clone()).await;
                 }
                 drop(l);
+                if reason == RelayDisconnectReason::AuthExpired {
+                    info!("relay auth expired, minting a new token");
+                    if let Err(e) = mgr.refresh_auth() {
+                        warn!("failed to refresh auth token: {e}");
+                    }
+                }
+                let delay = if reason == RelayDisconnectReason::RateLimited {
+                    RATE_LIMITED_DELAY
+                } else {
+                    RECONNECT_DELAY
+                };
                 let mgr_backoff = mgr.clone();
-                retry_backoff(mgr_backoff).await;
+                retry_backoff(mgr_backoff, delay).await;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        std::sync::{Arc, Mutex},
+    };
+
+    #[tokio::test]
+    async fn test_reconnect_after_cooldown() -> anyhow::Result<()> {
+        let budget = Duration::from_millis(200);
+        let cooldown = Duration::from_millis(600);
+        let breaker = CircuitBreaker::isolated(1, cooldown);
+        let attempts: Arc<Mutex<Vec<Instant>>> = Arc::default();
+        let start = Instant::now();
+        reconnect(Some(&breaker), Duration::from_millis(10), budget, || {
+            let attempts = attempts.clone();
+            async move {
+                let count = {
+                    let mut attempts = attempts.lock().unwrap();
+                    attempts.push(Instant::now());
+                    attempts.len()
+                };
+                if count == 1 {
+                    return Err(TransportError::NoClient.into());
+                }
+                Ok(())
+            }
+        })
+        .await?;
+        let probe = {
+            let attempts = attempts.lock().unwrap();
+            assert_eq!(2, attempts.len());
+            attempts[1]
+        };
+        // the probe waited for the cooldown, past the backoff budget
+        assert!(probe.duration_since(start) >= cooldown);
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    crate::{spawn_task, wait::wait_until, PairingManager, SocketEvent},
+    crate::{spawn_task, wait::wait_until, PairingManager, RelayDisconnectReason, SocketEvent},
     tokio::sync::mpsc,
     tracing::warn,
 };
@@ -13,12 +13,17 @@ pub async fn handle_socket(mgr: PairingManager, mut rx: mpsc::UnboundedReceiver<
                     listener.handle_socket_event(message.clone()).await;
                 }
             }
-            SocketEvent::ForceDisconnect => {
+            SocketEvent::ForceDisconnect(reason) => {
                 let l = mgr.socket_listeners.lock().await;
                 for listener in l.iter() {
                     listener.handle_socket_event(message.clone()).await;
                 }
                 drop(l);
+                if reason == RelayDisconnectReason::AuthExpired {
+                    if let Err(e) = mgr.refresh_auth() {
+                        warn!("failed to refresh auth token: {e}");
+                    }
+                }
                 let reconnector = mgr.clone();
                 if let Err(e) = wait_until(1000, async move {
                     reconnector.open_socket().await
@@ -0,0 +1,88 @@
+use {
+    async_trait::async_trait,
+    dashmap::DashMap,
+    monedero_domain::{Message, SubscriptionId, Topic},
+    monedero_relay::{ConnectionOptions, SharedTransport, Transport},
+    std::{sync::Arc, time::Duration},
+};
+
+/// [`Transport`] remembering the topics it subscribed, so subscribing an
+/// active topic again returns its id without asking the relay. A new
+/// connection starts without subscriptions, so [`Transport::connect`] and
+/// [`Transport::disconnect`] forget them all.
+pub struct Subscriptions {
+    inner: SharedTransport,
+    active: DashMap<Topic, SubscriptionId>,
+}
+
+impl Subscriptions {
+    pub fn new(inner: SharedTransport) -> Self {
+        Self {
+            inner,
+            active: DashMap::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for Subscriptions {
+    async fn connect(&self, opts: &ConnectionOptions) -> monedero_relay::Result<()> {
+        self.active.clear();
+        self.inner.connect(opts).await
+    }
+
+    async fn disconnect(&self) -> monedero_relay::Result<()> {
+        self.active.clear();
+        self.inner.disconnect().await
+    }
+
+    async fn publish(
+        &self,
+        topic: Topic,
+        message: Arc<str>,
+        tag: u32,
+        ttl: Duration,
+        prompt: bool,
+    ) -> monedero_relay::Result<()> {
+        self.inner.publish(topic, message, tag, ttl, prompt).await
+    }
+
+    async fn subscribe(&self, topic: Topic) -> monedero_relay::Result<SubscriptionId> {
+        if let Some(id) = self.active.get(&topic) {
+            return Ok(id.clone());
+        }
+        let id = self.inner.subscribe(topic.clone()).await?;
+        self.active.insert(topic, id.clone());
+        Ok(id)
+    }
+
+    async fn batch_subscribe(
+        &self,
+        topics: Vec<Topic>,
+    ) -> monedero_relay::Result<Vec<SubscriptionId>> {
+        let missing: Vec<Topic> = topics
+            .iter()
+            .filter(|t| !self.active.contains_key(*t))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            let ids = self.inner.batch_subscribe(missing.clone()).await?;
+            for (topic, id) in missing.into_iter().zip(ids) {
+                self.active.insert(topic, id);
+            }
+        }
+        Ok(topics
+            .iter()
+            .filter_map(|t| self.active.get(t).map(|id| id.clone()))
+            .collect())
+    }
+
+    async fn unsubscribe(&self, topic: Topic) -> monedero_relay::Result<()> {
+        self.active.remove(&topic);
+        self.inner.unsubscribe(topic).await
+    }
+
+    async fn fetch(&self, topic: Topic) -> monedero_relay::Result<Vec<Message>> {
+        self.inner.fetch(topic).await
+    }
+}
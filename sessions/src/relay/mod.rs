@@ -5,19 +5,41 @@ use {
         spawn_task,
         SocketEvent,
     },
+    chrono::{DateTime, Utc},
+    dashmap::DashMap,
+    lru::LruCache,
     monedero_cipher::Cipher,
-    monedero_domain::Message,
-    monedero_relay::{ClientError, CloseFrame, ConnectionHandler},
+    monedero_domain::{Message, MessageId, Topic},
+    monedero_relay::{ClientError, CloseFrame, ConnectionHandler, RelayDisconnectReason},
+    std::{
+        num::NonZeroUsize,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+            Mutex,
+            PoisonError,
+        },
+    },
     tokio::sync::mpsc,
     tracing::{error, info, trace, warn},
     xtra::prelude::*,
 };
 
+/// Payloads remembered to drop the ones fetched again after a reconnect
+const SEEN_PAYLOADS: NonZeroUsize = NonZeroUsize::new(1024).unwrap();
+
+/// Payload ids already handled, a request and its response share the id
+type SeenPayloads = Arc<Mutex<LruCache<(MessageId, bool), ()>>>;
+
+#[derive(Clone)]
 pub struct RelayHandler {
     cipher: Cipher,
     req_tx: mpsc::UnboundedSender<RpcRequest>,
     res_tx: mpsc::UnboundedSender<Response>,
     socket_tx: mpsc::UnboundedSender<SocketEvent>,
+    connected: Arc<AtomicBool>,
+    last_seen: Arc<DashMap<Topic, DateTime<Utc>>>,
+    seen: Option<SeenPayloads>,
 }
 
 impl RelayHandler {
@@ -26,6 +48,8 @@ impl RelayHandler {
         request_actor: Address<RequestHandlerActor>,
         response_actor: Address<InboundResponseActor>,
         socket_tx: mpsc::UnboundedSender<SocketEvent>,
+        connected: Arc<AtomicBool>,
+        last_seen: Arc<DashMap<Topic, DateTime<Utc>>>,
     ) -> Self {
         let (req_tx, req_rx) = mpsc::unbounded_channel::<RpcRequest>();
         let (res_tx, res_rx) = mpsc::unbounded_channel::<Response>();
@@ -40,19 +64,48 @@ impl RelayHandler {
             req_tx,
             res_tx,
             socket_tx,
+            connected,
+            last_seen,
+            seen: None,
         }
     }
+
+    /// Drop payloads already handled, so messages fetched from the relay
+    /// mailbox can be replayed through [`ConnectionHandler::message_received`]
+    #[must_use]
+    pub(crate) fn with_dedup(mut self) -> Self {
+        self.seen = Some(Arc::new(Mutex::new(LruCache::new(SEEN_PAYLOADS))));
+        self
+    }
+
+    /// Whether the payload `id` was handled before, recording it if not
+    fn already_seen(&self, id: MessageId, request: bool) -> bool {
+        self.seen.as_ref().is_some_and(|seen| {
+            seen.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .put((id, request), ())
+                .is_some()
+        })
+    }
 }
 
 impl ConnectionHandler for RelayHandler {
     fn connected(&mut self) {
+        self.connected.store(true, Ordering::SeqCst);
         if self.socket_tx.send(SocketEvent::Connected).is_err() {
             warn!("failed to send socket event");
         }
     }
 
-    fn disconnected(&mut self, _frame: Option<CloseFrame<'static>>) {
-        if self.socket_tx.send(SocketEvent::ForceDisconnect).is_err() {
+    fn disconnected(&mut self, frame: Option<CloseFrame<'static>>) {
+        self.connected.store(false, Ordering::SeqCst);
+        let reason = RelayDisconnectReason::from(frame.as_ref());
+        info!("relay closed socket: {reason}");
+        if self
+            .socket_tx
+            .send(SocketEvent::ForceDisconnect(reason))
+            .is_err()
+        {
             warn!("failed to send socket event");
         }
     }
@@ -63,11 +116,21 @@ impl ConnectionHandler for RelayHandler {
             return;
         }
         trace!("decoding {}", message.id);
-        match self
+        let decoded = self
             .cipher
-            .decode::<Payload>(&message.topic, &message.message)
-        {
+            .decode::<Payload>(&message.topic, &message.message);
+        if decoded.is_ok() && self.cipher.is_pairing_topic(&message.topic) {
+            self.last_seen.insert(message.topic.clone(), Utc::now());
+        }
+        match decoded {
+            Ok(Payload::Request(req)) if self.already_seen(req.id, true) => {
+                trace!("skipping request {} handled before", req.id);
+            }
+            Ok(Payload::Response(res)) if self.already_seen(res.id, false) => {
+                trace!("skipping response {} handled before", res.id);
+            }
             Ok(Payload::Request(req)) => {
+                crate::wire::inbound_request(&message.topic, &req);
                 let rpc: RpcRequest = RpcRequest {
                     topic: message.topic,
                     payload: req,
@@ -77,6 +140,7 @@ impl ConnectionHandler for RelayHandler {
                 }
             }
             Ok(Payload::Response(res)) => {
+                crate::wire::inbound_response(&message.topic, &res);
                 if let Err(e) = self.res_tx.send(res) {
                     warn!("[relay handler] response channel is broken, error: {e}");
                 }
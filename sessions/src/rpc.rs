@@ -82,10 +82,10 @@ impl Payload {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub(crate) struct RpcResponse {
-    pub(crate) id: MessageId,
-    pub(crate) topic: Topic,
-    pub(crate) payload: RpcResponsePayload,
+pub struct RpcResponse {
+    pub id: MessageId,
+    pub topic: Topic,
+    pub payload: RpcResponsePayload,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -112,9 +112,9 @@ impl RpcResponse {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub(crate) struct RpcRequest {
-    pub(crate) topic: Topic,
-    pub(crate) payload: Request,
+pub struct RpcRequest {
+    pub topic: Topic,
+    pub payload: Request,
 }
 
 impl Display for RpcRequest {
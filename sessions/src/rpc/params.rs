@@ -14,7 +14,7 @@ pub(super) mod shared_types;
 use {
     crate::rpc::{sdkerrors::SdkError, SdkErrors},
     paste::paste,
-    serde::{Deserialize, Serialize},
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
     serde_json::Value,
     std::{
         fmt::{Debug, Display, Formatter},
@@ -47,6 +47,24 @@ pub enum ParamsError {
     /// Sign API invalid response tag.
     #[error("Response tag={0} does not match any of the Sign API methods")]
     ResponseTag(u32),
+    /// Session request method does not belong to the chain's namespace.
+    #[error("Method {method} cannot be requested on chain {chain_id}")]
+    MethodChainMismatch {
+        method: monedero_domain::namespaces::Method,
+        chain_id: monedero_domain::namespaces::ChainId,
+    },
+    /// Expiry is in the past or too far in the future.
+    #[error("Expiry {0} is out of bounds")]
+    InvalidExpiry(u64),
+    /// Response payload does not fit the method its tag names.
+    #[error("Response tag={tag} has a malformed payload: {reason}")]
+    MalformedResponse { tag: u32, reason: String },
+    /// Session request params lack a field the method needs.
+    #[error("Params of {method} lack {field}")]
+    MissingParam {
+        method: monedero_domain::namespaces::Method,
+        field: &'static str,
+    },
 }
 
 /// Relay protocol metadata.
@@ -84,6 +102,28 @@ pub struct IrnMetadata {
     pub prompt: bool,
 }
 
+/// Smallest publish ttl (seconds) accepted by the relay.
+pub const IRN_MIN_TTL: u64 = 300;
+/// Largest publish ttl (seconds) accepted by the relay, 30 days.
+pub const IRN_MAX_TTL: u64 = 30 * 24 * 60 * 60;
+/// Relay ttl (seconds) of `wc_sessionPropose`
+pub const SESSION_PROPOSE_TTL: u64 = session_propose::IRN_REQUEST_METADATA.ttl;
+
+impl IrnMetadata {
+    /// Override the ttl, clamped to [`IRN_MIN_TTL`]..=[`IRN_MAX_TTL`].
+    #[must_use]
+    pub const fn with_ttl(self, ttl: u64) -> Self {
+        let ttl = if ttl < IRN_MIN_TTL {
+            IRN_MIN_TTL
+        } else if ttl > IRN_MAX_TTL {
+            IRN_MAX_TTL
+        } else {
+            ttl
+        };
+        Self { ttl, ..self }
+    }
+}
+
 // Convenience macro to de-duplicate implementation for different parameter
 // sets.
 macro_rules! impl_relay_protocol_metadata {
@@ -110,6 +150,14 @@ macro_rules! impl_relay_protocol_metadata {
     }
 }
 
+/// Deserialize the payload of a response tagged `tag`
+fn response_from_value<T: DeserializeOwned>(value: Value, tag: u32) -> Result<T, ParamsError> {
+    serde_json::from_value(value).map_err(|e| ParamsError::MalformedResponse {
+        tag,
+        reason: e.to_string(),
+    })
+}
+
 // Convenience macro to de-duplicate implementation for different parameter
 // sets.
 macro_rules! impl_relay_protocol_helpers {
@@ -119,31 +167,32 @@ macro_rules! impl_relay_protocol_helpers {
                 type Params = Self;
 
                 fn irn_try_from_tag(value: Value, tag: u32) -> Result<Self::Params, ParamsError> {
-                    if tag == session_propose::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionPropose(serde_json::from_value(value)?))
+                    let params = if tag == session_propose::IRN_RESPONSE_METADATA.tag {
+                        Self::SessionPropose(response_from_value(value, tag)?)
                     } else if tag == session_settle::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionSettle(serde_json::from_value(value)?))
+                        Self::SessionSettle(response_from_value(value, tag)?)
                     } else if tag == session_update::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionUpdate(serde_json::from_value(value)?))
+                        Self::SessionUpdate(response_from_value(value, tag)?)
                     } else if tag == session_extend::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionExtend(serde_json::from_value(value)?))
+                        Self::SessionExtend(response_from_value(value, tag)?)
                     } else if tag == session_request::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionRequest(serde_json::from_value(value)?))
+                        Self::SessionRequest(response_from_value(value, tag)?)
                     } else if tag == session_event::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionEvent(serde_json::from_value(value)?))
+                        Self::SessionEvent(response_from_value(value, tag)?)
                     } else if tag == session_delete::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionDelete(serde_json::from_value(value)?))
+                        Self::SessionDelete(response_from_value(value, tag)?)
                     } else if tag == session_ping::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::SessionPing(serde_json::from_value(value)?))
+                        Self::SessionPing(response_from_value(value, tag)?)
                     } else if tag == pair_ping::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::PairPing(serde_json::from_value(value)?))
+                        Self::PairPing(response_from_value(value, tag)?)
                     } else if tag == pair_delete::IRN_RESPONSE_METADATA.tag  {
-                        Ok(Self::PairDelete(serde_json::from_value(value)?))
+                        Self::PairDelete(response_from_value(value, tag)?)
                     } else if tag == pair_extend::IRN_RESPONSE_METADATA.tag {
-                        Ok(Self::PairExtend(serde_json::from_value(value)?))
+                        Self::PairExtend(response_from_value(value, tag)?)
                     } else {
-                        Err(ParamsError::ResponseTag(tag))
-                    }
+                        return Err(ParamsError::ResponseTag(tag));
+                    };
+                    params.validate(tag)
                 }
             }
         }
@@ -181,6 +230,42 @@ pub enum RequestParams {
     SessionPing(()),
 }
 
+impl RequestParams {
+    /// The JSON-RPC method name, e.g. `wc_sessionPing`
+    pub const fn method(&self) -> &'static str {
+        match self {
+            Self::PairDelete(_) => "wc_pairingDelete",
+            Self::PairExtend(_) => "wc_pairingExtend",
+            Self::PairPing(_) => "wc_pairingPing",
+            Self::SessionPropose(_) => "wc_sessionPropose",
+            Self::SessionSettle(_) => "wc_sessionSettle",
+            Self::SessionUpdate(_) => "wc_sessionUpdate",
+            Self::SessionExtend(_) => "wc_sessionExtend",
+            Self::SessionRequest(_) => "wc_sessionRequest",
+            Self::SessionEvent(_) => "wc_sessionEvent",
+            Self::SessionDelete(_) => "wc_sessionDelete",
+            Self::SessionPing(()) => "wc_sessionPing",
+        }
+    }
+
+    /// The `method` tag of a JSON-RPC request, without deserializing its
+    /// params. Useful to route frames before full deserialization, also works
+    /// for methods this crate doesn't know.
+    pub fn method_name(value: &Value) -> Option<&str> {
+        value.get("method")?.as_str()
+    }
+}
+
+/// Like [`RequestParams::method_name`] for a raw JSON frame. Only the `method`
+/// field is parsed, params are skipped.
+pub fn peek_method(json: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Peek {
+        method: String,
+    }
+    serde_json::from_str::<Peek>(json).ok().map(|p| p.method)
+}
+
 impl Display for RequestParams {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let req: &str = match self {
@@ -241,6 +326,28 @@ pub enum ResponseParamsSuccess {
 impl_relay_protocol_metadata!(ResponseParamsSuccess, response);
 impl_relay_protocol_helpers!(ResponseParamsSuccess);
 
+impl ResponseParamsSuccess {
+    /// Catch payloads that deserialize but can't be what the peer meant, e.g.
+    /// a `wc_sessionPropose` response without a responder key
+    fn validate(self, tag: u32) -> Result<Self, ParamsError> {
+        if let Self::SessionPropose(response) = &self {
+            let key = data_encoding::HEXLOWER_PERMISSIVE
+                .decode(response.responder_public_key.as_bytes())
+                .ok();
+            if key.is_none_or(|k| k.len() != 32) {
+                return Err(ParamsError::MalformedResponse {
+                    tag,
+                    reason: format!(
+                        "responderPublicKey '{}' is not a 32 byte hex key",
+                        response.responder_public_key
+                    ),
+                });
+            }
+        }
+        Ok(self)
+    }
+}
+
 impl TryFrom<ResponseParamsSuccess> for ResponseParams {
     type Error = ParamsError;
 
@@ -261,6 +368,7 @@ pub struct ErrorParams {
     //#[serde(skip_serializing_if = "Option::is_none")]
     //#[serde(default)]
     // pub message: Option<String>,
+    #[serde(default)]
     pub message: String,
 }
 
@@ -271,6 +379,12 @@ impl ErrorParams {
             message: "Unknown Error".to_string(),
         }
     }
+
+    /// The empty error, without code or message, a peer sends when the
+    /// session has expired
+    pub fn is_session_expired(&self) -> bool {
+        self.code.is_none() && self.message.is_empty()
+    }
 }
 
 /// Typed error response parameters.
@@ -293,6 +407,15 @@ pub enum ResponseParamsError {
 impl_relay_protocol_metadata!(ResponseParamsError, response);
 impl_relay_protocol_helpers!(ResponseParamsError);
 
+impl ResponseParamsError {
+    /// [`ErrorParams`] has no required fields, anything that deserializes is
+    /// accepted
+    #[allow(clippy::unnecessary_wraps)]
+    const fn validate(self, _tag: u32) -> Result<Self, ParamsError> {
+        Ok(self)
+    }
+}
+
 #[allow(clippy::fallible_impl_from)]
 impl From<SdkErrors> for ErrorParams {
     /// # Panics
@@ -325,7 +448,16 @@ pub struct Controller {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, anyhow::Result, serde::de::DeserializeOwned, serde_json};
+    use {
+        super::*,
+        anyhow::Result,
+        monedero_domain::{
+            namespaces::{ChainId, ChainType, Method, Namespaces, SolanaMethod},
+            MessageIdGenerator,
+        },
+        serde::de::DeserializeOwned,
+        serde_json,
+    };
 
     /// Trims json of the whitespaces and newlines.
     ///
@@ -350,4 +482,114 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_irn_ttl_override() {
+        let metadata = session_request::IRN_REQUEST_METADATA;
+        let overridden = metadata.with_ttl(3600);
+        assert_eq!(overridden.ttl, 3600);
+        assert_eq!(overridden.tag, metadata.tag);
+        assert_eq!(overridden.prompt, metadata.prompt);
+        assert_eq!(metadata.with_ttl(1).ttl, IRN_MIN_TTL);
+        assert_eq!(metadata.with_ttl(u64::MAX).ttl, IRN_MAX_TTL);
+    }
+
+    #[test]
+    fn test_empty_error_params() -> Result<()> {
+        let params: ErrorParams = serde_json::from_str("{}")?;
+        assert_eq!(None, params.code);
+        assert!(params.message.is_empty());
+        assert!(params.is_session_expired());
+        assert!(!ErrorParams::unknown().is_session_expired());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mistagged_response() -> Result<()> {
+        let propose_tag = session_propose::IRN_RESPONSE_METADATA.tag;
+        let settle_tag = session_settle::IRN_RESPONSE_METADATA.tag;
+        let propose = serde_json::json!({
+            "relay": {"protocol": "irn"},
+            "responderPublicKey": "a3ad5e26070ddb2809200c6f56e739333512015bceeadbb8ea1731c4c7ddb207"
+        });
+        assert!(matches!(
+            ResponseParamsSuccess::irn_try_from_tag(propose.clone(), propose_tag)?,
+            ResponseParamsSuccess::SessionPropose(_)
+        ));
+
+        let err = ResponseParamsSuccess::irn_try_from_tag(Value::Bool(true), propose_tag)
+            .expect_err("settle response tagged as propose");
+        assert!(matches!(err, ParamsError::MalformedResponse { tag, .. } if tag == propose_tag));
+        assert!(err.to_string().contains("invalid type: boolean"), "{err}");
+
+        let err = ResponseParamsSuccess::irn_try_from_tag(propose, settle_tag)
+            .expect_err("propose response tagged as settle");
+        assert!(matches!(err, ParamsError::MalformedResponse { tag, .. } if tag == settle_tag));
+
+        let empty_key = serde_json::json!({
+            "relay": {"protocol": "irn"},
+            "responderPublicKey": ""
+        });
+        let err = ResponseParamsSuccess::irn_try_from_tag(empty_key, propose_tag)
+            .expect_err("propose response without a key");
+        assert!(err.to_string().contains("responderPublicKey"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_peek_method() -> Result<()> {
+        let chain_id = ChainId::Solana(ChainType::Dev);
+        let requests = [
+            RequestParams::PairDelete(PairDeleteRequest::default()),
+            RequestParams::PairExtend(PairExtendRequest { expiry: 1 }),
+            RequestParams::PairPing(PairPingRequest::default()),
+            RequestParams::SessionPropose(SessionProposeRequest::new(
+                Metadata::default(),
+                String::from("00"),
+                Namespaces::default(),
+                None,
+            )),
+            RequestParams::SessionSettle(SessionSettleRequest::default()),
+            RequestParams::SessionUpdate(SessionUpdateRequest {
+                namespaces: Namespaces::default(),
+            }),
+            RequestParams::SessionExtend(SessionExtendRequest { expiry: 1 }),
+            RequestParams::SessionRequest(
+                SessionRequestBuilder::new(
+                    chain_id.clone(),
+                    Method::Solana(SolanaMethod::SignMessage),
+                )
+                .build()?,
+            ),
+            RequestParams::SessionEvent(SessionEventRequest {
+                event: Event {
+                    name: String::from("accountsChanged"),
+                    data: Value::Null,
+                },
+                chain_id,
+            }),
+            RequestParams::SessionDelete(SessionDeleteRequest {
+                code: 6000,
+                message: String::from("bye"),
+            }),
+            RequestParams::SessionPing(()),
+        ];
+        for params in requests {
+            let request = crate::rpc::Request::new(MessageIdGenerator::new().next(), params);
+            let json = serde_json::to_string(&request)?;
+            let value = serde_json::to_value(&request)?;
+            let method = request.params.method();
+            assert_eq!(Some(method), RequestParams::method_name(&value));
+            assert_eq!(Some(String::from(method)), peek_method(&json));
+        }
+
+        // params don't have to be understood
+        let unknown = r#"{"id":1,"jsonrpc":"2.0","method":"wc_authRequest","params":[1]}"#;
+        assert_eq!(Some(String::from("wc_authRequest")), peek_method(unknown));
+        assert_eq!(
+            None,
+            peek_method(r#"{"id":1,"jsonrpc":"2.0","result":true}"#)
+        );
+        Ok(())
+    }
 }
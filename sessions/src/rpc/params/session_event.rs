@@ -29,6 +29,33 @@ pub struct Event {
     pub data: serde_json::Value,
 }
 
+/// [`Event`] with [`Event::data`] parsed for the well known event names
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParsedEvent {
+    AccountsChanged(Vec<String>),
+    ChainChanged(ChainId),
+    /// Unknown event, or data not in the expected shape
+    Other(serde_json::Value),
+}
+
+impl Event {
+    /// Parse [`Self::data`] according to [`Self::name`], falling back to
+    /// [`ParsedEvent::Other`]
+    #[must_use]
+    pub fn parse(&self) -> ParsedEvent {
+        match self.name.as_str() {
+            "accountsChanged" => serde_json::from_value(self.data.clone())
+                .map(ParsedEvent::AccountsChanged)
+                .ok(),
+            "chainChanged" => serde_json::from_value(self.data.clone())
+                .map(ParsedEvent::ChainChanged)
+                .ok(),
+            _ => None,
+        }
+        .unwrap_or_else(|| ParsedEvent::Other(self.data.clone()))
+    }
+}
+
 #[derive(Debug, Serialize, PartialEq, Eq, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionEventRequest {
@@ -59,4 +86,47 @@ mod tests {
 
         param_serde_test::<SessionEventRequest>(json)
     }
+
+    #[test]
+    fn test_parse_event() -> Result<()> {
+        let request: SessionEventRequest = serde_json::from_str(
+            r#"
+        {
+            "event": {
+                "name": "accountsChanged",
+                "data": ["0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"]
+            },
+            "chainId": "eip155:5"
+        }
+        "#,
+        )?;
+        assert_eq!(
+            ParsedEvent::AccountsChanged(vec![String::from(
+                "0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"
+            )]),
+            request.event.parse()
+        );
+
+        let event = Event {
+            name: String::from("chainChanged"),
+            data: serde_json::json!("eip155:1"),
+        };
+        assert_eq!(
+            ParsedEvent::ChainChanged(ChainId::EIP155(alloy_chains::Chain::mainnet())),
+            event.parse()
+        );
+
+        let event = Event {
+            name: String::from("chainChanged"),
+            data: serde_json::json!(1),
+        };
+        assert_eq!(ParsedEvent::Other(serde_json::json!(1)), event.parse());
+
+        let event = Event {
+            name: String::from("balanceChanged"),
+            data: serde_json::json!({"balance": "1"}),
+        };
+        assert_eq!(ParsedEvent::Other(event.data.clone()), event.parse());
+        Ok(())
+    }
 }
@@ -3,9 +3,13 @@
 
 use {
     super::IrnMetadata,
+    crate::rpc::{ErrorParams, IntoUnknownError, ResponseParamsError},
     serde::{Deserialize, Serialize},
 };
 
+/// A session can be extended at most 7 days into the future
+pub const MAX_SESSION_EXTEND_SECS: u64 = 7 * 24 * 60 * 60;
+
 pub(super) const IRN_REQUEST_METADATA: IrnMetadata = IrnMetadata {
     tag: 1106,
     ttl: 86400,
@@ -24,6 +28,20 @@ pub struct SessionExtendRequest {
     pub expiry: u64,
 }
 
+impl SessionExtendRequest {
+    /// The new expiry must be after `now` and no more than
+    /// [`MAX_SESSION_EXTEND_SECS`] past it (unix seconds)
+    pub const fn is_valid(&self, now: u64) -> bool {
+        self.expiry > now && self.expiry <= now.saturating_add(MAX_SESSION_EXTEND_SECS)
+    }
+}
+
+impl IntoUnknownError for SessionExtendRequest {
+    fn unknown(&self) -> ResponseParamsError {
+        ResponseParamsError::SessionExtend(ErrorParams::unknown())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -37,4 +55,19 @@ mod tests {
 
         param_serde_test::<SessionExtendRequest>(json)
     }
+
+    #[test]
+    fn test_session_extend_bounds() {
+        let now = 1_700_000_000;
+        assert!(SessionExtendRequest { expiry: now + 60 }.is_valid(now));
+        assert!(SessionExtendRequest {
+            expiry: now + MAX_SESSION_EXTEND_SECS
+        }
+        .is_valid(now));
+        assert!(!SessionExtendRequest { expiry: now }.is_valid(now));
+        assert!(!SessionExtendRequest {
+            expiry: now + MAX_SESSION_EXTEND_SECS + 1
+        }
+        .is_valid(now));
+    }
 }
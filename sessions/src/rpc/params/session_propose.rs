@@ -83,6 +83,19 @@ impl SessionProposeRequest {
             optional_namespaces: optional,
         }
     }
+
+    /// Offer `relay` instead of the default relay protocol
+    #[must_use]
+    pub fn with_relay(mut self, relay: RelayProtocol) -> Self {
+        self.relays = vec![relay];
+        self
+    }
+
+    /// Relay protocol the proposer offered, which the wallet echoes in its
+    /// response and settlement. The default one if none was offered.
+    pub fn relay(&self) -> RelayProtocol {
+        self.relays.first().cloned().unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Serialize, PartialEq, Eq, Deserialize, Clone)]
@@ -92,6 +105,46 @@ pub struct SessionProposeResponse {
     pub responder_public_key: String,
 }
 
+impl SessionProposeResponse {
+    /// Starts from `responder_public_key` and the default relay protocol
+    pub fn builder(responder_public_key: impl Into<String>) -> SessionProposeResponseBuilder {
+        SessionProposeResponseBuilder {
+            relay: RelayProtocol::default(),
+            responder_public_key: responder_public_key.into(),
+        }
+    }
+}
+
+/// Builds the [`SessionProposeResponse`] a wallet answers a proposal with,
+/// see [`crate::WalletSettlementHandler::propose_response`].
+#[derive(Debug, Clone)]
+pub struct SessionProposeResponseBuilder {
+    relay: RelayProtocol,
+    responder_public_key: String,
+}
+
+impl SessionProposeResponseBuilder {
+    #[must_use]
+    pub fn relay(mut self, relay: RelayProtocol) -> Self {
+        self.relay = relay;
+        self
+    }
+
+    #[must_use]
+    pub fn responder_public_key(mut self, responder_public_key: impl Into<String>) -> Self {
+        self.responder_public_key = responder_public_key.into();
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> SessionProposeResponse {
+        SessionProposeResponse {
+            relay: self.relay,
+            responder_public_key: self.responder_public_key,
+        }
+    }
+}
+
 impl IntoUnknownError for SessionProposeRequest {
     fn unknown(&self) -> ResponseParamsError {
         ResponseParamsError::SessionPropose(ErrorParams::unknown())
@@ -1,6 +1,6 @@
 use {
-    crate::rpc::{ErrorParams, IntoUnknownError, ResponseParamsError},
-    monedero_domain::namespaces::ChainId,
+    crate::rpc::{ErrorParams, IntoUnknownError, ParamsError, ResponseParamsError},
+    monedero_domain::namespaces::{ChainId, EipMethod, Method, SolanaMethod},
     serde::{Deserialize, Serialize},
     std::fmt::{Display, Formatter},
 };
@@ -39,6 +39,57 @@ pub struct SessionRequestRequest {
     pub chain_id: ChainId,
 }
 
+impl SessionRequestRequest {
+    /// Checks the params of known Solana and EIP155 methods carry what
+    /// wallets need, e.g. `transaction` for `solana_signTransaction`, so
+    /// malformed requests fail before reaching the wallet. Other methods are
+    /// not checked.
+    pub fn validate_params(&self) -> Result<(), ParamsError> {
+        let params = &self.request.params;
+        let missing = match &self.request.method {
+            Method::Solana(method) => solana_fields(method)
+                .iter()
+                .find(|field| params.get(field).is_none()),
+            // EIP155 params are positional
+            Method::EIP155(method) => eip_fields(method)
+                .iter()
+                .enumerate()
+                .find(|(i, _)| params.get(i).is_none())
+                .map(|(_, field)| field),
+            Method::Other(_) => None,
+        };
+        missing.map_or(Ok(()), |field| {
+            Err(ParamsError::MissingParam {
+                method: self.request.method.clone(),
+                field,
+            })
+        })
+    }
+}
+
+/// Keys the params object of `method` must have
+const fn solana_fields(method: &SolanaMethod) -> &'static [&'static str] {
+    match method {
+        SolanaMethod::SignMessage => &["message", "pubkey"],
+        SolanaMethod::SignTransaction | SolanaMethod::SignAndSendTransaction => &["transaction"],
+        SolanaMethod::SignAllTransactions => &["transactions"],
+        SolanaMethod::Other(_) => &[],
+    }
+}
+
+/// Names of the params array entries `method` needs, in order
+const fn eip_fields(method: &EipMethod) -> &'static [&'static str] {
+    match method {
+        EipMethod::PersonalSign | EipMethod::PersonalSignExt => &["message", "address"],
+        EipMethod::Sign => &["address", "message"],
+        EipMethod::SignTypedData | EipMethod::SignTypedDataV4 => &["address", "typedData"],
+        EipMethod::SignTransaction | EipMethod::SendTransaction | EipMethod::SendTransactionExt => {
+            &["transaction"]
+        }
+        EipMethod::Other(_) => &[],
+    }
+}
+
 impl Display for SessionRequestRequest {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -49,6 +100,64 @@ impl Display for SessionRequestRequest {
     }
 }
 
+/// Builds a [`SessionRequestRequest`], checking the method belongs to the
+/// chain's namespace.
+#[derive(Debug, Clone)]
+pub struct SessionRequestBuilder {
+    chain_id: ChainId,
+    method: Method,
+    params: serde_json::Value,
+    expiry: Option<u64>,
+}
+
+impl SessionRequestBuilder {
+    pub const fn new(chain_id: ChainId, method: Method) -> Self {
+        Self {
+            chain_id,
+            method,
+            params: serde_json::Value::Null,
+            expiry: None,
+        }
+    }
+
+    #[must_use]
+    pub fn params(mut self, params: serde_json::Value) -> Self {
+        self.params = params;
+        self
+    }
+
+    #[must_use]
+    pub const fn expiry(mut self, expiry: u64) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// Fails with [`ParamsError::MethodChainMismatch`] when an EIP155 method
+    /// is paired with a non `eip155:` chain, or a Solana method with a non
+    /// `solana:` chain. [`Method::Other`] is accepted on any chain.
+    pub fn build(self) -> Result<SessionRequestRequest, ParamsError> {
+        let valid = match &self.method {
+            Method::EIP155(_) => matches!(self.chain_id, ChainId::EIP155(_)),
+            Method::Solana(_) => matches!(self.chain_id, ChainId::Solana(_)),
+            Method::Other(_) => true,
+        };
+        if !valid {
+            return Err(ParamsError::MethodChainMismatch {
+                method: self.method,
+                chain_id: self.chain_id,
+            });
+        }
+        Ok(SessionRequestRequest {
+            request: RequestMethod {
+                method: self.method,
+                params: self.params,
+                expiry: self.expiry,
+            },
+            chain_id: self.chain_id,
+        })
+    }
+}
+
 impl IntoUnknownError for SessionRequestRequest {
     fn unknown(&self) -> ResponseParamsError {
         ResponseParamsError::SessionRequest(ErrorParams::unknown())
@@ -60,8 +169,63 @@ mod tests {
     use {
         super::{super::tests::param_serde_test, *},
         anyhow::Result,
+        monedero_domain::namespaces::{ChainType, SolanaMethod},
     };
 
+    #[test]
+    fn test_builder_solana() -> Result<()> {
+        let chain_id = ChainId::Solana(ChainType::Dev);
+        let request =
+            SessionRequestBuilder::new(chain_id.clone(), Method::Solana(SolanaMethod::SignMessage))
+                .params(serde_json::json!({ "message": "hello" }))
+                .expiry(300)
+                .build()?;
+        assert_eq!(request.chain_id, chain_id);
+        assert_eq!(
+            request.request.method,
+            Method::Solana(SolanaMethod::SignMessage)
+        );
+        assert_eq!(request.request.expiry, Some(300));
+        Ok(())
+    }
+
+    #[test]
+    fn test_builder_method_chain_mismatch() {
+        let result = SessionRequestBuilder::new(
+            ChainId::EIP155(alloy_chains::Chain::sepolia()),
+            Method::Solana(SolanaMethod::SignMessage),
+        )
+        .build();
+        assert!(matches!(
+            result,
+            Err(ParamsError::MethodChainMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_params_solana() -> Result<()> {
+        let sign_message = |params| {
+            SessionRequestBuilder::new(
+                ChainId::Solana(ChainType::Dev),
+                Method::Solana(SolanaMethod::SignMessage),
+            )
+            .params(params)
+            .build()
+        };
+        sign_message(serde_json::json!({ "message": "hello", "pubkey": "AqP3" }))?
+            .validate_params()?;
+
+        let result = sign_message(serde_json::json!({ "message": "hello" }))?.validate_params();
+        assert!(matches!(
+            result,
+            Err(ParamsError::MissingParam {
+                field: "pubkey",
+                ..
+            })
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_serde_eth_sign_transaction() -> Result<()> {
         // https://specs.walletconnect.com/2.0/specs/clients/sign/
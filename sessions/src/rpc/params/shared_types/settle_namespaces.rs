@@ -3,6 +3,7 @@ use {
     std::collections::BTreeSet,
 };
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, PartialEq, Eq, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct SettleNamespace {
@@ -1,5 +1,6 @@
 use crate::rpc::{ErrorParams, PairDeleteRequest};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SdkErrors {
     InvalidMethod,
     InvalidEvent,
@@ -0,0 +1,39 @@
+use {
+    crate::{rpc::Metadata, ClientSession, Topic},
+    monedero_domain::namespaces::{Chains, Events, Methods, NamespaceName},
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// Summary of what a session allows, see [`ClientSession::capabilities`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCapabilities {
+    pub topic: Topic,
+    /// See [`ClientSession::peer_metadata`]
+    pub peer: Metadata,
+    pub chains: Chains,
+    pub methods: BTreeMap<NamespaceName, Methods>,
+    pub events: BTreeMap<NamespaceName, Events>,
+}
+
+impl ClientSession {
+    /// Chains, methods and events granted per namespace along with the peer's
+    /// metadata, from [`Self::namespaces`]
+    pub fn capabilities(&self) -> SessionCapabilities {
+        let namespaces = self.namespaces();
+        SessionCapabilities {
+            topic: self.topic(),
+            peer: self.peer_metadata().clone(),
+            chains: namespaces.chains(),
+            methods: namespaces
+                .iter()
+                .map(|(name, ns)| (name.clone(), ns.methods.clone()))
+                .collect(),
+            events: namespaces
+                .iter()
+                .map(|(name, ns)| (name.clone(), ns.events.clone()))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use {
+    crate::{actors::DebugSession, ClientSession, Topic},
+    xtra::prelude::*,
+};
+
+/// Snapshot of an active session, see
+/// [`crate::PairingManager::debug_sessions`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionDebug {
+    pub topic: Topic,
+    /// See [`ClientSession::handle_count`]
+    pub handle_count: usize,
+    pub time_remaining: chrono::Duration,
+}
+
+impl Handler<DebugSession> for ClientSession {
+    type Return = SessionDebug;
+
+    async fn handle(&mut self, _message: DebugSession, _ctx: &mut Context<Self>) -> Self::Return {
+        SessionDebug {
+            topic: self.topic(),
+            handle_count: self.handle_count(),
+            time_remaining: self.time_remaining(),
+        }
+    }
+}
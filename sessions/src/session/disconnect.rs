@@ -0,0 +1,83 @@
+use {
+    crate::{
+        actors::{ClearSession, SessionRequestHandlerActor},
+        rpc::{RequestParams, SessionDeleteRequest},
+        spawn_task,
+        transport::SessionTransport,
+    },
+    std::sync::atomic::{AtomicBool, Ordering},
+    tracing::{error, warn},
+    xtra::Address,
+};
+
+/// Send `wc_sessionDelete` to the peer and drop the session locally
+pub(super) async fn delete_session(
+    transport: &SessionTransport,
+    session_actor: &Address<SessionRequestHandlerActor>,
+) -> bool {
+    let accepted: bool = match transport
+        .publish_request(RequestParams::SessionDelete(SessionDeleteRequest::default()))
+        .await
+    {
+        Ok(false) => {
+            warn!("other side did not accept our delete request");
+            false
+        }
+        Ok(true) => true,
+        Err(e) => {
+            error!("failed send session delete: {e}");
+            false
+        }
+    };
+    let _ = session_actor
+        .send(ClearSession(transport.topic.clone()))
+        .await;
+    accepted
+}
+
+/// Shared by clones of a [`crate::ClientSession`], deletes the session once
+/// the last clone is dropped
+pub(super) struct DisconnectGuard {
+    armed: AtomicBool,
+    transport: SessionTransport,
+    session_actor: Address<SessionRequestHandlerActor>,
+}
+
+impl DisconnectGuard {
+    pub(super) const fn new(
+        transport: SessionTransport,
+        session_actor: Address<SessionRequestHandlerActor>,
+    ) -> Self {
+        Self {
+            armed: AtomicBool::new(true),
+            transport,
+            session_actor,
+        }
+    }
+
+    /// Session was deleted explicitly, nothing to do on drop
+    pub(super) fn disarm(&self) {
+        self.armed.store(false, Ordering::Release);
+    }
+}
+
+impl Drop for DisconnectGuard {
+    fn drop(&mut self) {
+        if !self.armed.load(Ordering::Acquire) {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if tokio::runtime::Handle::try_current().is_err() {
+            warn!(
+                "no runtime available, cannot delete session {}",
+                self.transport
+            );
+            return;
+        }
+        let transport = self.transport.clone();
+        let session_actor = self.session_actor.clone();
+        spawn_task(async move {
+            delete_session(&transport, &session_actor).await;
+        });
+    }
+}
@@ -0,0 +1,40 @@
+use {
+    monedero_domain::{namespaces::Namespaces, SessionSettled, Topic},
+    std::fmt::{Display, Formatter},
+};
+
+/// Capacity of the session lifecycle broadcast channel.
+/// Slow receivers will observe `RecvError::Lagged` once this is exceeded.
+pub const SESSION_EVENTS_CAPACITY: usize = 64;
+
+/// Session lifecycle notifications
+///
+/// See [`crate::PairingManager::session_events`]
+#[derive(Debug, Clone)]
+pub enum SessionEvent {
+    Settled(SessionSettled),
+    Deleted(Topic),
+    Extended(Topic, i64),
+    Updated(Topic, Namespaces),
+}
+
+impl SessionEvent {
+    pub const fn topic(&self) -> &Topic {
+        match self {
+            Self::Settled(settled) => &settled.topic,
+            Self::Deleted(topic) | Self::Extended(topic, _) | Self::Updated(topic, _) => topic,
+        }
+    }
+}
+
+impl Display for SessionEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let topic = crate::shorten_topic(self.topic());
+        match self {
+            Self::Settled(_) => write!(f, "settled topic={topic}"),
+            Self::Deleted(_) => write!(f, "deleted topic={topic}"),
+            Self::Extended(_, expiry) => write!(f, "extended topic={topic} expiry={expiry}"),
+            Self::Updated(_, _) => write!(f, "updated topic={topic}"),
+        }
+    }
+}
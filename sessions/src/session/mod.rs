@@ -1,35 +1,54 @@
 use {
     crate::{
-        rpc::{RequestParams, SessionDeleteRequest},
+        rpc::{Event, Metadata, RequestParams, SessionEventRequest, SessionRequestBuilder},
+        spawn_task,
         transport::SessionTransport,
         Error,
+        PairingManager,
         Result,
+        SessionError,
         SessionHandler,
         Topic,
     },
-    monedero_domain::SessionSettled,
+    monedero_domain::{MessageId, SessionSettled},
     serde::de::DeserializeOwned,
+    serde_json::json,
     std::{
         fmt::{Debug, Display, Formatter},
-        sync::Arc,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+            PoisonError,
+            RwLock,
+        },
         time::Duration,
     },
     tokio::sync::Mutex,
-    tracing::{error, warn},
+    tracing::{debug, warn},
     xtra::prelude::*,
 };
 
+mod capabilities;
+mod debug;
+mod disconnect;
+mod events;
 mod pending;
 mod session_delete;
-mod session_ping;
+mod session_event;
 mod session_request;
 
-pub(crate) use pending::PendingSession;
 use {
-    crate::actors::{ClearSession, SessionRequestHandlerActor},
-    monedero_cipher::CipherError,
-    monedero_domain::namespaces::Namespaces,
+    crate::actors::SessionRequestHandlerActor,
+    disconnect::{delete_session, DisconnectGuard},
+    monedero_cipher::{Cipher, CipherError},
+    monedero_domain::namespaces::{ChainId, Method, NamespaceName, Namespaces, SolanaMethod},
 };
+pub use {capabilities::SessionCapabilities, debug::SessionDebug, events::SessionEvent};
+pub(crate) use {events::SESSION_EVENTS_CAPACITY, pending::PendingSession};
+
+/// Session event telling the peer a request was cancelled, see
+/// [`ClientSession::cancel_request`]
+pub const REQUEST_CANCELLED: &str = "requestCancelled";
 
 #[derive(Clone, Hash, Eq, PartialEq)]
 pub(crate) enum Category {
@@ -64,10 +83,18 @@ impl Display for Category {
 #[derive(Clone, Actor)]
 pub struct ClientSession {
     pub settled: Arc<SessionSettled>,
+    peer_metadata: Arc<Metadata>,
+    namespaces: Arc<RwLock<Namespaces>>,
     transport: SessionTransport,
     session_actor: Address<SessionRequestHandlerActor>,
     handler: Arc<Mutex<Box<dyn SessionHandler>>>,
     category: Category,
+    sync_namespaces: bool,
+    disconnect_guard: Option<Arc<DisconnectGuard>>,
+    cipher: Cipher,
+    /// Set once the settlement is acknowledged and stored, see
+    /// [`Self::is_settled`]
+    complete: Arc<AtomicBool>,
 }
 
 impl Debug for ClientSession {
@@ -83,18 +110,27 @@ impl Debug for ClientSession {
 
 impl ClientSession {
     pub(crate) async fn new(
-        session_actor: Address<SessionRequestHandlerActor>,
-        transport: SessionTransport,
+        mgr: &PairingManager,
         settled: SessionSettled,
+        peer_metadata: Metadata,
         handler: Arc<Mutex<Box<dyn SessionHandler>>>,
         category: Category,
     ) -> Result<Self> {
         let me = Self {
-            session_actor,
-            transport,
+            session_actor: mgr.actors().session(),
+            transport: SessionTransport {
+                topic: settled.topic.clone(),
+                transport: mgr.topic_transport(),
+            },
+            namespaces: Arc::new(RwLock::new(settled.namespaces.clone())),
             settled: Arc::new(settled),
+            peer_metadata: Arc::new(peer_metadata),
             handler,
             category,
+            sync_namespaces: mgr.sync_namespaces(),
+            disconnect_guard: None,
+            cipher: mgr.ciphers(),
+            complete: Arc::new(AtomicBool::new(false)),
         };
         me.register().await?;
         Ok(me)
@@ -107,48 +143,211 @@ impl ClientSession {
         Ok(())
     }
 
-    pub fn namespaces(&self) -> &Namespaces {
-        &self.settled.namespaces
+    /// Current namespaces of the session. These may differ from the
+    /// settlement when account sync is enabled, see
+    /// [`crate::ReownBuilder::sync_namespaces`]
+    pub fn namespaces(&self) -> Namespaces {
+        self.namespaces
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Chains granted across every namespace of the session
+    pub fn chains(&self) -> Vec<ChainId> {
+        self.namespaces().chains().0.into_iter().collect()
+    }
+
+    /// Whether `chain` was granted in the session
+    pub fn supports_chain(&self, chain: &ChainId) -> bool {
+        self.namespaces()
+            .get(&NamespaceName::from(chain))
+            .is_some_and(|ns| ns.chains.0.contains(chain))
     }
 
     pub fn topic(&self) -> Topic {
         self.transport.topic.clone()
     }
 
+    /// Whether settlement has completed. A wallet's session is registered
+    /// before the dapp acknowledges the settlement, until then publishing
+    /// fails with [`SessionError::NotSettled`].
+    pub fn is_settled(&self) -> bool {
+        self.complete.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn mark_settled(&self) {
+        self.complete.store(true, Ordering::Release);
+    }
+
+    /// Time left until the session expires, from the stored settlement so
+    /// extensions are included. Zero once expired.
+    pub fn time_remaining(&self) -> chrono::Duration {
+        match self.cipher.settlement(&self.topic()) {
+            Ok(Some(settled)) => settled.time_remaining(),
+            _ => self.settled.time_remaining(),
+        }
+    }
+
+    /// Live clones of this session, including the one kept by the session's
+    /// actor until the session is deleted. Only meant for tracking down
+    /// leaked handles, see [`crate::PairingManager::debug_sessions`].
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.settled)
+    }
+
+    /// Metadata the peer declared during pairing: the wallet's settlement
+    /// controller for a dapp, the proposer for a wallet. Sessions restored
+    /// from storage have default metadata.
+    pub fn peer_metadata(&self) -> &Metadata {
+        &self.peer_metadata
+    }
+
+    /// Replace the handler of the requests and events the peer sends on this
+    /// session. Only this session's topic is affected, every other session
+    /// of the manager keeps its own handler.
+    pub async fn set_handler<T: SessionHandler>(&self, handler: T) {
+        *self.handler.lock().await = Box::new(handler);
+    }
+
     pub async fn publish_request<R: DeserializeOwned>(&self, params: RequestParams) -> Result<R> {
-        match self.transport.publish_request(params).await {
+        self.publish(params, None, |_| {}).await
+    }
+
+    /// Like [`Self::publish_request`], handing the request id to `sent` once
+    /// the relay accepted the request, for [`Self::cancel_request`]
+    pub async fn publish_request_with_id<R: DeserializeOwned>(
+        &self,
+        params: RequestParams,
+        sent: impl FnOnce(MessageId) + Send,
+    ) -> Result<R> {
+        self.publish(params, None, sent).await
+    }
+
+    /// Stop waiting for the response to request `id`, its publish fails with
+    /// [`crate::TransportError::Cancelled`]. The peer is sent a
+    /// [`REQUEST_CANCELLED`] event carrying `id` so it can stop prompting, it
+    /// is free to ignore it. Requests no longer pending are left alone.
+    pub async fn cancel_request(&self, id: MessageId) -> Result<()> {
+        if !self.transport.cancel(id).await? {
+            return Ok(());
+        }
+        let Some(chain_id) = self.chains().into_iter().next() else {
+            return Ok(());
+        };
+        let event = RequestParams::SessionEvent(SessionEventRequest {
+            event: Event {
+                name: String::from(REQUEST_CANCELLED),
+                data: json!({ "id": id }),
+            },
+            chain_id,
+        });
+        let transport = self.transport.clone();
+        spawn_task(async move {
+            if let Err(e) = transport.publish_request::<bool>(event).await {
+                debug!("peer did not take the cancel of request {id}: {e}");
+            }
+        });
+        Ok(())
+    }
+
+    /// Like [`Self::publish_request`], but the relay keeps the request for
+    /// `ttl` seconds instead of the method's default, e.g. for requests that
+    /// need a slow approval on a hardware wallet.
+    ///
+    /// `ttl` is clamped to the relay bounds
+    /// [`crate::rpc::IRN_MIN_TTL`]..=[`crate::rpc::IRN_MAX_TTL`].
+    pub async fn publish_request_with_ttl<R: DeserializeOwned>(
+        &self,
+        params: RequestParams,
+        ttl: u64,
+    ) -> Result<R> {
+        self.publish(params, Some(ttl), |_| {}).await
+    }
+
+    async fn publish<R: DeserializeOwned>(
+        &self,
+        params: RequestParams,
+        ttl: Option<u64>,
+        sent: impl FnOnce(MessageId) + Send,
+    ) -> Result<R> {
+        // the settlement itself is what completes it
+        if !self.is_settled() && !matches!(params, RequestParams::SessionSettle(_)) {
+            return Err(SessionError::NotSettled(self.topic()).into());
+        }
+        if !self.cipher.is_subscribed(&self.transport.topic) {
+            return Err(SessionError::NotSubscribed(self.topic()).into());
+        }
+        match self
+            .transport
+            .publish_request_notify(params, ttl, sent)
+            .await
+        {
             Ok(r) => Ok(r),
-            Err(Error::CipherError(CipherError::UnknownTopic(_))) => {
-                Err(Error::NoClientSession(self.topic()))
+            Err(Error::Cipher(CipherError::UnknownTopic(_))) => {
+                Err(SessionError::NoClientSession(self.topic()).into())
             }
             Err(e) => Err(e),
         }
     }
 
+    /// Publishes `method` on the single Solana chain of the session, sparing
+    /// the caller from building a [`crate::rpc::SessionRequestRequest`]. Fails
+    /// with [`SessionError::AmbiguousChain`] when several Solana chains were
+    /// settled, use [`Self::publish_request`] then.
+    pub async fn solana_request(
+        &self,
+        method: SolanaMethod,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let chain_id = {
+            let namespaces = self.namespaces();
+            let mut chains = namespaces
+                .get(&NamespaceName::Solana)
+                .map(|ns| ns.chains.iter().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            if chains.len() > 1 {
+                return Err(SessionError::AmbiguousChain(NamespaceName::Solana).into());
+            }
+            chains
+                .pop()
+                .ok_or(SessionError::NoChain(NamespaceName::Solana))?
+        };
+        let request = SessionRequestBuilder::new(chain_id, Method::Solana(method))
+            .params(params)
+            .build()?;
+        self.publish_request(RequestParams::SessionRequest(request))
+            .await
+    }
+
     pub async fn ping(&self) -> Result<bool> {
         self.publish_request(RequestParams::SessionPing(())).await
     }
 
     pub async fn delete(&self) -> bool {
-        let accepted: bool = match self
-            .publish_request(RequestParams::SessionDelete(SessionDeleteRequest::default()))
-            .await
-        {
-            Ok(false) => {
-                warn!("other side did not accept our delete request");
-                false
-            }
-            Ok(true) => true,
-            Err(e) => {
-                error!("failed send session delete: {e}");
-                false
-            }
-        };
-        let _ = self
-            .session_actor
-            .send(ClearSession(self.transport.topic.clone()))
-            .await;
-        accepted
+        if let Some(guard) = &self.disconnect_guard {
+            guard.disarm();
+        }
+        delete_session(&self.transport, &self.session_actor).await
+    }
+
+    /// Best effort `wc_sessionDelete` when the last clone of this session is
+    /// dropped without calling [`Self::delete`].
+    ///
+    /// Only this instance and clones made from it afterwards are tracked.
+    #[must_use]
+    pub fn disconnect_on_drop(mut self, enabled: bool) -> Self {
+        // replacing the last reference to a previous guard must not delete
+        if let Some(previous) = self.disconnect_guard.take().and_then(Arc::into_inner) {
+            previous.disarm();
+        }
+        self.disconnect_guard = enabled.then(|| {
+            Arc::new(DisconnectGuard::new(
+                self.transport.clone(),
+                self.session_actor.clone(),
+            ))
+        });
+        self
     }
 
     pub async fn pinger(&self, duration: Duration) {
@@ -161,3 +360,50 @@ impl ClientSession {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{mock_connection_opts, KvStorage, MockRelay, NoopSessionHandler, ReownBuilder},
+        monedero_domain::ProjectId,
+    };
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_publish_before_settled() -> anyhow::Result<()> {
+        let p = ProjectId::from("987f2292c12194ae69ddb6c52ceb1d62");
+        let _relay = MockRelay::start().await?;
+        let manager = ReownBuilder::new(p.clone())
+            .connect_opts(mock_connection_opts(&p))
+            .store(KvStorage::mem())
+            .build()
+            .await?;
+        let topic = Topic::generate();
+        let session = ClientSession::new(
+            &manager,
+            SessionSettled {
+                topic: topic.clone(),
+                namespaces: Namespaces::default(),
+                expiry: (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp(),
+            },
+            Metadata::default(),
+            Arc::new(Mutex::new(Box::new(NoopSessionHandler))),
+            Category::Wallet,
+        )
+        .await?;
+        assert!(!session.is_settled());
+        assert!(matches!(
+            session.ping().await,
+            Err(Error::Session(SessionError::NotSettled(t))) if t == topic
+        ));
+
+        // clones share the flag, past it the usual checks apply
+        session.clone().mark_settled();
+        assert!(session.is_settled());
+        assert!(matches!(
+            session.ping().await,
+            Err(Error::Session(SessionError::NotSubscribed(_)))
+        ));
+        Ok(())
+    }
+}
@@ -1,32 +1,54 @@
 use {
     crate::{
-        rpc::{RequestParams, SessionSettleRequest},
+        rpc::{Metadata, RequestParams, SessionSettleRequest},
         session::Category,
-        transport::SessionTransport,
+        spawn_task,
         ClientSession,
         Error,
         PairingManager,
+        ProposalStage,
         Result,
+        SessionError,
         SessionHandler,
+        ShortTopic,
     },
+    backoff::{future::retry, ExponentialBackoffBuilder},
     dashmap::DashMap,
     monedero_domain::{PairingTopic, SessionSettled},
-    std::sync::Arc,
+    std::{
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
     tokio::sync::{
+        mpsc,
         oneshot::{self, Sender},
         Mutex,
     },
-    tracing::warn,
+    tracing::{info, warn},
 };
 
+/// First delay before re-publishing a settlement that failed to send
+const SETTLE_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+/// Give up re-publishing a settlement after this long
+const SETTLE_RETRY_MAX_ELAPSED: Duration = Duration::from_secs(10);
+
 pub struct HandlerContainer {
+    /// Tells a re-added proposal on the same pairing from the one an expiry
+    /// was scheduled for
+    id: u64,
     pub tx: Sender<Result<ClientSession>>,
     pub handlers: Arc<Mutex<Box<dyn SessionHandler>>>,
+    pub progress: Option<mpsc::UnboundedSender<ProposalStage>>,
 }
 
 #[derive(Clone, Default)]
 pub struct PendingSession {
     pending: Arc<DashMap<PairingTopic, HandlerContainer>>,
+    next_id: Arc<AtomicU64>,
+    timeout: Option<Duration>,
 }
 
 impl PendingSession {
@@ -34,56 +56,110 @@ impl PendingSession {
         Self::default()
     }
 
+    /// Proposals not settled within `timeout` fail with
+    /// [`SessionError::ProposalTimeout`] and are dropped
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..Self::default()
+        }
+    }
+
+    /// Proposals awaiting settlement
+    pub fn count(&self) -> usize {
+        self.pending.len()
+    }
+
     pub fn add<T: SessionHandler>(
         &self,
         topic: PairingTopic,
         handlers: T,
+    ) -> oneshot::Receiver<Result<ClientSession>> {
+        self.add_with_progress(topic, handlers, None)
+    }
+
+    /// Like [`Self::add`], reporting [`ProposalStage`]s to `progress`
+    pub fn add_with_progress<T: SessionHandler>(
+        &self,
+        topic: PairingTopic,
+        handlers: T,
+        progress: Option<mpsc::UnboundedSender<ProposalStage>>,
     ) -> oneshot::Receiver<Result<ClientSession>> {
         let (tx, rx) = oneshot::channel::<Result<ClientSession>>();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
         let h = HandlerContainer {
+            id,
             tx,
             handlers: Arc::new(Mutex::new(Box::new(handlers))),
+            progress,
         };
-        self.pending.insert(topic, h);
+        self.pending.insert(topic.clone(), h);
+        if let Some(timeout) = self.timeout {
+            let pending = self.clone();
+            spawn_task(async move {
+                tokio::time::sleep(timeout).await;
+                pending.expire(&topic, id);
+            });
+        }
         rx
     }
 
+    fn expire(&self, topic: &PairingTopic, id: u64) {
+        let Some((_, handler)) = self.pending.remove_if(topic, |_, h| h.id == id) else {
+            return;
+        };
+        warn!(
+            "proposal on pairing {} expired before settlement",
+            ShortTopic(topic)
+        );
+        let _ = handler
+            .tx
+            .send(Err(SessionError::ProposalTimeout(topic.clone()).into()));
+    }
+
+    /// Report `stage` to the progress channel of the pending proposal, if any
+    pub fn progress(&self, topic: &PairingTopic, stage: ProposalStage) {
+        if let Some(handler) = self.pending.get(topic) {
+            report(handler.progress.as_ref(), stage);
+        }
+    }
+
     pub fn error(&self, topic: &PairingTopic, err: Error) {
         if let Ok(handlers) = self.remove(topic) {
             if handlers.tx.send(Err(err)).is_err() {
-                warn!("settlement channel has closed! {topic}");
+                warn!("settlement channel has closed! {}", ShortTopic(topic));
             }
         } else {
-            warn!("failed to find pairing topic {topic} in pending handlers");
-        };
+            warn!(
+                "failed to find pairing topic {} in pending handlers",
+                ShortTopic(topic)
+            );
+        }
     }
 
     fn remove(&self, topic: &PairingTopic) -> Result<HandlerContainer> {
         let (_, handler) = self
             .pending
             .remove(topic)
-            .ok_or(Error::InvalidPendingHandler(topic.clone()))?;
+            .ok_or_else(|| SessionError::InvalidPendingHandler(topic.clone()))?;
         Ok(handler)
     }
 
+    /// Resolve the proposal pending on `pairing_topic` with the session
     pub async fn settled(
         &self,
         mgr: &PairingManager,
+        pairing_topic: &PairingTopic,
         settled: SessionSettled,
+        peer_metadata: Metadata,
         category: Category,
         send_to_peer: Option<SessionSettleRequest>,
     ) -> Result<ClientSession> {
-        let pairing_topic = mgr.topic().ok_or(Error::NoPairingTopic)?;
-        let handlers = self.remove(&pairing_topic)?;
-        let actors = mgr.actors();
-        let session_transport = SessionTransport {
-            topic: settled.topic.clone(),
-            transport: mgr.topic_transport(),
-        };
+        let handlers = self.remove(pairing_topic)?;
         let client_session = ClientSession::new(
-            actors.session(),
-            session_transport,
+            mgr,
             settled.clone(),
+            peer_metadata,
             handlers.handlers,
             category,
         )
@@ -93,23 +169,110 @@ impl PendingSession {
         // warn!("failed to ping session: {e}. Session maybe broken, try new pairing");
         // }
         if let Some(req) = send_to_peer {
-            let result = client_session
-                .publish_request::<bool>(RequestParams::SessionSettle(req))
-                .await;
-            let client_session_result: Result<ClientSession> = match result {
-                Ok(true) => Ok(client_session.clone()),
-                Ok(false) => Err(Error::ProposalRejected),
-                Err(e) => Err(e),
-            };
+            let client_session_result: Result<ClientSession> =
+                match publish_settlement(&client_session, req).await {
+                    Ok(true) => Ok(client_session.clone()),
+                    Ok(false) => Err(SessionError::ProposalRejected.into()),
+                    Err(e) => Err(e),
+                };
+            if client_session_result.is_ok() {
+                client_session.mark_settled();
+                report(handlers.progress.as_ref(), ProposalStage::Settled);
+            }
             if handlers.tx.send(client_session_result).is_err() {
                 warn!("oneshot proposal channel has closed");
             }
         } else {
+            client_session.mark_settled();
+            report(handlers.progress.as_ref(), ProposalStage::Settled);
             handlers
                 .tx
                 .send(Ok(client_session.clone()))
-                .map_err(|_| Error::SettlementRecvError)?;
+                .map_err(|_| SessionError::SettlementRecvError)?;
         }
         Ok(client_session)
     }
 }
+
+fn report(progress: Option<&mpsc::UnboundedSender<ProposalStage>>, stage: ProposalStage) {
+    if let Some(progress) = progress {
+        // the caller may have stopped listening, the proposal goes on
+        let _ = progress.send(stage);
+    }
+}
+
+/// Publish the settlement, retrying with backoff while the relay can't take
+/// it. An answer from the peer, even an error, is final. Fails with
+/// [`SessionError::SettlementPublishFailed`] once retries are exhausted.
+async fn publish_settlement(session: &ClientSession, req: SessionSettleRequest) -> Result<bool> {
+    let backoff = ExponentialBackoffBuilder::new()
+        .with_initial_interval(SETTLE_RETRY_INTERVAL)
+        .with_max_elapsed_time(Some(SETTLE_RETRY_MAX_ELAPSED))
+        .build();
+    retry(backoff, || async {
+        session
+            .publish_request::<bool>(RequestParams::SessionSettle(req.clone()))
+            .await
+            .map_err(|e| {
+                if matches!(e, Error::RpcError(_)) {
+                    return backoff::Error::permanent(e);
+                }
+                info!("failed to publish settlement, retrying: {e}");
+                backoff::Error::transient(e)
+            })
+    })
+    .await
+    .map_err(|e| match e {
+        Error::RpcError(_) => e,
+        _ => SessionError::SettlementPublishFailed(e.to_string()).into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{io::Write, sync::Mutex as StdMutex},
+        tracing_subscriber::fmt::MakeWriter,
+    };
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<StdMutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_logs_short_topic() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .finish();
+        let topic = PairingTopic::generate();
+        tracing::subscriber::with_default(subscriber, || {
+            PendingSession::new().error(&topic, Error::ReceiveError);
+        });
+        let logged = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let short = ShortTopic(&topic).to_string();
+        assert_eq!(9, short.len());
+        assert!(logged.contains(&format!("failed to find pairing topic {short} in")));
+        assert!(!logged.contains(&topic.to_string()));
+    }
+}
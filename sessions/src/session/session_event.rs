@@ -0,0 +1,88 @@
+use {
+    crate::{rpc::SessionEventRequest, ClientSession},
+    monedero_domain::{
+        namespaces::{Account, ChainId, NamespaceName, Namespaces},
+        SessionSettled,
+    },
+    serde_json::Value,
+    std::{str::FromStr, sync::PoisonError},
+    tracing::debug,
+    xtra::prelude::*,
+};
+
+const ACCOUNTS_CHANGED: &str = "accountsChanged";
+const CHAIN_CHANGED: &str = "chainChanged";
+
+/// `accountsChanged` carries either plain addresses for the event's chain or
+/// CAIP-10 accounts
+fn parse_accounts(chain: &ChainId, data: &Value) -> Option<Vec<Account>> {
+    let accounts = data.as_array()?;
+    accounts
+        .iter()
+        .map(|a| {
+            let a = a.as_str()?;
+            Some(Account::from_str(a).unwrap_or_else(|_| Account {
+                address: String::from(a),
+                chain: chain.clone(),
+            }))
+        })
+        .collect()
+}
+
+/// `chainChanged` carries the reference of the new chain, as a number, hex
+/// string or a full CAIP-2 id
+fn parse_chain(chain: &ChainId, data: &Value) -> Option<ChainId> {
+    let reference = match data {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if s.contains(':') => return ChainId::from_str(s).ok(),
+        Value::String(s) => s.strip_prefix("0x").map_or_else(
+            || s.clone(),
+            |hex| u64::from_str_radix(hex, 16).map_or_else(|_| s.clone(), |n| n.to_string()),
+        ),
+        _ => return None,
+    };
+    ChainId::from_str(&format!("{}:{reference}", NamespaceName::from(chain))).ok()
+}
+
+fn apply_event(namespaces: &mut Namespaces, message: &SessionEventRequest) -> bool {
+    let chain = &message.chain_id;
+    let data = &message.event.data;
+    match message.event.name.as_str() {
+        ACCOUNTS_CHANGED => parse_accounts(chain, data)
+            .is_some_and(|accounts| namespaces.replace_accounts(chain, accounts)),
+        CHAIN_CHANGED => parse_chain(chain, data).is_some_and(|c| namespaces.add_chain(c)),
+        _ => false,
+    }
+}
+
+impl Handler<SessionEventRequest> for ClientSession {
+    /// The updated settlement, if the event changed this session's namespaces
+    type Return = Option<SessionSettled>;
+
+    async fn handle(
+        &mut self,
+        message: SessionEventRequest,
+        _ctx: &mut Context<Self>,
+    ) -> Self::Return {
+        self.handler.lock().await.event(message.event.clone()).await;
+        if !self.sync_namespaces {
+            return None;
+        }
+        let mut namespaces = self
+            .namespaces
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !apply_event(&mut namespaces, &message) {
+            debug!(
+                "session event {} did not change namespaces",
+                message.event.name
+            );
+            return None;
+        }
+        Some(SessionSettled {
+            topic: self.topic(),
+            namespaces: namespaces.clone(),
+            expiry: self.settled.expiry,
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use {
     crate::{
         rpc::{
+            ErrorParams,
             ResponseParamsError,
             ResponseParamsSuccess,
             RpcResponsePayload,
@@ -28,6 +29,12 @@ impl Handler<SessionRequestRequest> for ClientSession {
             WalletRequestResponse::Error(e) => {
                 RpcResponsePayload::Error(ResponseParamsError::SessionRequest(e.into()))
             }
+            WalletRequestResponse::ErrorWithMessage(e, message) => {
+                RpcResponsePayload::Error(ResponseParamsError::SessionRequest(ErrorParams {
+                    message,
+                    ..e.into()
+                }))
+            }
         }
     }
 }
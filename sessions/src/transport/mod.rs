@@ -1,11 +1,12 @@
 use {
     crate::{
-        actors::{SendRequest, TransportActor, Unsubscribe},
+        actors::{CancelRequest, SendRequest, TransportActor, Unsubscribe},
         rpc::{RequestParams, ResponseParams},
         wait,
         Result,
+        ShortTopic,
     },
-    monedero_domain::Topic,
+    monedero_domain::{MessageId, Topic},
     serde::de::DeserializeOwned,
     std::fmt::{Debug, Display, Formatter},
     xtra::Address,
@@ -20,6 +21,12 @@ impl TopicTransport {
     pub(crate) async fn unsubscribe(&self, topic: Topic) -> Result<()> {
         self.transport_actor.send(Unsubscribe(topic)).await?
     }
+
+    /// Fail the pending request `id` with [`crate::TransportError::Cancelled`].
+    /// Returns whether it was still awaiting its response.
+    pub(crate) async fn cancel(&self, id: MessageId) -> Result<bool> {
+        self.transport_actor.send(CancelRequest(id)).await?
+    }
 }
 
 impl TopicTransport {
@@ -27,28 +34,55 @@ impl TopicTransport {
         Self { transport_actor }
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    #[tracing::instrument(level = "trace", skip(self))]
     pub async fn publish_request<R: DeserializeOwned>(
         &self,
         topic: Topic,
         params: RequestParams,
+    ) -> Result<R> {
+        self.publish_request_with_ttl(topic, params, None).await
+    }
+
+    /// Publish with the relay ttl (seconds) overridden, see
+    /// [`crate::rpc::IrnMetadata::with_ttl`]. The response is awaited for the
+    /// same ttl.
+    pub async fn publish_request_with_ttl<R: DeserializeOwned>(
+        &self,
+        topic: Topic,
+        params: RequestParams,
+        ttl: Option<u64>,
+    ) -> Result<R> {
+        self.publish_request_notify(topic, params, ttl, |_| {})
+            .await
+    }
+
+    /// Like [`Self::publish_request_with_ttl`], calling `sent` with the request
+    /// id once the relay accepted the request, before the response is awaited
+    #[allow(clippy::cast_possible_truncation)]
+    #[tracing::instrument(level = "trace", skip(self, topic, sent), fields(topic = %ShortTopic(&topic)))]
+    pub(crate) async fn publish_request_notify<R: DeserializeOwned>(
+        &self,
+        topic: Topic,
+        params: RequestParams,
+        ttl: Option<u64>,
+        sent: impl FnOnce(MessageId) + Send,
     ) -> Result<R> {
         let (id, ttl, rx) = self
             .transport_actor
-            .send(SendRequest(topic, params))
+            .send(SendRequest(topic, params, ttl))
             .await??;
+        sent(id);
 
         if let Ok(result) = wait::wait_until((ttl.as_secs() * 1000) as u32, rx).await {
             return match result {
-                Ok(response) => match response.params {
+                Ok(None) => Err(crate::TransportError::Cancelled(id).into()),
+                Ok(Some(response)) => match response.params {
                     ResponseParams::Success(v) => Ok(serde_json::from_value(v)?),
                     ResponseParams::Err(v) => Err(crate::Error::RpcError(v)),
                 },
-                Err(_) => Err(crate::Error::ResponseChannelError(id)),
+                Err(_) => Err(crate::TransportError::ResponseChannelError(id).into()),
             };
         }
-        Err(crate::Error::ResponseTimeout)
+        Err(crate::TransportError::ResponseTimeout.into())
     }
 }
 
@@ -76,4 +110,19 @@ impl SessionTransport {
             .publish_request(self.topic.clone(), params)
             .await
     }
+
+    pub(crate) async fn publish_request_notify<R: DeserializeOwned>(
+        &self,
+        params: RequestParams,
+        ttl: Option<u64>,
+        sent: impl FnOnce(MessageId) + Send,
+    ) -> Result<R> {
+        self.transport
+            .publish_request_notify(self.topic.clone(), params, ttl, sent)
+            .await
+    }
+
+    pub(crate) async fn cancel(&self, id: MessageId) -> Result<bool> {
+        self.transport.cancel(id).await
+    }
 }
@@ -1,4 +1,4 @@
-use crate::Error::WaitError;
+use crate::TransportError::WaitError;
 
 #[allow(clippy::option_if_let_else)]
 #[cfg(not(target_family = "wasm"))]
@@ -13,7 +13,7 @@ where
     .await
     {
         Ok(result) => Ok(result),
-        Err(_) => Err(WaitError(duration_ms)),
+        Err(_) => Err(WaitError(duration_ms).into()),
     }
 }
 
@@ -31,6 +31,6 @@ where
 
     match select(pinned_future, timeout_future).await {
         Either::Left((v, _)) => Ok(v),
-        Either::Right(_) => Err(WaitError(duration_ms)),
+        Either::Right(_) => Err(WaitError(duration_ms).into()),
     }
 }
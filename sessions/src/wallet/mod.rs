@@ -6,23 +6,28 @@ use {
         rpc::{
             Controller,
             Metadata,
-            RelayProtocol,
+            RequestParams,
             ResponseParamsError,
             RpcResponsePayload,
             SdkErrors,
             SessionProposeRequest,
             SessionSettleRequest,
+            SessionUpdateRequest,
         },
         session::{Category, PendingSession},
         spawn_task,
-        wallet::settlement::WalletSettlementActor,
+        wallet::settlement::{SettlementRequest, WalletSettlementActor},
         PairingManager,
+        ProposalContext,
         ProposeFuture,
         Result,
+        SessionError,
         SessionHandler,
+        Topic,
         WalletSettlementHandler,
     },
-    monedero_domain::{Pairing, SessionSettled},
+    dashmap::DashMap,
+    monedero_domain::{namespaces::Namespaces, Pairing, SessionSettled},
     std::{
         fmt::{Debug, Display, Formatter},
         str::FromStr,
@@ -32,12 +37,20 @@ use {
     xtra::prelude::*,
 };
 
+/// Decides which proposals may reach the [`WalletSettlementHandler`], see
+/// [`Wallet::with_allowlist`]
+type ProposalAllowlist = Arc<dyn Fn(&SessionProposeRequest) -> bool + Send + Sync>;
+
 #[derive(Clone, xtra::Actor)]
 pub struct Wallet {
     manager: PairingManager,
     pending: Arc<PendingSession>,
     settlement_handler: Address<WalletSettlementActor>,
     metadata: Metadata,
+    allowlist: Option<ProposalAllowlist>,
+    /// Namespaces each settled session's proposal required, the floor for
+    /// [`Wallet::update_session`]
+    required: Arc<DashMap<Topic, Namespaces>>,
 }
 
 impl Display for Wallet {
@@ -56,18 +69,36 @@ impl Wallet {
     #[tracing::instrument(skip(request), level = "info")]
     async fn send_settlement(
         &self,
+        pairing_topic: Topic,
         request: SessionProposeRequest,
         public_key: String,
     ) -> Result<()> {
         let session_topic = self
             .manager
-            .register_dapp_pk(request.proposer.clone())
+            .register_dapp_pk(&pairing_topic, request.proposer.clone())
             .await?;
-        let namespaces = self.settlement_handler.send(request).await??;
+        let peer = request.proposer.metadata.clone();
+        let required = request.required_namespaces.clone();
+        let relay = request.relay();
+        let pairing = self
+            .manager
+            .ciphers()
+            .pairing_by_topic(&pairing_topic)
+            .ok_or(SessionError::NoPairingTopic)?;
+        let context = ProposalContext {
+            pairing_methods: pairing.methods(),
+            pairing_topic: pairing.topic,
+        };
+        let namespaces = self
+            .settlement_handler
+            .send(SettlementRequest(request, context))
+            .await??;
+        namespaces.validate_accounts()?;
+        namespaces.validate(&required)?;
         let now = chrono::Utc::now();
         let future = now + chrono::Duration::hours(24);
         let session_settlement = SessionSettleRequest {
-            relay: RelayProtocol::default(),
+            relay,
             controller: Controller {
                 public_key,
                 metadata: self.metadata.clone(),
@@ -75,14 +106,17 @@ impl Wallet {
             namespaces: namespaces.clone(),
             expiry: future.timestamp(),
         };
+        self.required.insert(session_topic.clone(), required);
         self.pending
             .settled(
                 &self.manager,
+                &pairing_topic,
                 SessionSettled {
                     topic: session_topic,
                     namespaces,
                     expiry: session_settlement.expiry,
                 },
+                peer,
                 Category::Wallet,
                 Some(session_settlement),
             )
@@ -91,21 +125,32 @@ impl Wallet {
     }
 }
 
-async fn send_settlement(wallet: Wallet, request: SessionProposeRequest, public_key: String) {
-    if let Err(e) = wallet.send_settlement(request, public_key).await {
+async fn send_settlement(
+    wallet: Wallet,
+    pairing_topic: Topic,
+    request: SessionProposeRequest,
+    public_key: String,
+) {
+    if let Err(e) = wallet
+        .send_settlement(pairing_topic, request, public_key)
+        .await
+    {
         warn!("failed to create ClientSession: '{e}'");
     }
 }
 
-impl Handler<SessionProposeRequest> for Wallet {
+/// A proposal and the pairing topic it arrived on
+pub struct PairingProposal(pub Topic, pub SessionProposeRequest);
+
+impl Handler<PairingProposal> for Wallet {
     type Return = RpcResponsePayload;
 
     async fn handle(
         &mut self,
-        message: SessionProposeRequest,
+        PairingProposal(pairing_topic, message): PairingProposal,
         _ctx: &mut Context<Self>,
     ) -> Self::Return {
-        let pk = self.manager.pair_key();
+        let pk = self.manager.pair_key_for(&pairing_topic);
         if pk.is_none() {
             error!("no pairing key!");
             return RpcResponsePayload::Error(ResponseParamsError::SessionPropose(
@@ -113,6 +158,17 @@ impl Handler<SessionProposeRequest> for Wallet {
             ));
         }
         let pk = pk.unwrap();
+        if let Some(allowlist) = &self.allowlist {
+            if !allowlist(&message) {
+                warn!(
+                    "rejecting proposal from {} ({})",
+                    message.proposer.metadata.name, message.proposer.metadata.url
+                );
+                return RpcResponsePayload::Error(ResponseParamsError::SessionPropose(
+                    SdkErrors::UserRejected.into(),
+                ));
+            }
+        }
         if let Ok((accepted, response)) = self
             .settlement_handler
             .send(SessionProposePublicKey(String::from(&pk), message.clone()))
@@ -120,7 +176,9 @@ impl Handler<SessionProposeRequest> for Wallet {
         {
             if accepted {
                 let wallet = self.clone();
-                spawn_task(async move { send_settlement(wallet, message, pk).await });
+                spawn_task(
+                    async move { send_settlement(wallet, pairing_topic, message, pk).await },
+                );
             }
             return response;
         }
@@ -137,6 +195,25 @@ impl Wallet {
     pub async fn new<T: WalletSettlementHandler>(
         manager: PairingManager,
         handler: T,
+    ) -> Result<Self> {
+        Self::init(manager, handler, None).await
+    }
+
+    /// Like [`Self::new`], rejecting proposals `allow` returns false for with
+    /// [`SdkErrors::UserRejected`] before `handler` sees them, e.g. to only
+    /// connect dapps with an allowlisted metadata url
+    pub async fn with_allowlist<T, F>(manager: PairingManager, handler: T, allow: F) -> Result<Self>
+    where
+        T: WalletSettlementHandler,
+        F: Fn(&SessionProposeRequest) -> bool + Send + Sync + 'static,
+    {
+        Self::init(manager, handler, Some(Arc::new(allow))).await
+    }
+
+    async fn init<T: WalletSettlementHandler>(
+        manager: PairingManager,
+        handler: T,
+        allowlist: Option<ProposalAllowlist>,
     ) -> Result<Self> {
         let metadata = Metadata {
             name: "mock wallet".to_string(),
@@ -153,6 +230,8 @@ impl Wallet {
             pending: Arc::new(PendingSession::new()),
             metadata,
             settlement_handler,
+            allowlist,
+            required: Arc::new(DashMap::new()),
         };
         me.manager.actors().proposal().send(me.clone()).await?;
         Ok(me)
@@ -169,4 +248,44 @@ impl Wallet {
         self.manager.set_pairing(pairing.clone()).await?;
         Ok((pairing, ProposeFuture::new(rx)))
     }
+
+    /// Push new `namespaces` for the session on `topic` to the dapp with
+    /// `wc_sessionUpdate`, storing them once the dapp accepts.
+    ///
+    /// `namespaces` must still satisfy what the dapp required, else this
+    /// fails with [`SessionError::UpdateBelowRequired`]. For sessions
+    /// restored from storage the proposal is gone, so the settled namespaces
+    /// are the floor instead.
+    pub async fn update_session(&self, topic: &Topic, namespaces: Namespaces) -> Result<()> {
+        let ciphers = self.manager.ciphers();
+        let settled = ciphers
+            .settlement(topic)?
+            .ok_or_else(|| SessionError::NoClientSession(topic.clone()))?;
+        let satisfied = self.required.get(topic).map_or_else(
+            || namespaces.satisfies(&settled.namespaces),
+            |required| namespaces.satisfies(&required),
+        );
+        if !satisfied {
+            return Err(SessionError::UpdateBelowRequired(topic.clone()).into());
+        }
+        namespaces.validate_accounts()?;
+        let accepted: bool = self
+            .manager
+            .topic_transport()
+            .publish_request(
+                topic.clone(),
+                RequestParams::SessionUpdate(SessionUpdateRequest {
+                    namespaces: namespaces.clone(),
+                }),
+            )
+            .await?;
+        if !accepted {
+            return Err(SessionError::UpdateRejected(topic.clone()).into());
+        }
+        ciphers.set_settlement(topic, SessionSettled {
+            namespaces,
+            ..settled
+        })?;
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@ use {
     crate::{
         rpc::{RpcResponsePayload, SessionProposeRequest},
         wallet::SessionProposePublicKey,
+        ProposalContext,
         Result,
         WalletSettlementHandler,
     },
@@ -37,15 +38,18 @@ impl WalletSettlementActor {
     }
 }
 
-impl Handler<SessionProposeRequest> for WalletSettlementActor {
+/// Ask the handler for the namespaces to settle a proposal with
+pub struct SettlementRequest(pub SessionProposeRequest, pub ProposalContext);
+
+impl Handler<SettlementRequest> for WalletSettlementActor {
     type Return = Result<Namespaces>;
 
     async fn handle(
         &mut self,
-        message: SessionProposeRequest,
+        message: SettlementRequest,
         _ctx: &mut Context<Self>,
     ) -> Self::Return {
         let l = self.handler.lock().await;
-        l.settlement(message).await
+        l.settlement(message.0, message.1).await
     }
 }
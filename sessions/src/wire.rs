@@ -0,0 +1,59 @@
+//! Wire level tracing of JSON-RPC payloads.
+//!
+//! Enable the `wire-trace` feature to log every decoded inbound
+//! request/response and every outbound request as pretty JSON at trace level.
+//! Without the feature these functions compile to nothing.
+
+use {
+    crate::rpc::{Request, Response},
+    monedero_domain::Topic,
+};
+
+#[cfg(feature = "wire-trace")]
+pub const WIRE_TARGET: &str = "monedero_mesh::wire";
+
+#[cfg(feature = "wire-trace")]
+fn trace_json<T: serde::Serialize>(
+    direction: &str,
+    topic: &Topic,
+    id: monedero_domain::MessageId,
+    payload: &T,
+) {
+    match serde_json::to_string_pretty(payload) {
+        Ok(json) => tracing::trace!(
+            target: WIRE_TARGET,
+            direction,
+            topic = %topic,
+            id = %id,
+            "\n{json}"
+        ),
+        Err(e) => tracing::warn!(target: WIRE_TARGET, "failed to serialize {direction} {id}: {e}"),
+    }
+}
+
+#[cfg(feature = "wire-trace")]
+pub fn inbound_request(topic: &Topic, request: &Request) {
+    trace_json("inbound-request", topic, request.id, request);
+}
+
+#[cfg(feature = "wire-trace")]
+pub fn inbound_response(topic: &Topic, response: &Response) {
+    trace_json("inbound-response", topic, response.id, response);
+}
+
+#[cfg(feature = "wire-trace")]
+pub fn outbound_request(topic: &Topic, request: &Request) {
+    trace_json("outbound-request", topic, request.id, request);
+}
+
+#[cfg(not(feature = "wire-trace"))]
+#[inline]
+pub const fn inbound_request(_topic: &Topic, _request: &Request) {}
+
+#[cfg(not(feature = "wire-trace"))]
+#[inline]
+pub const fn inbound_response(_topic: &Topic, _response: &Response) {}
+
+#[cfg(not(feature = "wire-trace"))]
+#[inline]
+pub const fn outbound_request(_topic: &Topic, _request: &Request) {}
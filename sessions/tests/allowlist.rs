@@ -0,0 +1,55 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, SessionProposeRequest},
+        Dapp,
+        Error,
+        NoopSessionHandler,
+        Wallet,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+const KNOWN_DAPP: &str = "https://known.example.com";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_wallet_allowlist() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let wallet = Wallet::with_allowlist(
+        builder(&p).build().await?,
+        WalletProposal {},
+        |proposal: &SessionProposeRequest| proposal.proposer.metadata.url == KNOWN_DAPP,
+    )
+    .await?;
+    let dapp = |url: &str| {
+        let md = Metadata {
+            url: url.to_string(),
+            ..Default::default()
+        };
+        async { Dapp::new(builder(&p).build().await?, md).await }
+    };
+
+    let unknown = dapp("https://unknown.example.com").await?;
+    let (pairing, rx, _) = unknown
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let result = timeout(Duration::from_secs(5), rx).await?;
+    assert_matches!(result, Err(Error::RpcError(v)) if v["code"] == 5000);
+
+    let known = dapp(KNOWN_DAPP).await?;
+    let (pairing, rx, _) = known
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let (_, wallet_rx) = wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    timeout(Duration::from_secs(5), rx).await??;
+    timeout(Duration::from_secs(5), wallet_rx).await??;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use {
+    assert_matches::assert_matches,
+    async_trait::async_trait,
+    monedero_domain::namespaces::{ChainId, ChainType, Method, SolanaMethod},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, RequestParams, SessionRequestBuilder, SessionRequestRequest},
+        Dapp,
+        Error,
+        NoopSessionHandler,
+        RegisteredComponents,
+        SessionEventHandler,
+        SessionHandler,
+        TransportError,
+        Wallet,
+        WalletRequestResponse,
+    },
+    serde_json::json,
+    std::time::Duration,
+    tokio::{sync::oneshot, time::timeout},
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Keeps the user "prompting" past the cancel
+struct SlowWallet;
+
+#[async_trait]
+impl SessionEventHandler for SlowWallet {}
+
+#[async_trait]
+impl SessionHandler for SlowWallet {
+    async fn request(&self, _request: SessionRequestRequest) -> WalletRequestResponse {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        WalletRequestResponse::Success(json!({}))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_cancel_request() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p).build().await?;
+    let responses = dapp_manager.actors().response();
+    let dapp = Dapp::new(dapp_manager, Metadata::default()).await?;
+    let wallet = Wallet::new(builder(&p).build().await?, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), SlowWallet).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let request = SessionRequestBuilder::new(
+        ChainId::Solana(ChainType::Dev),
+        Method::Solana(SolanaMethod::SignMessage),
+    )
+    .params(json!({"message": "hi"}))
+    .build()?;
+    let (id_tx, id_rx) = oneshot::channel();
+    let requester = session.clone();
+    let pending = tokio::spawn(async move {
+        requester
+            .publish_request_with_id::<serde_json::Value>(
+                RequestParams::SessionRequest(request),
+                move |id| {
+                    let _ = id_tx.send(id);
+                },
+            )
+            .await
+    });
+    let id = timeout(Duration::from_secs(5), id_rx).await??;
+    assert_eq!(1, responses.send(RegisteredComponents).await?);
+
+    session.cancel_request(id).await?;
+    let result = timeout(Duration::from_secs(1), pending).await??;
+    assert_matches!(result, Err(Error::Transport(TransportError::Cancelled(i))) if i == id);
+    // the cancel event awaits its ack until the wallet is done prompting
+    for _ in 0..50 {
+        if responses.send(RegisteredComponents).await? == 0 {
+            return Ok(());
+        }
+        yield_ms(100).await;
+    }
+    anyhow::bail!("cancelled request is still pending")
+}
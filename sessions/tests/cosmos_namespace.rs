@@ -0,0 +1,143 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::namespaces::{
+        Account,
+        Accounts,
+        ChainId,
+        Event,
+        Events,
+        Method,
+        Methods,
+        Namespace,
+        NamespaceDefaults,
+        NamespaceName,
+        Namespaces,
+    },
+    monedero_mesh::{
+        init_tracing,
+        rpc::{
+            Metadata,
+            RequestParams,
+            SessionProposeRequest,
+            SessionRequestBuilder,
+            SessionRequestRequest,
+        },
+        Dapp,
+        NoopSessionHandler,
+        ProposalContext,
+        Result,
+        SessionEventHandler,
+        SessionHandler,
+        Wallet,
+        WalletRequestResponse,
+        WalletSettlementHandler,
+    },
+    serde_json::{json, Value},
+    std::{collections::BTreeSet, time::Duration},
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+const COSMOS_ACCOUNT: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+
+/// Wallet granting every namespace exactly as proposed
+struct CosmosWallet;
+
+#[async_trait]
+impl WalletSettlementHandler for CosmosWallet {
+    async fn settlement(
+        &self,
+        proposal: SessionProposeRequest,
+        _context: ProposalContext,
+    ) -> Result<Namespaces> {
+        let mut settled = Namespaces::default();
+        for (name, ns) in proposal.required_namespaces.iter() {
+            let accounts = ns
+                .chains
+                .iter()
+                .map(|chain| Account {
+                    address: String::from(COSMOS_ACCOUNT),
+                    chain: chain.clone(),
+                })
+                .collect();
+            settled.insert(name.clone(), Namespace {
+                accounts: Accounts(accounts),
+                ..ns.clone()
+            });
+        }
+        Ok(settled)
+    }
+}
+
+/// Answers with the method and chain it received
+struct EchoHandler;
+
+#[async_trait]
+impl SessionEventHandler for EchoHandler {}
+
+#[async_trait]
+impl SessionHandler for EchoHandler {
+    async fn request(&self, request: SessionRequestRequest) -> WalletRequestResponse {
+        WalletRequestResponse::Success(json!({
+            "method": request.request.method.to_string(),
+            "chainId": request.chain_id.to_string(),
+        }))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_cosmos_namespace() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let cosmos = NamespaceName::Other(String::from("cosmos"));
+    let sign = Method::Other(String::from("cosmos_signDirect"));
+    let defaults = NamespaceDefaults::default().with(
+        cosmos.clone(),
+        Methods(BTreeSet::from([sign.clone()])),
+        Events(BTreeSet::from([Event::Other(String::from(
+            "cosmos_accountsChanged",
+        ))])),
+    );
+    let dapp =
+        Dapp::with_namespace_defaults(builder(&p).build().await?, Metadata::default(), defaults)
+            .await?;
+    let wallet = Wallet::new(builder(&p).build().await?, CosmosWallet).await?;
+
+    let chain = ChainId::Other(String::from("cosmos:cosmoshub-4"));
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, std::slice::from_ref(&chain))
+        .await?;
+    let (_, wallet_rx) = wallet.pair(pairing.to_string(), EchoHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+
+    for namespaces in [session.namespaces(), wallet_session.namespaces()] {
+        let ns = namespaces
+            .get(&cosmos)
+            .expect("cosmos namespace was settled");
+        assert!(ns.chains.contains(&chain));
+        assert!(ns.methods.contains(&sign));
+        assert!(ns
+            .events
+            .contains(&Event::Other(String::from("cosmos_accountsChanged"))));
+        let account = ns.accounts.iter().next().expect("cosmos account");
+        assert_eq!(chain, account.chain);
+        assert_eq!(COSMOS_ACCOUNT, account.address);
+    }
+
+    let request = SessionRequestBuilder::new(chain.clone(), sign.clone())
+        .params(json!({"signDoc": "doc"}))
+        .build()?;
+    let result: Value = timeout(
+        Duration::from_secs(5),
+        session.publish_request(RequestParams::SessionRequest(request)),
+    )
+    .await??;
+    assert_eq!(
+        json!({"method": "cosmos_signDirect", "chainId": "cosmos:cosmoshub-4"}),
+        result
+    );
+    Ok(())
+}
@@ -58,7 +58,9 @@ async fn test_dapp_settlement() -> anyhow::Result<()> {
     assert_eq!(0, components);
     assert_matches!(
         session.ping().await,
-        Err(monedero_mesh::Error::NoClientSession(_))
+        Err(monedero_mesh::Error::Session(
+            monedero_mesh::SessionError::NotSubscribed(_)
+        ))
     );
     yield_ms(500).await;
     // propose again should repair
@@ -0,0 +1,44 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{NoopSessionHandler, SessionEvent},
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_disconnect_on_drop() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let mut wallet_events = test.wallet_actors.session_events();
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx)
+        .await??
+        .disconnect_on_drop(true);
+    let topic = session.topic();
+    assert_matches!(
+        timeout(Duration::from_secs(5), wallet_events.recv()).await??,
+        SessionEvent::Settled(_)
+    );
+
+    let clone = session.clone();
+    drop(session);
+    yield_ms(500).await;
+    assert!(clone.ping().await?);
+
+    drop(clone);
+    assert_matches!(
+        timeout(Duration::from_secs(5), wallet_events.recv()).await??,
+        SessionEvent::Deleted(deleted) if deleted == topic
+    );
+    yield_ms(500).await;
+    Ok(())
+}
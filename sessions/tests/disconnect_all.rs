@@ -0,0 +1,72 @@
+use {
+    assert_matches::assert_matches,
+    monedero_cipher::SessionKey,
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        init_tracing,
+        rpc::Metadata,
+        Dapp,
+        Error,
+        NoopSessionHandler,
+        SessionEvent,
+        TransportError,
+        Wallet,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_disconnect_all() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p).build().await?;
+    let dapp = Dapp::new(dapp_manager.clone(), Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let mut wallet_events = wallet_manager.session_events();
+    let wallet = Wallet::new(wallet_manager, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    assert_matches!(
+        timeout(Duration::from_secs(5), wallet_events.recv()).await??,
+        SessionEvent::Settled(_)
+    );
+
+    // two more sessions whose peers never answer
+    let ciphers = dapp_manager.ciphers();
+    let public_key = ciphers.public_key().unwrap();
+    let mut orphans = Vec::new();
+    for _ in 0..2 {
+        let peer = SessionKey::from_osrng(public_key.as_bytes())?;
+        let (topic, _) =
+            ciphers.create_common_topic(&ciphers.pairing().unwrap().topic, peer.public_key())?;
+        dapp_manager.subscribe(topic.clone()).await?;
+        orphans.push(topic);
+    }
+    assert_eq!(3, ciphers.session_count());
+
+    let results = dapp_manager.disconnect_all().await?;
+    assert_eq!(3, results.len());
+    for (topic, result) in &results {
+        if *topic == session.topic() {
+            assert!(result.is_ok());
+        } else {
+            assert!(orphans.contains(topic));
+            assert_matches!(result, Err(Error::Transport(TransportError::WaitError(_))));
+        }
+    }
+    assert_eq!(0, ciphers.session_count());
+    assert!(ciphers.pairing().is_some());
+    assert_matches!(
+        timeout(Duration::from_secs(5), wallet_events.recv()).await??,
+        SessionEvent::Deleted(deleted) if deleted == session.topic()
+    );
+    Ok(())
+}
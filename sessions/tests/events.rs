@@ -0,0 +1,33 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{NoopSessionHandler, SessionEvent},
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_session_events() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let mut events = test.dapp_actors.session_events();
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let event = timeout(Duration::from_secs(5), events.recv()).await??;
+    assert_matches!(event, SessionEvent::Settled(ref settled) if settled.topic == session.topic());
+
+    assert!(session.delete().await);
+    let event = timeout(Duration::from_secs(5), events.recv()).await??;
+    assert_matches!(event, SessionEvent::Deleted(ref topic) if *topic == session.topic());
+    yield_ms(500).await;
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        init_tracing,
+        rpc::Metadata,
+        Dapp,
+        NoopSessionHandler,
+        PairingManager,
+        Wallet,
+    },
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+fn count(manager: &PairingManager, method: &str) -> Arc<AtomicUsize> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    manager.register_interceptor(method, move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        None
+    });
+    calls
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_fetch_on_reconnect() -> anyhow::Result<()> {
+    init_tracing();
+    let (relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p).fetch_on_reconnect(true).build().await?;
+    let settles = count(&dapp_manager, "wc_sessionSettle");
+    let deletes = count(&dapp_manager, "wc_sessionDelete");
+    let dapp = Dapp::new(dapp_manager.clone(), Metadata::default()).await?;
+    let wallet = Wallet::new(builder(&p).build().await?, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let (_, wallet_rx) = wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    timeout(Duration::from_secs(5), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+    assert_eq!(1, settles.load(Ordering::SeqCst));
+
+    // only a fetch can recover what is published while offline
+    relay.redeliver_on_subscribe(false);
+    dapp_manager.disconnect_socket().await?;
+    let delete = tokio::spawn(async move { wallet_session.delete().await });
+    yield_ms(500).await;
+    assert_eq!(0, deletes.load(Ordering::SeqCst));
+
+    dapp_manager.reconnect().await?;
+    assert!(timeout(Duration::from_secs(5), delete).await??);
+    yield_ms(500).await;
+    assert_eq!(1, deletes.load(Ordering::SeqCst));
+    // messages handled before the blip are not replayed
+    assert_eq!(1, settles.load(Ordering::SeqCst));
+    Ok(())
+}
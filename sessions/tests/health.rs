@@ -0,0 +1,44 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, Health, NoopSessionHandler, Wallet},
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_health() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p).build().await?;
+    let dapp = Dapp::new(dapp_manager.clone(), Metadata::default()).await?;
+    let wallet = Wallet::new(builder(&p).build().await?, WalletProposal {}).await?;
+    assert_eq!(dapp_manager.health(), Health {
+        relay_connected: true,
+        active_sessions: 0,
+        pairing_present: false,
+    });
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let _session = timeout(Duration::from_secs(5), rx).await??;
+    let health = dapp_manager.health();
+    assert_eq!(health, Health {
+        relay_connected: true,
+        active_sessions: 1,
+        pairing_present: true,
+    });
+    assert_eq!(
+        serde_json::json!({"relayConnected": true, "activeSessions": 1, "pairingPresent": true}),
+        serde_json::to_value(health)?
+    );
+
+    dapp_manager.disconnect_socket().await?;
+    yield_ms(300).await;
+    assert!(!dapp_manager.health().relay_connected);
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, ResponseParamsSuccess, RpcResponse, RpcResponsePayload},
+        Dapp,
+        NoopSessionHandler,
+        Wallet,
+    },
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_ping_interceptor() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp = Dapp::new(builder(&p).build().await?, Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let pings = Arc::new(AtomicUsize::new(0));
+    let counter = pings.clone();
+    wallet_manager.register_interceptor("wc_sessionPing", move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        None
+    });
+    let wallet = Wallet::new(wallet_manager.clone(), WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    // pings still reach the default handler
+    assert!(session.ping().await?);
+    assert!(session.ping().await?);
+    assert_eq!(2, pings.load(Ordering::SeqCst));
+
+    // a response short-circuits the default handler
+    wallet_manager.register_interceptor("wc_sessionPing", |req| {
+        Some(RpcResponse {
+            id: req.payload.id,
+            topic: req.topic,
+            payload: RpcResponsePayload::Success(ResponseParamsSuccess::SessionPing(false)),
+        })
+    });
+    assert!(!session.ping().await?);
+    assert_eq!(2, pings.load(Ordering::SeqCst));
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler, Wallet},
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_keepalive() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p)
+        .keepalive_interval(Some(Duration::from_millis(200)))
+        .build()
+        .await?;
+    let dapp = Dapp::new(dapp_manager.clone(), Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let pings = Arc::new(AtomicUsize::new(0));
+    let counter = pings.clone();
+    wallet_manager.register_interceptor("wc_pairingPing", move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        None
+    });
+    let wallet = Wallet::new(wallet_manager, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let _session = timeout(Duration::from_secs(5), rx).await??;
+
+    let before = pings.load(Ordering::SeqCst);
+    yield_ms(1100).await;
+    let during = pings.load(Ordering::SeqCst) - before;
+    assert!((3..=7).contains(&during), "{during} pings in 1.1s");
+
+    dapp_manager.shutdown().await?;
+    yield_ms(300).await;
+    let stopped = pings.load(Ordering::SeqCst);
+    yield_ms(1000).await;
+    assert_eq!(stopped, pings.load(Ordering::SeqCst));
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler, Wallet},
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_peer_last_seen() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp = Dapp::new(builder(&p).build().await?, Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let wallet = Wallet::new(wallet_manager.clone(), WalletProposal {}).await?;
+    assert!(wallet_manager.peer_last_seen().is_none());
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let _session = timeout(Duration::from_secs(5), rx).await??;
+    let proposed = wallet_manager
+        .peer_last_seen()
+        .expect("proposal was received on the pairing topic");
+
+    yield_ms(50).await;
+    assert!(dapp.pair_ping().await?);
+    let pinged = wallet_manager.peer_last_seen().expect("ping was received");
+    assert!(pinged > proposed);
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use monedero_mesh::{init_tracing, MeshMetrics, RegisteredComponents};
+
+mod test_utils;
+use test_utils::*;
+
+// single threaded, so the session actor can't drain its mailbox until the test
+// yields
+#[tokio::test]
+async fn test_mailbox_metrics() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let manager = builder(&p).build().await?;
+    let before = manager.metrics();
+    assert_eq!(None, before.mailbox_capacity);
+
+    let session = manager.actors().session();
+    let mut pending = Vec::new();
+    for _ in 0..100 {
+        pending.push(session.send(RegisteredComponents).detach().await?);
+    }
+    // the actor may already have taken the first message
+    let flooded = manager.metrics();
+    assert!(flooded.session_mailbox >= before.session_mailbox + 99);
+
+    for handled in pending {
+        handled.await?;
+    }
+    assert_eq!(MeshMetrics::default(), MeshMetrics {
+        mailbox_capacity: None,
+        ..manager.metrics()
+    });
+    Ok(())
+}
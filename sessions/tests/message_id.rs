@@ -0,0 +1,49 @@
+use {
+    monedero_domain::{
+        namespaces::{ChainId, ChainType},
+        MessageId,
+    },
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler, Wallet},
+    std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+const FIXED_ID: u64 = 1_234_567;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_custom_message_id_generator() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p)
+        .message_id_generator(|| MessageId::new(FIXED_ID))
+        .build()
+        .await?;
+    let dapp = Dapp::new(dapp_manager, Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    for method in ["wc_sessionPropose", "wc_sessionPing"] {
+        let seen = seen.clone();
+        wallet_manager.register_interceptor(method, move |req| {
+            seen.lock().unwrap().push(req.payload.id);
+            None
+        });
+    }
+    let wallet = Wallet::new(wallet_manager, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    assert!(session.ping().await?);
+
+    let seen = seen.lock().unwrap().clone();
+    assert_eq!(vec![MessageId::new(FIXED_ID); 2], seen);
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType, Namespaces},
+    monedero_mesh::{
+        rpc::{Metadata, RequestParams, SessionProposeRequest},
+        Error,
+        NoopSessionHandler,
+        SessionEvent,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_propose_on_session_topic_rejected() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let mut wallet_events = test.wallet_actors.session_events();
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    assert_matches!(
+        timeout(Duration::from_secs(5), wallet_events.recv()).await??,
+        SessionEvent::Settled(_)
+    );
+
+    let proposal = SessionProposeRequest::new(
+        Metadata::default(),
+        String::from("00"),
+        Namespaces::default(),
+        None,
+    );
+    let result: monedero_mesh::Result<serde_json::Value> = session
+        .publish_request(RequestParams::SessionPropose(proposal))
+        .await;
+    assert_matches!(result, Err(Error::RpcError(v)) if v["code"] == 1001);
+
+    // the wallet must not have started a second settlement
+    yield_ms(500).await;
+    assert!(wallet_events.try_recv().is_err());
+    Ok(())
+}
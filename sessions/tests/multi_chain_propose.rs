@@ -0,0 +1,60 @@
+use {
+    monedero_domain::namespaces::{
+        ChainId,
+        ChainType,
+        Chains,
+        EipMethod,
+        Event,
+        Events,
+        Methods,
+        NamespaceName,
+        SolanaMethod,
+    },
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler, Wallet},
+    std::{collections::BTreeSet, time::Duration},
+    tokio::{sync::mpsc, time::timeout},
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_propose_multi_chain() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp = Dapp::new(builder(&p).build().await?, Metadata::default()).await?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let wallet = Wallet::new(builder(&p).build().await?, CaptureProposal(tx)).await?;
+
+    let sepolia = ChainId::EIP155(alloy_chains::Chain::sepolia());
+    let solana = ChainId::Solana(ChainType::Dev);
+    let chains = Chains::from([sepolia.clone(), solana.clone()]);
+    let (pairing, _, _) = dapp.propose(NoopSessionHandler, &chains).await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let proposal = timeout(Duration::from_secs(5), rx.recv())
+        .await?
+        .ok_or_else(|| anyhow::format_err!("no proposal"))?;
+    let required = proposal.required_namespaces;
+    assert_eq!(2, required.len());
+
+    let eip = required
+        .get(&NamespaceName::EIP155)
+        .ok_or_else(|| anyhow::format_err!("no eip155 namespace"))?;
+    assert_eq!(Chains::from([sepolia]), eip.chains);
+    assert_eq!(Methods(EipMethod::defaults()), eip.methods);
+    assert_eq!(
+        Events(BTreeSet::from([
+            Event::AccountsChanged,
+            Event::ChainChanged
+        ])),
+        eip.events
+    );
+
+    let sol = required
+        .get(&NamespaceName::Solana)
+        .ok_or_else(|| anyhow::format_err!("no solana namespace"))?;
+    assert_eq!(Chains::from([solana]), sol.chains);
+    assert_eq!(Methods(SolanaMethod::defaults()), sol.methods);
+    assert!(sol.events.is_empty());
+    Ok(())
+}
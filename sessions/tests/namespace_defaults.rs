@@ -0,0 +1,49 @@
+use {
+    monedero_domain::namespaces::{
+        ChainId,
+        ChainType,
+        Events,
+        Method,
+        Methods,
+        NamespaceDefaults,
+        NamespaceName,
+        SolanaMethod,
+    },
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler, Wallet},
+    std::{collections::BTreeSet, time::Duration},
+    tokio::{sync::mpsc, time::timeout},
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_propose_namespace_defaults() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let sign_only = Methods(BTreeSet::from([Method::Solana(SolanaMethod::SignMessage)]));
+    let defaults = NamespaceDefaults::default().with(
+        NamespaceName::Solana,
+        sign_only.clone(),
+        Events(BTreeSet::new()),
+    );
+    let dapp =
+        Dapp::with_namespace_defaults(builder(&p).build().await?, Metadata::default(), defaults)
+            .await?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let wallet = Wallet::new(builder(&p).build().await?, CaptureProposal(tx)).await?;
+
+    let (pairing, _, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let proposal = timeout(Duration::from_secs(5), rx.recv())
+        .await?
+        .ok_or_else(|| anyhow::format_err!("no proposal"))?;
+    let solana = proposal
+        .required_namespaces
+        .get(&NamespaceName::Solana)
+        .ok_or_else(|| anyhow::format_err!("no solana namespace"))?;
+    assert_eq!(sign_only, solana.methods);
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use {
+    monedero_domain::Pairing,
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler},
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_dapp_pair_existing_failed() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let manager = builder(&p).build().await?;
+    let dapp = Dapp::new(manager.clone(), Metadata::default()).await?;
+
+    // subscribing to the pairing fails while offline
+    manager.disconnect_socket().await?;
+    let uri = Pairing::default().to_string();
+    assert!(dapp.pair_existing(&uri, NoopSessionHandler).await.is_err());
+    assert_eq!(0, dapp.pending_proposals());
+    Ok(())
+}
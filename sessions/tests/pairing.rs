@@ -0,0 +1,29 @@
+use {
+    monedero_domain::Pairing,
+    monedero_mesh::NoopSessionHandler,
+    std::str::FromStr,
+    tracing::info,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_dapp_pair_existing() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let uri = Pairing::default().to_string();
+    let expected = Pairing::from_str(&uri)?;
+    let _rx = test.dapp.pair_existing(&uri, NoopSessionHandler).await?;
+    let pairing = test
+        .dapp
+        .pairing()
+        .ok_or_else(|| anyhow::format_err!("no pairing!"))?;
+    assert_eq!(expected.topic, pairing.topic);
+    info!("dapp restored pairing {pairing}");
+
+    let (wallet_pairing, _) = test.wallet.pair(uri, NoopSessionHandler).await?;
+    assert_eq!(wallet_pairing.topic, pairing.topic);
+    yield_ms(500).await;
+    assert!(test.dapp.pair_ping().await?);
+    Ok(())
+}
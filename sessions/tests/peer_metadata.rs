@@ -0,0 +1,27 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::NoopSessionHandler,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_peer_metadata() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let (_, wallet_rx) = test
+        .wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let dapp_session = timeout(Duration::from_secs(5), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+    assert_eq!("mock wallet", dapp_session.peer_metadata().name);
+    assert_eq!("mock-dapp", wallet_session.peer_metadata().name);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use {
+    monedero_domain::{
+        namespaces::{ChainId, ChainType},
+        Pairing,
+    },
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, RequestParams, SessionProposeRequest, SessionProposeResponse},
+        Dapp,
+        NoopSessionHandler,
+        Wallet,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_preload_pairings() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let pairings = vec![Pairing::default(), Pairing::default()];
+    let service = builder(&p)
+        .preload_pairings(pairings.clone())
+        .build()
+        .await?;
+    assert_eq!(
+        Some(pairings[0].topic.clone()),
+        service.pairing().map(|p| p.topic)
+    );
+    let subscriptions = service.ciphers().subscriptions();
+    for pairing in &pairings {
+        assert!(subscriptions.contains(&pairing.topic));
+    }
+
+    // the service answers on every preloaded topic without pairing again
+    let mut peers = Vec::new();
+    for pairing in &pairings {
+        let peer = builder(&p).build().await?;
+        let dapp = Dapp::new(peer.clone(), Metadata::default()).await?;
+        let rx = dapp
+            .pair_existing(&pairing.to_string(), NoopSessionHandler)
+            .await?;
+        assert!(timeout(Duration::from_secs(5), peer.ping()).await??);
+        peers.push((peer, dapp, rx));
+    }
+
+    // and settles sessions proposed over one that isn't the current pairing,
+    // pairing with it again keeps the others
+    let wallet = Wallet::new(service, WalletProposal {}).await?;
+    let second = pairings[1].clone();
+    let (_, wallet_rx) = wallet.pair(second.to_string(), NoopSessionHandler).await?;
+    let (dapp_manager, _dapp, dapp_rx) = peers.pop().expect("peer of the second pairing");
+    let proposal = SessionProposeRequest::new(
+        Metadata::default(),
+        dapp_manager.pair_key().unwrap(),
+        (&[ChainId::Solana(ChainType::Dev)]).into(),
+        None,
+    );
+    let response: SessionProposeResponse = timeout(
+        Duration::from_secs(5),
+        dapp_manager.publish_request(RequestParams::SessionPropose(proposal)),
+    )
+    .await??;
+    let (session_topic, _) = dapp_manager
+        .ciphers()
+        .create_common_topic(&second.topic, response.responder_public_key)?;
+    dapp_manager.subscribe(session_topic.clone()).await?;
+
+    let dapp_session = timeout(Duration::from_secs(5), dapp_rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+    assert_eq!(session_topic, dapp_session.topic());
+    assert_eq!(session_topic, wallet_session.topic());
+    assert!(wallet_session.ping().await?);
+    Ok(())
+}
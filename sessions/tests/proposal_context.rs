@@ -0,0 +1,72 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::namespaces::{ChainId, ChainType, Namespaces},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, SessionProposeRequest},
+        Dapp,
+        NoopSessionHandler,
+        ProposalContext,
+        Result,
+        Wallet,
+        WalletSettlementHandler,
+    },
+    std::time::Duration,
+    tokio::{sync::mpsc, time::timeout},
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Settles like [`WalletProposal`] after handing what it was given to the test
+struct CaptureContext(mpsc::UnboundedSender<(Metadata, ProposalContext)>);
+
+#[async_trait]
+impl WalletSettlementHandler for CaptureContext {
+    async fn settlement(
+        &self,
+        proposal: SessionProposeRequest,
+        context: ProposalContext,
+    ) -> Result<Namespaces> {
+        let _ = self
+            .0
+            .send((proposal.proposer.metadata.clone(), context.clone()));
+        WalletProposal {}.settlement(proposal, context).await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_proposal_context() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let md = Metadata {
+        name: String::from("context-dapp"),
+        url: String::from("https://dapp.example.com"),
+        ..Default::default()
+    };
+    let dapp = Dapp::new(builder(&p).build().await?, md).await?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let wallet = Wallet::new(builder(&p).build().await?, CaptureContext(tx)).await?;
+
+    let (pairing, settled, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let mut uri = pairing.uri_parts();
+    uri.methods = Some(String::from("[wc_sessionPropose],[solana_signTransaction]"));
+    wallet.pair(uri.to_string(), NoopSessionHandler).await?;
+    let (metadata, context) = timeout(Duration::from_secs(5), rx.recv())
+        .await?
+        .ok_or_else(|| anyhow::format_err!("handler was not called"))?;
+    assert_eq!("context-dapp", metadata.name);
+    assert_eq!("https://dapp.example.com", metadata.url);
+    assert_eq!(pairing.topic, context.pairing_topic);
+    assert_eq!(
+        Some(vec![
+            String::from("wc_sessionPropose"),
+            String::from("solana_signTransaction"),
+        ]),
+        context.pairing_methods
+    );
+    let _session = timeout(Duration::from_secs(5), settled).await??;
+    Ok(())
+}
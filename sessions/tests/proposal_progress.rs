@@ -0,0 +1,33 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{NoopSessionHandler, ProposalStage},
+    std::time::Duration,
+    tokio::{sync::mpsc, time::timeout},
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_proposal_progress() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (tx, mut progress) = mpsc::unbounded_channel();
+    let (pairing, rx, restored) = test
+        .dapp
+        .propose_with_progress(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)], tx)
+        .await?;
+    assert!(!restored);
+    assert_eq!(
+        Some(ProposalStage::ProposalSent),
+        timeout(Duration::from_secs(5), progress.recv()).await?
+    );
+    assert!(progress.try_recv().is_err());
+
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let _session = timeout(Duration::from_secs(5), rx).await??;
+    assert_eq!(Ok(ProposalStage::WalletResponded), progress.try_recv());
+    assert_eq!(Ok(ProposalStage::Settled), progress.try_recv());
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, Error, NoopSessionHandler, SessionError},
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_proposal_timeout() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let manager = builder(&p)
+        .proposal_timeout(Duration::from_millis(500))
+        .build()
+        .await?;
+    let dapp = Dapp::new(manager, Metadata::default()).await?;
+
+    // no wallet ever pairs
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    assert_eq!(1, dapp.pending_proposals());
+    let result = timeout(Duration::from_secs(5), rx).await?;
+    assert_matches!(result, Err(Error::Session(SessionError::ProposalTimeout(topic))) if topic == pairing.topic);
+    assert_eq!(0, dapp.pending_proposals());
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use {
+    async_trait::async_trait,
+    monedero_cipher::Cipher,
+    monedero_domain::namespaces::{ChainId, Namespaces},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, RelayProtocol, SessionProposeRequest, SessionProposeResponseBuilder},
+        Dapp,
+        NoopSessionHandler,
+        ProposalContext,
+        Result,
+        Wallet,
+        WalletSettlementHandler,
+    },
+    std::time::Duration,
+    x25519_dalek::{PublicKey, StaticSecret},
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Answers proposals with a responder key of its own instead of the pairing
+/// key
+struct CustomKeyWallet(StaticSecret);
+
+impl CustomKeyWallet {
+    fn public_key(&self) -> String {
+        data_encoding::HEXLOWER_PERMISSIVE.encode(PublicKey::from(&self.0).as_bytes())
+    }
+}
+
+#[async_trait]
+impl WalletSettlementHandler for CustomKeyWallet {
+    async fn settlement(
+        &self,
+        proposal: SessionProposeRequest,
+        _context: ProposalContext,
+    ) -> Result<Namespaces> {
+        Ok(proposal.required_namespaces)
+    }
+
+    fn propose_response(
+        &self,
+        _proposal: &SessionProposeRequest,
+        response: SessionProposeResponseBuilder,
+    ) -> SessionProposeResponseBuilder {
+        response
+            .responder_public_key(self.public_key())
+            .relay(RelayProtocol {
+                data: Some(String::from("custom")),
+                ..RelayProtocol::default()
+            })
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_propose_response() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let secret = StaticSecret::from([7u8; 32]);
+    let dapp_manager = builder(&p).build().await?;
+    let dapp = Dapp::new(dapp_manager.clone(), Metadata::default()).await?;
+    let wallet = Wallet::new(builder(&p).build().await?, CustomKeyWallet(secret.clone())).await?;
+
+    let (pairing, _rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::EIP155(
+            alloy_chains::Chain::sepolia(),
+        )])
+        .await?;
+    let dapp_pk = dapp_manager.pair_key().expect("dapp pairing key");
+    let (expected, _) = Cipher::derive_sym_key(&secret, &dapp_pk)?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+
+    // the dapp derives its session topic from the responder key it received
+    let ciphers = dapp_manager.ciphers();
+    for _ in 0..50 {
+        if ciphers.is_subscribed(&expected) {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    anyhow::bail!("dapp never derived the session topic of the custom responder key")
+}
@@ -0,0 +1,104 @@
+use {
+    monedero_domain::{
+        namespaces::{ChainId, ChainType},
+        Pairing,
+        ProjectId,
+    },
+    monedero_mesh::{
+        init_tracing,
+        rpc::RequestParams,
+        Dapp,
+        Metadata,
+        NoopSessionHandler,
+        PairingManager,
+        ProposerKeyPolicy,
+        Wallet,
+    },
+    std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::time::timeout,
+    x25519_dalek::PublicKey,
+};
+
+mod test_utils;
+use test_utils::*;
+
+fn hex(pk: &PublicKey) -> String {
+    data_encoding::HEXLOWER_PERMISSIVE.encode(pk.as_bytes())
+}
+
+/// Public key the pairing URI discloses
+fn pairing_key(pairing: &Pairing) -> String {
+    hex(&PublicKey::from(&pairing.params.sym_key))
+}
+
+/// Proposer public keys of the proposals `manager` receives
+fn offered_keys(manager: &PairingManager) -> Arc<Mutex<Vec<String>>> {
+    let keys = Arc::new(Mutex::new(Vec::new()));
+    let seen = keys.clone();
+    manager.register_interceptor("wc_sessionPropose", move |request| {
+        if let RequestParams::SessionPropose(propose) = request.payload.params {
+            seen.lock().unwrap().push(propose.proposer.public_key);
+        }
+        None
+    });
+    keys
+}
+
+/// Settle two sessions, returning their pairings and the proposer keys the
+/// wallet was offered
+async fn settle_twice(
+    p: &ProjectId,
+    policy: ProposerKeyPolicy,
+) -> anyhow::Result<(Vec<Pairing>, Vec<String>)> {
+    let dapp = Dapp::new(
+        builder(p).proposer_key_policy(policy).build().await?,
+        Metadata::default(),
+    )
+    .await?;
+    let wallet_manager = builder(p).build().await?;
+    let offered = offered_keys(&wallet_manager);
+    let wallet = Wallet::new(wallet_manager, WalletProposal {}).await?;
+
+    let mut pairings = Vec::new();
+    // other chains, so the first session isn't reused
+    for chain in [ChainType::Dev, ChainType::Test] {
+        let (pairing, rx, _) = dapp
+            .propose(NoopSessionHandler, &[ChainId::Solana(chain)])
+            .await?;
+        wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+        let session = timeout(Duration::from_secs(5), rx).await??;
+        assert!(session.ping().await?);
+        pairings.push(pairing);
+    }
+    assert_ne!(pairings[0].topic, pairings[1].topic);
+    let offered = offered.lock().unwrap().clone();
+    Ok((pairings, offered))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_proposer_key_policy() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+
+    let (pairings, offered) = settle_twice(&p, ProposerKeyPolicy::Ephemeral).await?;
+    assert_eq!(
+        vec![pairing_key(&pairings[0]), pairing_key(&pairings[1])],
+        offered
+    );
+
+    // one proposer key, never the symKey the pairing URI discloses
+    let (pairings, offered) = settle_twice(&p, ProposerKeyPolicy::Persistent).await?;
+    assert_eq!(2, offered.len());
+    assert_eq!(offered[0], offered[1]);
+    assert_ne!(
+        pairings[0].params.sym_key.to_bytes(),
+        pairings[1].params.sym_key.to_bytes()
+    );
+    for pairing in &pairings {
+        assert_ne!(pairing_key(pairing), offered[0]);
+    }
+    Ok(())
+}
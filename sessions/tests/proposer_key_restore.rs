@@ -0,0 +1,52 @@
+use {
+    monedero_domain::{
+        namespaces::{ChainId, ChainType},
+        ProjectId,
+    },
+    monedero_mesh::{
+        init_tracing,
+        Dapp,
+        KvStorage,
+        Metadata,
+        NoopSessionHandler,
+        PairingManager,
+        ProposerKeyPolicy,
+    },
+};
+
+mod test_utils;
+use test_utils::*;
+
+async fn persistent_dapp(
+    p: &ProjectId,
+    store: KvStorage,
+) -> anyhow::Result<(PairingManager, Dapp)> {
+    let manager = builder(p)
+        .store(store)
+        .proposer_key_policy(ProposerKeyPolicy::Persistent)
+        .build()
+        .await?;
+    let dapp = Dapp::new(manager.clone(), Metadata::default()).await?;
+    Ok((manager, dapp))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_proposer_key_survives_rebuild() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let store = KvStorage::mem();
+
+    let (manager, dapp) = persistent_dapp(&p, store.clone()).await?;
+    let before = manager.ciphers().proposer_key()?.map(|k| k.to_bytes());
+    assert!(before.is_some());
+    dapp.propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    // wiping the pairing, as an expired session does, keeps the proposer key
+    manager.ciphers().reset();
+    drop((manager, dapp));
+
+    let (manager, _) = persistent_dapp(&p, store).await?;
+    let after = manager.ciphers().proposer_key()?.map(|k| k.to_bytes());
+    assert_eq!(before, after);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        rpc::{RelayProtocolMetadata, RequestParams, IRN_MIN_TTL},
+        NoopSessionHandler,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_publish_request_with_ttl() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let ping = RequestParams::SessionPing(()).irn_metadata();
+    assert!(session.ping().await?);
+    assert!(
+        session
+            .publish_request_with_ttl::<bool>(RequestParams::SessionPing(()), 3600)
+            .await?
+    );
+    // below the relay minimum
+    assert!(
+        session
+            .publish_request_with_ttl::<bool>(RequestParams::SessionPing(()), 10)
+            .await?
+    );
+    let ttls: Vec<u64> = test
+        .relay
+        .publishes()
+        .iter()
+        .filter(|p| p.topic == session.topic() && p.tag == ping.tag)
+        .map(|p| u64::from(p.ttl_secs))
+        .collect();
+    assert_eq!(vec![ping.ttl, 3600, IRN_MIN_TTL], ttls);
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use {
+    monedero_domain::Pairing,
+    monedero_mesh::{init_tracing, Error, TransportError, RATE_LIMITED_RETRY_AFTER},
+    std::time::{Duration, Instant},
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_rate_limited() -> anyhow::Result<()> {
+    init_tracing();
+    let (relay, p) = mock_relay().await?;
+    let dapp = builder(&p).build().await?;
+    let wallet = builder(&p).retry_rate_limited(true).build().await?;
+    let pairing = Pairing::default();
+    dapp.set_pairing(pairing.clone()).await?;
+    wallet.set_pairing(pairing).await?;
+    assert!(timeout(Duration::from_secs(5), dapp.ping()).await??);
+
+    relay.rate_limit_publishes(1);
+    let result = dapp.ping().await;
+    assert!(
+        matches!(
+            result,
+            Err(Error::Transport(TransportError::RateLimited { retry_after }))
+                if retry_after == RATE_LIMITED_RETRY_AFTER
+        ),
+        "{result:?}"
+    );
+
+    relay.rate_limit_publishes(1);
+    let start = Instant::now();
+    assert!(timeout(Duration::from_secs(5), wallet.ping()).await??);
+    assert!(start.elapsed() >= RATE_LIMITED_RETRY_AFTER);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use {
+    async_trait::async_trait,
+    monedero_mesh::{init_tracing, RelayDisconnectReason, SocketEvent, SocketListener},
+    monedero_relay::CLOSE_AUTH_EXPIRED,
+    std::time::Duration,
+    tokio::{sync::mpsc, time::timeout},
+};
+
+mod test_utils;
+use test_utils::*;
+
+struct Events(mpsc::UnboundedSender<SocketEvent>);
+
+#[async_trait]
+impl SocketListener for Events {
+    async fn handle_socket_event(&self, event: SocketEvent) {
+        let _ = self.0.send(event);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_reconnect_auth_expired() -> anyhow::Result<()> {
+    init_tracing();
+    let (relay, p) = mock_relay().await?;
+    let mgr = builder(&p).build().await?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    mgr.register_socket_listener(Events(tx)).await;
+    let original = relay.auth_tokens();
+    assert!(!original.is_empty());
+
+    relay.close_all(CLOSE_AUTH_EXPIRED, "auth expired").await;
+    let reason = timeout(Duration::from_secs(5), async {
+        while let Some(event) = rx.recv().await {
+            if let SocketEvent::ForceDisconnect(reason) = event {
+                return Some(reason);
+            }
+        }
+        None
+    })
+    .await?;
+    assert_eq!(Some(RelayDisconnectReason::AuthExpired), reason);
+
+    let reconnected = timeout(Duration::from_secs(10), async {
+        loop {
+            let tokens = relay.auth_tokens();
+            if tokens.len() > original.len() {
+                return tokens;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await?;
+    let fresh = reconnected.last().expect("reconnect token");
+    assert!(!original.contains(fresh));
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType, Method, SolanaMethod},
+    monedero_mesh::{
+        rpc::{RequestParams, SessionRequestBuilder},
+        ClientSession,
+        Error,
+        NoopSessionHandler,
+        RejectingSessionHandler,
+        SdkErrors,
+    },
+    serde_json::json,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+async fn sign_message(
+    session: &ClientSession,
+) -> anyhow::Result<monedero_mesh::Result<serde_json::Value>> {
+    let request = SessionRequestBuilder::new(
+        ChainId::Solana(ChainType::Dev),
+        Method::Solana(SolanaMethod::SignMessage),
+    )
+    .params(json!({"message": "hi"}))
+    .build()?;
+    Ok(timeout(
+        Duration::from_secs(5),
+        session.publish_request(RequestParams::SessionRequest(request)),
+    )
+    .await?)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_rejecting_session_handler() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(
+            pairing.to_string(),
+            RejectingSessionHandler(SdkErrors::UserRejected),
+        )
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    let result = sign_message(&session).await?;
+    assert_matches!(
+        result,
+        Err(Error::RpcError(v)) if v["code"] == 5000 && v["message"] == "User rejected."
+    );
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::namespaces::{ChainId, ChainType, SolanaMethod},
+    monedero_mesh::{
+        rpc::{ErrorParams, SessionRequestRequest},
+        Error,
+        NoopSessionHandler,
+        SdkErrors,
+        SessionEventHandler,
+        SessionHandler,
+        WalletRequestResponse,
+    },
+    serde_json::json,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Rejects every request, saying why
+struct ExplainingWallet;
+
+#[async_trait]
+impl SessionEventHandler for ExplainingWallet {}
+
+#[async_trait]
+impl SessionHandler for ExplainingWallet {
+    async fn request(&self, _request: SessionRequestRequest) -> WalletRequestResponse {
+        WalletRequestResponse::ErrorWithMessage(
+            SdkErrors::UserRejected,
+            String::from("message signing is disabled for this account"),
+        )
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_rejection_message() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), ExplainingWallet)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let Err(Error::RpcError(v)) = timeout(
+        Duration::from_secs(5),
+        session.solana_request(SolanaMethod::SignMessage, json!({"message": "hi"})),
+    )
+    .await?
+    else {
+        anyhow::bail!("request was not rejected");
+    };
+    let error: ErrorParams = serde_json::from_value(v)?;
+    assert_eq!(Some(5000), error.code);
+    assert_eq!(
+        "message signing is disabled for this account",
+        error.message
+    );
+    Ok(())
+}
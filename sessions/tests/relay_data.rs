@@ -0,0 +1,60 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, RelayProtocol, RequestParams},
+        Dapp,
+        NoopSessionHandler,
+        Wallet,
+    },
+    std::time::Duration,
+    tokio::{sync::mpsc, time::timeout},
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_relay_data_echoed() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p)
+        .relay_data(Some(String::from("route-via-eu")))
+        .build()
+        .await?;
+    let (settle_tx, mut settle_rx) = mpsc::unbounded_channel();
+    dapp_manager.register_interceptor("wc_sessionSettle", move |req| {
+        if let RequestParams::SessionSettle(settle) = req.payload.params {
+            let _ = settle_tx.send(settle.relay);
+        }
+        None
+    });
+    let dapp = Dapp::new(dapp_manager, Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let (propose_tx, mut propose_rx) = mpsc::unbounded_channel();
+    wallet_manager.register_interceptor("wc_sessionPropose", move |req| {
+        if let RequestParams::SessionPropose(proposal) = req.payload.params {
+            let _ = propose_tx.send(proposal.relays);
+        }
+        None
+    });
+    let wallet = Wallet::new(wallet_manager, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let uri = pairing.uri_parts();
+    assert_eq!(Some("route-via-eu"), uri.relay_data.as_deref());
+    wallet.pair(uri.to_string(), NoopSessionHandler).await?;
+    let _session = timeout(Duration::from_secs(5), rx).await??;
+
+    let expected = RelayProtocol {
+        data: Some(String::from("route-via-eu")),
+        ..RelayProtocol::default()
+    };
+    let offered = timeout(Duration::from_secs(5), propose_rx.recv()).await?;
+    assert_eq!(Some(vec![expected.clone()]), offered);
+    let settled = timeout(Duration::from_secs(5), settle_rx.recv()).await?;
+    assert_eq!(Some(expected), settled);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType, Method, NamespaceName, SolanaMethod},
+    monedero_mesh::NoopSessionHandler,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_session_capabilities() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let solana = ChainId::Solana(ChainType::Dev);
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, std::slice::from_ref(&solana))
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let capabilities = session.capabilities();
+    assert_eq!(session.topic(), capabilities.topic);
+    assert_eq!("mock wallet", capabilities.peer.name);
+    assert_eq!(
+        vec![&solana],
+        capabilities.chains.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(1, capabilities.methods.len());
+    let methods = &capabilities.methods[&NamespaceName::Solana];
+    for method in [
+        SolanaMethod::SignMessage,
+        SolanaMethod::SignTransaction,
+        SolanaMethod::SignAndSendTransaction,
+    ] {
+        assert!(methods.0.contains(&Method::Solana(method)));
+    }
+    assert!(capabilities.events.contains_key(&NamespaceName::Solana));
+
+    let json = serde_json::to_value(&capabilities)?;
+    assert_eq!("mock wallet", json["peer"]["name"]);
+    assert_eq!(serde_json::json!([solana.to_string()]), json["chains"]);
+    Ok(())
+}
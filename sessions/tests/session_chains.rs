@@ -0,0 +1,34 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::NoopSessionHandler,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_session_chains() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let sepolia = ChainId::EIP155(alloy_chains::Chain::sepolia());
+    let solana = ChainId::Solana(ChainType::Dev);
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[sepolia.clone(), solana.clone()])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let chains = session.chains();
+    assert_eq!(2, chains.len());
+    assert!(chains.contains(&sepolia));
+    assert!(chains.contains(&solana));
+    assert!(session.supports_chain(&sepolia));
+    assert!(session.supports_chain(&solana));
+    assert!(!session.supports_chain(&ChainId::EIP155(alloy_chains::Chain::mainnet())));
+    assert!(!session.supports_chain(&ChainId::Solana(ChainType::Main)));
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{Account, ChainId, NamespaceName},
+    monedero_mesh::{
+        rpc::{Event, RequestParams, SessionEventRequest},
+        NoopSessionHandler,
+        SessionEvent,
+    },
+    serde_json::json,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+const NEW_ACCOUNT: &str = "0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_accounts_changed_event() -> anyhow::Result<()> {
+    let test = init_test_components_with(|builder| builder.sync_namespaces(true)).await?;
+    let mut events = test.dapp_actors.session_events();
+    let chain = ChainId::EIP155(alloy_chains::Chain::sepolia());
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, std::slice::from_ref(&chain))
+        .await?;
+    let (_, wallet_rx) = test
+        .wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Settled(_)
+    );
+
+    let accepted: bool = wallet_session
+        .publish_request(RequestParams::SessionEvent(SessionEventRequest {
+            event: Event {
+                name: String::from("accountsChanged"),
+                data: json!([NEW_ACCOUNT]),
+            },
+            chain_id: chain.clone(),
+        }))
+        .await?;
+    assert!(accepted);
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Updated(topic, _) if topic == session.topic()
+    );
+
+    let expected = Account {
+        address: String::from(NEW_ACCOUNT),
+        chain,
+    };
+    let namespaces = session.namespaces();
+    let accounts = &namespaces
+        .get(&NamespaceName::EIP155)
+        .ok_or_else(|| anyhow::format_err!("no eip155 namespace"))?
+        .accounts;
+    assert_eq!(1, accounts.len());
+    assert!(accounts.contains(&expected));
+    yield_ms(500).await;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{Metadata, RequestParams, SessionExtendRequest, MAX_SESSION_EXTEND_SECS},
+        Dapp,
+        Error,
+        NoopSessionHandler,
+        SessionEvent,
+        Wallet,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_wallet_extends_session() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p).build().await?;
+    let ciphers = dapp_manager.ciphers();
+    let mut events = dapp_manager.session_events();
+    let dapp = Dapp::new(dapp_manager, Metadata::default()).await?;
+    let wallet = Wallet::new(builder(&p).build().await?, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let (_, wallet_rx) = wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Settled(_)
+    );
+    let topic = session.topic();
+
+    let now = chrono::Utc::now().timestamp();
+    let expiry = u64::try_from(now)? + 3 * 24 * 60 * 60;
+    let extended: bool = wallet_session
+        .publish_request(RequestParams::SessionExtend(SessionExtendRequest {
+            expiry,
+        }))
+        .await?;
+    assert!(extended);
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Extended(t, e) if t == topic && e == i64::try_from(expiry)?
+    );
+    assert!(!ciphers.is_expired(topic.clone())?);
+    let stored = ciphers.settlement(&topic)?.map(|s| s.expiry);
+    assert_eq!(Some(i64::try_from(expiry)?), stored);
+
+    // out of bounds
+    let too_far = u64::try_from(now)? + MAX_SESSION_EXTEND_SECS + 3600;
+    let result: monedero_mesh::Result<bool> = wallet_session
+        .publish_request(RequestParams::SessionExtend(SessionExtendRequest {
+            expiry: too_far,
+        }))
+        .await;
+    assert_matches!(result, Err(Error::RpcError(_)));
+    assert_eq!(stored, ciphers.settlement(&topic)?.map(|s| s.expiry));
+    Ok(())
+}
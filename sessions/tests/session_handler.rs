@@ -0,0 +1,76 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::namespaces::{ChainId, ChainType, Method, SolanaMethod},
+    monedero_mesh::{
+        rpc::{RequestParams, SessionRequestBuilder, SessionRequestRequest},
+        ClientSession,
+        Error,
+        NoopSessionHandler,
+        RejectingSessionHandler,
+        SdkErrors,
+        SessionEventHandler,
+        SessionHandler,
+        WalletRequestResponse,
+    },
+    serde_json::json,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Signs every request with a fixed signature
+struct SigningWallet;
+
+#[async_trait]
+impl SessionEventHandler for SigningWallet {}
+
+#[async_trait]
+impl SessionHandler for SigningWallet {
+    async fn request(&self, _request: SessionRequestRequest) -> WalletRequestResponse {
+        WalletRequestResponse::Success(json!({ "signature": "sig" }))
+    }
+}
+
+async fn sign_message(session: &ClientSession) -> anyhow::Result<monedero_mesh::Result<String>> {
+    let request = SessionRequestBuilder::new(
+        ChainId::Solana(ChainType::Dev),
+        Method::Solana(SolanaMethod::SignMessage),
+    )
+    .params(json!({"message": "hi", "pubkey": "pk"}))
+    .build()?;
+    let response: monedero_mesh::Result<serde_json::Value> = timeout(
+        Duration::from_secs(5),
+        session.publish_request(RequestParams::SessionRequest(request)),
+    )
+    .await?;
+    Ok(response.map(|v| v["signature"].as_str().unwrap_or_default().to_string()))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_set_handler() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let (_, wallet_rx) = test
+        .wallet
+        .pair(
+            pairing.to_string(),
+            RejectingSessionHandler(SdkErrors::UserRejected),
+        )
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+    assert!(matches!(
+        sign_message(&session).await?,
+        Err(Error::RpcError(_))
+    ));
+
+    // the clone kept by the wallet's session actor sees the new handler
+    wallet_session.set_handler(SigningWallet).await;
+    assert_eq!("sig", sign_message(&session).await??);
+    Ok(())
+}
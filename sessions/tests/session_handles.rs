@@ -0,0 +1,43 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler, Wallet},
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_session_handles() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p).build().await?;
+    let dapp = Dapp::new(dapp_manager.clone(), Metadata::default()).await?;
+    let wallet = Wallet::new(builder(&p).build().await?, WalletProposal {}).await?;
+    assert!(dapp_manager.debug_sessions().await?.is_empty());
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    yield_ms(100).await;
+    let base = session.handle_count();
+
+    let first = session.clone();
+    let second = session.clone();
+    assert_eq!(base + 2, session.handle_count());
+    assert_eq!(base + 2, first.handle_count());
+
+    let debug = dapp_manager.debug_sessions().await?;
+    assert_eq!(1, debug.len());
+    assert_eq!(session.topic(), debug[0].topic);
+    assert_eq!(base + 2, debug[0].handle_count);
+    assert!(debug[0].time_remaining > chrono::Duration::zero());
+
+    drop(first);
+    drop(second);
+    assert_eq!(base, session.handle_count());
+    Ok(())
+}
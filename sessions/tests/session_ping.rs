@@ -0,0 +1,63 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::namespaces::{ChainId, ChainType},
+    monedero_mesh::{
+        rpc::{Event, SessionRequestRequest},
+        NoopSessionHandler,
+        SdkErrors,
+        SessionEventHandler,
+        SessionHandler,
+        WalletRequestResponse,
+    },
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Counts every call, rejecting requests
+#[derive(Clone, Default)]
+struct CountingHandler(Arc<AtomicUsize>);
+
+#[async_trait]
+impl SessionEventHandler for CountingHandler {
+    async fn event(&self, _event: Event) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl SessionHandler for CountingHandler {
+    async fn request(&self, _request: SessionRequestRequest) -> WalletRequestResponse {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        WalletRequestResponse::Error(SdkErrors::UserRejected)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_session_ping_auto_response() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let handler = CountingHandler::default();
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), handler.clone())
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    for _ in 0..3 {
+        assert!(timeout(Duration::from_secs(5), session.ping()).await??);
+    }
+    // the wallet's handler is never asked
+    assert_eq!(0, handler.0.load(Ordering::SeqCst));
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::{
+        namespaces::{ChainId, ChainType},
+        SessionSettled,
+    },
+    monedero_mesh::{init_tracing, rpc::Metadata, Dapp, NoopSessionHandler, SessionEvent, Wallet},
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_session_reaper() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p)
+        .session_reaper_interval(Some(Duration::from_millis(200)))
+        .build()
+        .await?;
+    let ciphers = dapp_manager.ciphers();
+    let mut events = dapp_manager.session_events();
+    let dapp = Dapp::new(dapp_manager, Metadata::default()).await?;
+    let wallet = Wallet::new(builder(&p).build().await?, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Settled(_)
+    );
+    let topic = session.topic();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(
+        ciphers.settlement(&topic)?.is_some(),
+        "reaped a live session"
+    );
+
+    // the session runs out while the process is up
+    let settled = ciphers
+        .settlement(&topic)?
+        .ok_or_else(|| anyhow::format_err!("no settlement"))?;
+    ciphers.set_settlement(&topic, SessionSettled {
+        expiry: chrono::Utc::now().timestamp() - 1,
+        ..settled
+    })?;
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Deleted(t) if t == topic
+    );
+    assert!(ciphers.settlement(&topic)?.is_none());
+    assert!(!ciphers.is_subscribed(&topic));
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{Account, ChainId, ChainType, NamespaceName},
+    monedero_mesh::{
+        init_tracing,
+        rpc::Metadata,
+        Dapp,
+        Error,
+        NoopSessionHandler,
+        SessionError,
+        SessionEvent,
+        Wallet,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+const OTHER_SOLANA_ACCOUNT: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_wallet_updates_session() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let dapp_manager = builder(&p).build().await?;
+    let mut events = dapp_manager.session_events();
+    let dapp = Dapp::new(dapp_manager, Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let wallet_ciphers = wallet_manager.ciphers();
+    let wallet = Wallet::new(wallet_manager, WalletProposal {}).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let (_, wallet_rx) = wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(5), wallet_rx).await??;
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Settled(_)
+    );
+    let topic = session.topic();
+
+    let mut namespaces = wallet_session.namespaces();
+    let added = Account {
+        address: String::from(OTHER_SOLANA_ACCOUNT),
+        chain: ChainId::Solana(ChainType::Dev),
+    };
+    namespaces
+        .get_mut(&NamespaceName::Solana)
+        .ok_or_else(|| anyhow::format_err!("no solana namespace"))?
+        .accounts
+        .0
+        .insert(added.clone());
+    wallet.update_session(&topic, namespaces.clone()).await?;
+    assert_matches!(
+        timeout(Duration::from_secs(5), events.recv()).await??,
+        SessionEvent::Updated(t, ns) if t == topic && ns == namespaces
+    );
+    let stored = wallet_ciphers.settlement(&topic)?.map(|s| s.namespaces);
+    assert_eq!(Some(&namespaces), stored.as_ref());
+
+    // dropping the required solana namespace
+    let mut shrunk = namespaces.clone();
+    shrunk.remove(&NamespaceName::Solana);
+    assert_matches!(
+        wallet.update_session(&topic, shrunk).await,
+        Err(Error::Session(SessionError::UpdateBelowRequired(t))) if t == topic
+    );
+    assert_eq!(
+        stored,
+        wallet_ciphers.settlement(&topic)?.map(|s| s.namespaces)
+    );
+    Ok(())
+}
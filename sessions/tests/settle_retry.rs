@@ -0,0 +1,70 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::namespaces::{ChainId, ChainType, Namespaces},
+    monedero_mesh::{
+        init_tracing,
+        rpc::{
+            Metadata,
+            RelayProtocolMetadata,
+            RequestParams,
+            SessionProposeRequest,
+            SessionSettleRequest,
+        },
+        Dapp,
+        MockRelay,
+        NoopSessionHandler,
+        ProposalContext,
+        Result,
+        Wallet,
+        WalletSettlementHandler,
+    },
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Has the relay reject the next publish, the settlement, so only a retry
+/// gets it through
+struct FlakySettlement(MockRelay);
+
+#[async_trait]
+impl WalletSettlementHandler for FlakySettlement {
+    async fn settlement(
+        &self,
+        proposal: SessionProposeRequest,
+        context: ProposalContext,
+    ) -> Result<Namespaces> {
+        let namespaces = WalletProposal {}.settlement(proposal, context).await?;
+        // let the proposal response go out first
+        yield_ms(100).await;
+        self.0.rate_limit_publishes(1);
+        Ok(namespaces)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_settle_retry() -> anyhow::Result<()> {
+    init_tracing();
+    let (relay, p) = mock_relay().await?;
+    let dapp = Dapp::new(builder(&p).build().await?, Metadata::default()).await?;
+    let wallet_manager = builder(&p).build().await?;
+    let wallet = Wallet::new(wallet_manager, FlakySettlement(relay.clone())).await?;
+
+    let (pairing, rx, _) = dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    let (_, wallet_rx) = wallet.pair(pairing.to_string(), NoopSessionHandler).await?;
+    let dapp_session = timeout(Duration::from_secs(10), rx).await??;
+    let wallet_session = timeout(Duration::from_secs(10), wallet_rx).await??;
+    assert_eq!(dapp_session.topic(), wallet_session.topic());
+    let settle = RequestParams::SessionSettle(SessionSettleRequest::default()).irn_metadata();
+    let settles = relay
+        .publishes()
+        .iter()
+        .filter(|p| p.topic == dapp_session.topic() && p.tag == settle.tag)
+        .count();
+    assert!(settles >= 2, "{settles} settlement publishes");
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use {
+    monedero_domain::namespaces::{ChainId, ChainType, SolanaMethod},
+    monedero_mesh::NoopSessionHandler,
+    serde_json::json,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_solana_request() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let result = timeout(
+        Duration::from_secs(5),
+        session.solana_request(SolanaMethod::SignMessage, json!({"message": "hi"})),
+    )
+    .await?;
+    assert!(result.is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use {
+    monedero_domain::{SubscriptionId, Topic},
+    monedero_mesh::init_tracing,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_subscribe_ack() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let mgr = builder(&p).build().await?;
+
+    // the mock relay acks with an id derived from the topic
+    let topic = Topic::generate();
+    let id = mgr.subscribe(topic.clone()).await?;
+    assert_eq!(SubscriptionId::from(topic.as_ref()), id);
+
+    let topics = vec![Topic::generate(), Topic::generate()];
+    let ids = mgr.batch_subscribe(topics.clone()).await?;
+    let expected: Vec<SubscriptionId> = topics
+        .iter()
+        .map(|t| SubscriptionId::from(t.as_ref()))
+        .collect();
+    assert_eq!(expected, ids);
+    Ok(())
+}
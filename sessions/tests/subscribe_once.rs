@@ -0,0 +1,108 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::{SubscriptionId, Topic},
+    monedero_mesh::{
+        init_tracing,
+        ClientError,
+        ConnectionHandler,
+        ConnectionOptions,
+        SharedTransport,
+        Transport,
+        TransportFactory,
+    },
+    monedero_relay::WebSocketTransport,
+    std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+};
+
+mod test_utils;
+use test_utils::*;
+
+/// Relay client counting the topics it is asked to subscribe
+struct Counting {
+    inner: SharedTransport,
+    subscribes: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Transport for Counting {
+    async fn connect(&self, opts: &ConnectionOptions) -> Result<(), ClientError> {
+        self.inner.connect(opts).await
+    }
+
+    async fn disconnect(&self) -> Result<(), ClientError> {
+        self.inner.disconnect().await
+    }
+
+    async fn publish(
+        &self,
+        topic: Topic,
+        message: Arc<str>,
+        tag: u32,
+        ttl: Duration,
+        prompt: bool,
+    ) -> Result<(), ClientError> {
+        self.inner.publish(topic, message, tag, ttl, prompt).await
+    }
+
+    async fn subscribe(&self, topic: Topic) -> Result<SubscriptionId, ClientError> {
+        self.subscribes.fetch_add(1, Ordering::SeqCst);
+        self.inner.subscribe(topic).await
+    }
+
+    async fn batch_subscribe(
+        &self,
+        topics: Vec<Topic>,
+    ) -> Result<Vec<SubscriptionId>, ClientError> {
+        self.subscribes.fetch_add(topics.len(), Ordering::SeqCst);
+        self.inner.batch_subscribe(topics).await
+    }
+
+    async fn unsubscribe(&self, topic: Topic) -> Result<(), ClientError> {
+        self.inner.unsubscribe(topic).await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_subscribe_once() -> anyhow::Result<()> {
+    init_tracing();
+    let (_relay, p) = mock_relay().await?;
+    let subscribes = Arc::new(AtomicUsize::new(0));
+    let counter = subscribes.clone();
+    let manager = builder(&p)
+        .transport(
+            move |handler: Box<dyn ConnectionHandler>| -> SharedTransport {
+                Arc::new(Counting {
+                    inner: WebSocketTransport.create(handler),
+                    subscribes: counter.clone(),
+                })
+            },
+        )
+        .build()
+        .await?;
+
+    let topic = Topic::generate();
+    let first = manager.subscribe(topic.clone()).await?;
+    let second = manager.subscribe(topic.clone()).await?;
+    assert_eq!(first, second);
+    assert_eq!(1, subscribes.load(Ordering::SeqCst));
+
+    let other = Topic::generate();
+    let ids = manager
+        .batch_subscribe(vec![topic.clone(), other.clone()])
+        .await?;
+    assert_eq!(first, ids[0]);
+    assert_eq!(2, subscribes.load(Ordering::SeqCst));
+
+    // a new connection starts without subscriptions
+    manager.disconnect_socket().await?;
+    manager.open_socket().await?;
+    manager.subscribe(topic).await?;
+    assert_eq!(3, subscribes.load(Ordering::SeqCst));
+    Ok(())
+}
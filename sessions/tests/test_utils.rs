@@ -6,14 +6,10 @@ use {
             Accounts,
             ChainId,
             Chains,
-            EipMethod,
             Events,
-            Method,
-            Methods,
             Namespace,
             NamespaceName,
             Namespaces,
-            SolanaMethod,
         },
         ProjectId,
     },
@@ -33,6 +29,7 @@ use {
         Dapp,
         KvStorage,
         MockRelay,
+        ProposalContext,
         ReownBuilder,
         Result,
         SdkErrors,
@@ -43,6 +40,7 @@ use {
         collections::{BTreeMap, BTreeSet},
         time::Duration,
     },
+    tokio::sync::mpsc,
 };
 
 //#[allow(dead_code)]
@@ -57,6 +55,25 @@ pub struct TestStuff {
     pub relay: MockRelay,
 }
 
+#[allow(dead_code)]
+pub fn project_id() -> ProjectId {
+    ProjectId::from("987f2292c12194ae69ddb6c52ceb1d62")
+}
+
+/// Start the mock relay, returning it with the project id to connect with
+#[allow(dead_code)]
+pub async fn mock_relay() -> anyhow::Result<(MockRelay, ProjectId)> {
+    Ok((MockRelay::start().await?, project_id()))
+}
+
+/// A builder for the mock relay, with in-memory storage
+#[allow(dead_code)]
+pub fn builder(p: &ProjectId) -> ReownBuilder {
+    ReownBuilder::new(p.clone())
+        .connect_opts(mock_connection_opts(p))
+        .store(KvStorage::mem())
+}
+
 pub async fn yield_ms(ms: u64) {
     tokio::time::sleep(Duration::from_millis(ms)).await;
 }
@@ -64,30 +81,33 @@ pub async fn yield_ms(ms: u64) {
 pub struct WalletProposal {}
 
 pub const SUPPORTED_ACCOUNT: &str = "0xBA5BA3955463ADcc7aa3E33bbdfb8A68e0933dD8";
+pub const SUPPORTED_SOLANA_ACCOUNT: &str = "6LmSRCiu3z6NCSpF19oz1pHXkYkN4jWbj9K1nVELpDkT";
 
 #[async_trait]
 impl WalletSettlementHandler for WalletProposal {
-    async fn settlement(&self, proposal: SessionProposeRequest) -> Result<Namespaces> {
+    async fn settlement(
+        &self,
+        proposal: SessionProposeRequest,
+        _context: ProposalContext,
+    ) -> Result<Namespaces> {
         let mut settled: Namespaces = Namespaces(BTreeMap::new());
         for (name, namespace) in proposal.required_namespaces.iter() {
             let accounts: BTreeSet<Account> = namespace
                 .chains
                 .iter()
                 .map(|c| Account {
-                    address: String::from(SUPPORTED_ACCOUNT),
+                    address: String::from(match c {
+                        ChainId::Solana(_) => SUPPORTED_SOLANA_ACCOUNT,
+                        _ => SUPPORTED_ACCOUNT,
+                    }),
                     chain: c.clone(),
                 })
                 .collect();
 
-            let methods = match name {
-                NamespaceName::EIP155 => EipMethod::defaults(),
-                NamespaceName::Solana => SolanaMethod::defaults(),
-                NamespaceName::Other(_) => BTreeSet::from([Method::Other("unknown".to_owned())]),
-            };
             settled.insert(name.clone(), Namespace {
                 accounts: Accounts(accounts),
                 chains: Chains(namespace.chains.iter().cloned().collect()),
-                methods: Methods(methods),
+                methods: name.supported_methods(),
                 events: Events::default(),
             });
         }
@@ -120,22 +140,36 @@ impl WalletSettlementHandler for WalletProposal {
     }
 }
 
+/// Rejects every proposal after handing it to the test
+#[allow(dead_code)]
+pub struct CaptureProposal(pub mpsc::UnboundedSender<SessionProposeRequest>);
+
+#[async_trait]
+impl WalletSettlementHandler for CaptureProposal {
+    async fn settlement(
+        &self,
+        proposal: SessionProposeRequest,
+        _context: ProposalContext,
+    ) -> Result<Namespaces> {
+        let _ = self.0.send(proposal);
+        Err(monedero_mesh::SessionError::ProposalRejected.into())
+    }
+}
+
+#[allow(dead_code)]
 pub async fn init_test_components() -> anyhow::Result<TestStuff> {
+    init_test_components_with(|builder| builder).await
+}
+
+/// Like [`init_test_components`] with extra configuration of the dapp's
+/// builder
+pub async fn init_test_components_with(
+    configure_dapp: fn(ReownBuilder) -> ReownBuilder,
+) -> anyhow::Result<TestStuff> {
     init_tracing();
-    let p = ProjectId::from("987f2292c12194ae69ddb6c52ceb1d62");
-    let dapp_opts = mock_connection_opts(&p);
-    let wallet_opts = mock_connection_opts(&p);
-    let relay = monedero_mesh::MockRelay::start().await?;
-    let dapp_manager = ReownBuilder::new(p.clone())
-        .connect_opts(dapp_opts)
-        .store(KvStorage::mem())
-        .build()
-        .await?;
-    let wallet_manager = ReownBuilder::new(p)
-        .connect_opts(wallet_opts)
-        .store(KvStorage::mem())
-        .build()
-        .await?;
+    let (relay, p) = mock_relay().await?;
+    let dapp_manager = configure_dapp(builder(&p)).build().await?;
+    let wallet_manager = builder(&p).build().await?;
     let dapp_actors = dapp_manager.actors();
     let wallet_actors = wallet_manager.actors();
     let md = Metadata {
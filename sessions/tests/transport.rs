@@ -0,0 +1,144 @@
+use {
+    async_trait::async_trait,
+    monedero_domain::{Message, Pairing, SubscriptionId, Topic},
+    monedero_mesh::{
+        init_tracing,
+        ClientError,
+        ConnectionHandler,
+        ConnectionOptions,
+        SharedTransport,
+        Transport,
+    },
+    std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+type Handler = Arc<Mutex<Box<dyn ConnectionHandler>>>;
+
+struct Peer {
+    handler: Handler,
+    topics: Vec<Topic>,
+}
+
+/// In-process relay: delivers each publish to the other peers subscribed to
+/// the topic
+#[derive(Clone, Default)]
+struct Hub {
+    peers: Arc<Mutex<Vec<Peer>>>,
+}
+
+impl Hub {
+    fn join(&self, handler: Box<dyn ConnectionHandler>) -> SharedTransport {
+        let mut peers = self.peers.lock().unwrap();
+        peers.push(Peer {
+            handler: Arc::new(Mutex::new(handler)),
+            topics: Vec::new(),
+        });
+        Arc::new(MemoryTransport {
+            hub: self.clone(),
+            id: peers.len() - 1,
+        })
+    }
+}
+
+struct MemoryTransport {
+    hub: Hub,
+    id: usize,
+}
+
+impl MemoryTransport {
+    fn handler(&self) -> Handler {
+        self.hub.peers.lock().unwrap()[self.id].handler.clone()
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn connect(&self, _opts: &ConnectionOptions) -> Result<(), ClientError> {
+        self.handler().lock().unwrap().connected();
+        Ok(())
+    }
+
+    async fn disconnect(&self) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    async fn publish(
+        &self,
+        topic: Topic,
+        message: Arc<str>,
+        tag: u32,
+        _ttl: Duration,
+        _prompt: bool,
+    ) -> Result<(), ClientError> {
+        let receivers: Vec<Handler> = self
+            .hub
+            .peers
+            .lock()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(id, peer)| *id != self.id && peer.topics.contains(&topic))
+            .map(|(_, peer)| peer.handler.clone())
+            .collect();
+        for handler in receivers {
+            let message = Message {
+                topic: topic.clone(),
+                message: message.clone(),
+                tag,
+                ..Default::default()
+            };
+            handler.lock().unwrap().message_received(message);
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&self, topic: Topic) -> Result<SubscriptionId, ClientError> {
+        self.hub.peers.lock().unwrap()[self.id].topics.push(topic);
+        Ok(SubscriptionId::generate())
+    }
+
+    async fn batch_subscribe(
+        &self,
+        topics: Vec<Topic>,
+    ) -> Result<Vec<SubscriptionId>, ClientError> {
+        let mut ids = Vec::with_capacity(topics.len());
+        for topic in topics {
+            ids.push(self.subscribe(topic).await?);
+        }
+        Ok(ids)
+    }
+
+    async fn unsubscribe(&self, topic: Topic) -> Result<(), ClientError> {
+        self.hub.peers.lock().unwrap()[self.id]
+            .topics
+            .retain(|t| *t != topic);
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_memory_transport() -> anyhow::Result<()> {
+    init_tracing();
+    let p = project_id();
+    let hub = Hub::default();
+    let on_hub = || {
+        let hub = hub.clone();
+        builder(&p).transport(move |handler| hub.join(handler))
+    };
+    let dapp = on_hub().build().await?;
+    let wallet = on_hub().build().await?;
+    assert!(dapp.health().relay_connected);
+
+    let pairing = Pairing::default();
+    dapp.set_pairing(pairing.clone()).await?;
+    wallet.set_pairing(pairing).await?;
+    assert!(timeout(Duration::from_secs(5), dapp.ping()).await??);
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use {
+    assert_matches::assert_matches,
+    monedero_domain::namespaces::{ChainId, ChainType, Method, SolanaMethod},
+    monedero_mesh::{
+        rpc::{RequestParams, SessionRequestBuilder},
+        Error,
+        NoopSessionHandler,
+    },
+    serde_json::json,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_unauthorized_method() -> anyhow::Result<()> {
+    let test = init_test_components().await?;
+    let (pairing, rx, _) = test
+        .dapp
+        .propose(NoopSessionHandler, &[ChainId::Solana(ChainType::Dev)])
+        .await?;
+    test.wallet
+        .pair(pairing.to_string(), NoopSessionHandler)
+        .await?;
+    let session = timeout(Duration::from_secs(5), rx).await??;
+
+    let granted = SessionRequestBuilder::new(
+        ChainId::Solana(ChainType::Dev),
+        Method::Solana(SolanaMethod::SignMessage),
+    )
+    .params(json!({"message": "hi"}))
+    .build()?;
+    let result = timeout(
+        Duration::from_secs(5),
+        session.publish_request::<serde_json::Value>(RequestParams::SessionRequest(granted)),
+    )
+    .await?;
+    assert!(result.is_ok());
+
+    let outside = SessionRequestBuilder::new(
+        ChainId::Solana(ChainType::Dev),
+        Method::Solana(SolanaMethod::Other(String::from("solana_signIn"))),
+    )
+    .params(json!({}))
+    .build()?;
+    let result = timeout(
+        Duration::from_secs(5),
+        session.publish_request::<serde_json::Value>(RequestParams::SessionRequest(outside)),
+    )
+    .await?;
+    assert_matches!(result, Err(Error::RpcError(v)) if v["code"] == 3001);
+    Ok(())
+}
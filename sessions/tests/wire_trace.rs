@@ -0,0 +1,37 @@
+use {
+    monedero_domain::Pairing,
+    monedero_mesh::CapturedLogs,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+mod test_utils;
+use test_utils::*;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn test_published_request_traced() -> anyhow::Result<()> {
+    // global, the publish is traced on the transport actor's thread
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_writer(move || writer.clone())
+            .finish(),
+    )?;
+    let (_relay, p) = mock_relay().await?;
+    let dapp = builder(&p).build().await?;
+    let wallet = builder(&p).build().await?;
+    let pairing = Pairing::default();
+    dapp.set_pairing(pairing.clone()).await?;
+    wallet.set_pairing(pairing.clone()).await?;
+    assert!(timeout(Duration::from_secs(5), dapp.ping()).await??);
+
+    let logs = logs.contents();
+    let traced = logs
+        .lines()
+        .find(|l| l.contains("outbound-request") && l.contains(&pairing.topic.to_string()));
+    assert!(traced.is_some(), "{logs}");
+    assert!(logs.contains("\"method\": \"wc_pairingPing\""), "{logs}");
+    Ok(())
+}